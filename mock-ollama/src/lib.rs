@@ -0,0 +1,587 @@
+//! A scriptable stand-in for the parts of the Ollama HTTP API the app talks to.
+//!
+//! Everything the server says is driven by a [`Script`]: which models exist, what the
+//! assistant replies, how slowly it streams and which endpoints should fail. Use
+//! [`spawn`] from tests, or the `mock-ollama` binary for demos.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tower_http::cors::CorsLayer;
+
+const TIMESTAMP: &str = "2024-01-01T00:00:00.000000Z";
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct Script {
+    /// Models reported by `/api/tags`. Pulling a model adds it here.
+    pub models: Vec<String>,
+    /// Assistant replies, handed out in turn. When empty the last user message is echoed.
+    /// `<think>` tags are passed through untouched.
+    pub replies: Vec<String>,
+    /// Reasoning streamed in the `thinking` field when a request sets `think: true`.
+    pub thinking: Option<String>,
+    /// Words per streamed chunk.
+    pub chunk_words: usize,
+    /// Delay before every response.
+    pub latency_ms: u64,
+    /// Delay between streamed chunks.
+    pub token_delay_ms: u64,
+    pub embedding_dims: usize,
+    /// Failures to inject, keyed by endpoint path such as `/api/chat`.
+    pub errors: HashMap<String, ErrorRule>,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self {
+            models: vec![
+                "mock-llm:latest".to_string(),
+                "mock-embed:latest".to_string(),
+            ],
+            replies: Vec::new(),
+            thinking: None,
+            chunk_words: 1,
+            latency_ms: 0,
+            token_delay_ms: 20,
+            embedding_dims: 8,
+            errors: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ErrorRule {
+    pub status: u16,
+    #[serde(default)]
+    pub message: String,
+    /// Fail every n-th request to the endpoint. Every request fails when unset.
+    #[serde(default)]
+    pub every: Option<usize>,
+    /// On streaming requests, answer 200 and send the error halfway through the stream.
+    #[serde(default)]
+    pub mid_stream: bool,
+}
+
+struct MockState {
+    script: Script,
+    models: Mutex<Vec<String>>,
+    request_counts: Mutex<HashMap<String, usize>>,
+    next_reply: AtomicUsize,
+}
+
+type Shared = Arc<MockState>;
+
+impl MockState {
+    async fn delay(&self) {
+        if self.script.latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.script.latency_ms)).await;
+        }
+    }
+
+    /// Counts a request to `path` and returns the error rule if this one should fail.
+    fn injected_error(&self, path: &str) -> Option<ErrorRule> {
+        let rule = self.script.errors.get(path)?;
+        let mut counts = self.request_counts.lock().unwrap();
+        let count = counts.entry(path.to_string()).or_insert(0);
+        *count += 1;
+        match rule.every {
+            Some(n) if n > 0 && !count.is_multiple_of(n) => None,
+            _ => Some(rule.clone()),
+        }
+    }
+
+    fn has_model(&self, name: &str) -> bool {
+        self.models.lock().unwrap().iter().any(|m| m == name)
+    }
+
+    fn reply_for(&self, prompt: &str) -> String {
+        if self.script.replies.is_empty() {
+            return format!("You said: {}", prompt);
+        }
+        let i = self.next_reply.fetch_add(1, Ordering::Relaxed);
+        self.script.replies[i % self.script.replies.len()].clone()
+    }
+
+    /// Splits `text` into the chunks a streaming response would send.
+    fn chunks(&self, text: &str) -> Vec<String> {
+        let words: Vec<&str> = text.split_inclusive(' ').collect();
+        words
+            .chunks(self.script.chunk_words.max(1))
+            .map(|c| c.concat())
+            .collect()
+    }
+}
+
+fn error_response(status: u16, message: &str) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+fn model_not_found(model: &str) -> Response {
+    error_response(
+        404,
+        &format!("model \"{}\" not found, try pulling it first", model),
+    )
+}
+
+/// Streams `lines` as newline-delimited JSON, pausing between lines like a real model.
+/// With `fail_with`, the stream is cut in half and ends with an error line instead.
+fn ndjson(state: &MockState, mut lines: Vec<Value>, fail_with: Option<ErrorRule>) -> Response {
+    if let Some(rule) = fail_with {
+        lines.truncate(lines.len() / 2);
+        lines.push(json!({ "error": rule.message }));
+    }
+
+    let delay = Duration::from_millis(state.script.token_delay_ms);
+    let (tx, rx) = mpsc::channel::<Result<String, Infallible>>(16);
+    tokio::spawn(async move {
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 && !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            if tx.send(Ok(format!("{}\n", line))).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(ReceiverStream::new(rx)))
+        .unwrap()
+}
+
+/// Applies latency and error injection shared by every endpoint. Returns the early
+/// response for a failing request, or the rule to apply mid-stream.
+async fn prelude(
+    state: &MockState,
+    path: &str,
+    stream: bool,
+) -> Result<Option<ErrorRule>, Response> {
+    state.delay().await;
+    match state.injected_error(path) {
+        Some(rule) if rule.mid_stream && stream => Ok(Some(rule)),
+        Some(rule) => Err(error_response(rule.status, &rule.message)),
+        None => Ok(None),
+    }
+}
+
+/// Parses a JSON request body. Like Ollama, this ignores the `Content-Type` header.
+fn parse_body(body: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice(body).map_err(|e| format!("invalid JSON: {}", e))
+}
+
+fn wants_stream(req: &Value) -> bool {
+    req["stream"].as_bool().unwrap_or(true)
+}
+
+fn stats(eval_count: usize) -> Value {
+    json!({
+        "total_duration": 1_500_000_000u64,
+        "load_duration": 10_000_000u64,
+        "prompt_eval_count": 12,
+        "prompt_eval_duration": 100_000_000u64,
+        "eval_count": eval_count,
+        "eval_duration": 1_200_000_000u64,
+    })
+}
+
+fn merge(mut base: Value, extra: Value) -> Value {
+    if let (Some(base), Value::Object(extra)) = (base.as_object_mut(), extra) {
+        base.extend(extra);
+    }
+    base
+}
+
+async fn tags(State(state): State<Shared>) -> Response {
+    if let Err(resp) = prelude(&state, "/api/tags", false).await {
+        return resp;
+    }
+    let models: Vec<Value> = state
+        .models
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|name| {
+            json!({
+                "name": name,
+                "model": name,
+                "modified_at": TIMESTAMP,
+                "size": 4_000_000_000u64,
+                "digest": "mock",
+                "details": { "format": "gguf", "family": "mock", "parameter_size": "7B" },
+            })
+        })
+        .collect();
+    Json(json!({ "models": models })).into_response()
+}
+
+async fn chat(State(state): State<Shared>, body: Bytes) -> Response {
+    let req = match parse_body(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(400, &e),
+    };
+    let stream = wants_stream(&req);
+    let fail_with = match prelude(&state, "/api/chat", stream).await {
+        Ok(rule) => rule,
+        Err(resp) => return resp,
+    };
+    let model = req["model"].as_str().unwrap_or_default().to_string();
+    if !state.has_model(&model) {
+        return model_not_found(&model);
+    }
+
+    let prompt = req["messages"]
+        .as_array()
+        .and_then(|msgs| msgs.iter().rev().find(|m| m["role"] == "user"))
+        .and_then(|m| m["content"].as_str())
+        .unwrap_or_default();
+    let reply = state.reply_for(prompt);
+    let thinking = state
+        .script
+        .thinking
+        .clone()
+        .filter(|_| req["think"].as_bool().unwrap_or(false));
+
+    let header = json!({ "model": model, "created_at": TIMESTAMP });
+    let content_chunks = state.chunks(&reply);
+    let eval_count = content_chunks.len();
+
+    if !stream {
+        let mut message = json!({ "role": "assistant", "content": reply });
+        if let Some(thinking) = thinking {
+            message["thinking"] = json!(thinking);
+        }
+        let body = merge(
+            header,
+            merge(
+                json!({ "message": message, "done": true, "done_reason": "stop" }),
+                stats(eval_count),
+            ),
+        );
+        return Json(body).into_response();
+    }
+
+    let thinking_chunks = thinking.map(|t| state.chunks(&t)).unwrap_or_default();
+    let mut lines: Vec<Value> = thinking_chunks
+        .into_iter()
+        .map(|t| json!({ "role": "assistant", "content": "", "thinking": t }))
+        .chain(
+            content_chunks
+                .into_iter()
+                .map(|c| json!({ "role": "assistant", "content": c })),
+        )
+        .map(|message| merge(header.clone(), json!({ "message": message, "done": false })))
+        .collect();
+    lines.push(merge(
+        header,
+        merge(
+            json!({
+                "message": { "role": "assistant", "content": "" },
+                "done": true,
+                "done_reason": "stop",
+            }),
+            stats(eval_count),
+        ),
+    ));
+
+    ndjson(&state, lines, fail_with)
+}
+
+async fn generate(State(state): State<Shared>, body: Bytes) -> Response {
+    let req = match parse_body(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(400, &e),
+    };
+    let stream = wants_stream(&req);
+    let fail_with = match prelude(&state, "/api/generate", stream).await {
+        Ok(rule) => rule,
+        Err(resp) => return resp,
+    };
+    let model = req["model"].as_str().unwrap_or_default().to_string();
+    if !state.has_model(&model) {
+        return model_not_found(&model);
+    }
+
+    let reply = state.reply_for(req["prompt"].as_str().unwrap_or_default());
+    let chunks = state.chunks(&reply);
+    let eval_count = chunks.len();
+
+    // Fake token ids: the incoming context plus one id per chunk of prompt and reply.
+    let mut context: Vec<u64> = req["context"]
+        .as_array()
+        .map(|ids| ids.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default();
+    let start = context.len() as u64;
+    context.extend(
+        (start..).take(
+            state
+                .chunks(req["prompt"].as_str().unwrap_or_default())
+                .len()
+                + eval_count,
+        ),
+    );
+
+    let header = json!({ "model": model, "created_at": TIMESTAMP });
+    let last = merge(
+        header.clone(),
+        merge(
+            json!({ "response": "", "done": true, "done_reason": "stop", "context": context }),
+            stats(eval_count),
+        ),
+    );
+
+    if !stream {
+        return Json(merge(last, json!({ "response": reply }))).into_response();
+    }
+
+    let mut lines: Vec<Value> = chunks
+        .into_iter()
+        .map(|c| merge(header.clone(), json!({ "response": c, "done": false })))
+        .collect();
+    lines.push(last);
+
+    ndjson(&state, lines, fail_with)
+}
+
+async fn show(State(state): State<Shared>, body: Bytes) -> Response {
+    let req = match parse_body(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(400, &e),
+    };
+    if let Err(resp) = prelude(&state, "/api/show", false).await {
+        return resp;
+    }
+    let model = req["model"]
+        .as_str()
+        .or_else(|| req["name"].as_str())
+        .unwrap_or_default();
+    if !state.has_model(model) {
+        return model_not_found(model);
+    }
+
+    let mut capabilities = vec!["completion"];
+    if state.script.thinking.is_some() {
+        capabilities.push("thinking");
+    }
+    if model.contains("embed") {
+        capabilities = vec!["embedding"];
+    }
+
+    Json(json!({
+        "modelfile": format!(
+            "# Modelfile generated by mock-ollama\nFROM {}\nPARAMETER temperature 0.7\nSYSTEM \"\"\"You are a helpful mock assistant.\"\"\"\n",
+            model
+        ),
+        "parameters": "temperature                    0.7",
+        "template": "{{ .System }}\n{{ .Prompt }}",
+        "system": "You are a helpful mock assistant.",
+        "details": { "format": "gguf", "family": "mock", "parameter_size": "7B", "quantization_level": "Q4_0" },
+        "model_info": { "general.architecture": "mock" },
+        "capabilities": capabilities,
+        "modified_at": TIMESTAMP,
+    }))
+    .into_response()
+}
+
+async fn pull(State(state): State<Shared>, body: Bytes) -> Response {
+    let req = match parse_body(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(400, &e),
+    };
+    let stream = wants_stream(&req);
+    let fail_with = match prelude(&state, "/api/pull", stream).await {
+        Ok(rule) => rule,
+        Err(resp) => return resp,
+    };
+    let model = req["model"]
+        .as_str()
+        .or_else(|| req["name"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    if model.is_empty() {
+        return error_response(400, "model is required");
+    }
+
+    // A mid-stream failure means the pull never finished.
+    if fail_with.is_none() {
+        let mut models = state.models.lock().unwrap();
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+
+    if !stream {
+        return Json(json!({ "status": "success" })).into_response();
+    }
+
+    let total: u64 = 4_000_000_000;
+    let mut lines = vec![json!({ "status": "pulling manifest" })];
+    lines.extend((0..=5).map(|step| {
+        json!({
+            "status": "pulling mock",
+            "digest": "sha256:mock",
+            "total": total,
+            "completed": total / 5 * step,
+        })
+    }));
+    lines.push(json!({ "status": "verifying sha256 digest" }));
+    lines.push(json!({ "status": "writing manifest" }));
+    lines.push(json!({ "status": "success" }));
+
+    ndjson(&state, lines, fail_with)
+}
+
+async fn create(State(state): State<Shared>, body: Bytes) -> Response {
+    let req = match parse_body(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(400, &e),
+    };
+    let stream = wants_stream(&req);
+    let fail_with = match prelude(&state, "/api/create", stream).await {
+        Ok(rule) => rule,
+        Err(resp) => return resp,
+    };
+    let model = req["model"].as_str().unwrap_or_default().to_string();
+    if model.is_empty() {
+        return error_response(400, "model is required");
+    }
+    match req["from"].as_str() {
+        Some(from) if !state.has_model(from) => return model_not_found(from),
+        None if req["files"]
+            .as_object()
+            .is_none_or(|files| files.is_empty()) =>
+        {
+            return error_response(400, "neither 'from' or 'files' was specified");
+        }
+        _ => {}
+    }
+
+    if fail_with.is_none() {
+        let mut models = state.models.lock().unwrap();
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+
+    if !stream {
+        return Json(json!({ "status": "success" })).into_response();
+    }
+
+    let mut lines = vec![json!({ "status": "using existing layer sha256:mock" })];
+    for field in ["template", "system", "parameters", "messages", "license"] {
+        if !req[field].is_null() {
+            lines.push(json!({ "status": format!("creating new layer sha256:mock-{}", field) }));
+        }
+    }
+    lines.push(json!({ "status": "writing manifest" }));
+    lines.push(json!({ "status": "success" }));
+
+    ndjson(&state, lines, fail_with)
+}
+
+/// A bag-of-words embedding: texts sharing words end up close together, which is
+/// enough to make similarity searches look plausible.
+fn embed_text(text: &str, dims: usize) -> Vec<f32> {
+    let mut vector = vec![0.0f32; dims.max(1)];
+    for word in text.split_whitespace() {
+        let mut hasher = DefaultHasher::new();
+        word.to_lowercase().hash(&mut hasher);
+        let hash = hasher.finish();
+        let dim = (hash % vector.len() as u64) as usize;
+        vector[dim] += if hash & (1 << 63) == 0 { 1.0 } else { -1.0 };
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+async fn embed(State(state): State<Shared>, body: Bytes) -> Response {
+    let req = match parse_body(&body) {
+        Ok(req) => req,
+        Err(e) => return error_response(400, &e),
+    };
+    if let Err(resp) = prelude(&state, "/api/embed", false).await {
+        return resp;
+    }
+    let model = req["model"].as_str().unwrap_or_default();
+    if !state.has_model(model) {
+        return model_not_found(model);
+    }
+
+    let inputs: Vec<String> = match &req["input"] {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => return error_response(400, "input is required"),
+    };
+    let embeddings: Vec<Vec<f32>> = inputs
+        .iter()
+        .map(|text| embed_text(text, state.script.embedding_dims))
+        .collect();
+
+    Json(json!({
+        "model": model,
+        "embeddings": embeddings,
+        "total_duration": 20_000_000u64,
+        "prompt_eval_count": inputs.len(),
+    }))
+    .into_response()
+}
+
+pub fn router(script: Script) -> Router {
+    let state = Arc::new(MockState {
+        models: Mutex::new(script.models.clone()),
+        script,
+        request_counts: Mutex::new(HashMap::new()),
+        next_reply: AtomicUsize::new(0),
+    });
+
+    Router::new()
+        .route("/", get(|| async { "Ollama is running" }))
+        .route("/api/tags", get(tags))
+        .route("/api/chat", post(chat))
+        .route("/api/generate", post(generate))
+        .route("/api/show", post(show))
+        .route("/api/pull", post(pull))
+        .route("/api/create", post(create))
+        .route("/api/embed", post(embed))
+        .layer(CorsLayer::permissive())
+        .with_state(state)
+}
+
+pub async fn serve(listener: TcpListener, script: Script) -> io::Result<()> {
+    axum::serve(listener, router(script)).await
+}
+
+/// Starts a server on a free local port in the background and returns its address.
+/// Meant for tests; the server lives as long as the runtime.
+pub async fn spawn(script: Script) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(serve(listener, script));
+    Ok(addr)
+}
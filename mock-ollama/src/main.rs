@@ -0,0 +1,58 @@
+use std::process;
+
+use mock_ollama::Script;
+use tokio::net::TcpListener;
+
+const USAGE: &str = "usage: mock-ollama [--port <port>] [--script <script.json>]";
+
+fn parse_args() -> Result<(u16, Script), String> {
+    let mut port = 11434;
+    let mut script = Script::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                let value = args.next().ok_or("--port needs a value")?;
+                port = value
+                    .parse()
+                    .map_err(|e| format!("invalid port {}: {}", value, e))?;
+            }
+            "--script" => {
+                let path = args.next().ok_or("--script needs a path")?;
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("cannot read {}: {}", path, e))?;
+                script = serde_json::from_str(&text)
+                    .map_err(|e| format!("invalid script {}: {}", path, e))?;
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unknown argument {}\n{}", other, USAGE)),
+        }
+    }
+
+    Ok((port, script))
+}
+
+#[tokio::main]
+async fn main() {
+    let (port, script) = parse_args().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2);
+    });
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("cannot listen on port {}: {}", port, e);
+            process::exit(1);
+        });
+    println!("mock-ollama listening on http://127.0.0.1:{}", port);
+
+    if let Err(e) = mock_ollama::serve(listener, script).await {
+        eprintln!("server error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use mock_ollama::{spawn, ErrorRule, Script};
+use serde_json::{json, Value};
+
+fn quick(script: Script) -> Script {
+    Script {
+        token_delay_ms: 0,
+        ..script
+    }
+}
+
+async fn post(addr: SocketAddr, path: &str, body: Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("http://{}{}", addr, path))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Reads a streamed response the way the app does: chunk by chunk, parsing each
+/// complete line as JSON and carrying a partial line over to the next chunk.
+async fn ndjson(mut resp: reqwest::Response) -> Vec<Value> {
+    let mut lines = Vec::new();
+    let mut buffer = String::new();
+    while let Some(chunk) = resp.chunk().await.unwrap() {
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        while let Some(end) = buffer.find('\n') {
+            let line: String = buffer.drain(..=end).collect();
+            if !line.trim().is_empty() {
+                lines.push(serde_json::from_str(&line).unwrap());
+            }
+        }
+    }
+    assert!(buffer.trim().is_empty(), "unterminated line {:?}", buffer);
+    lines
+}
+
+fn chat_request(stream: bool) -> Value {
+    json!({
+        "model": "mock-llm:latest",
+        "messages": [{ "role": "user", "content": "hello there" }],
+        "stream": stream,
+    })
+}
+
+#[tokio::test]
+async fn chat_streams_the_reply_in_chunks() {
+    let addr = spawn(quick(Script {
+        replies: vec!["One two three four five".to_string()],
+        thinking: Some("Hmm let me see".to_string()),
+        chunk_words: 2,
+        ..Script::default()
+    }))
+    .await
+    .unwrap();
+
+    let mut request = chat_request(true);
+    request["think"] = json!(true);
+    let resp = post(addr, "/api/chat", request).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/x-ndjson");
+    let lines = ndjson(resp).await;
+
+    let (last, chunks) = lines.split_last().unwrap();
+    assert!(chunks.iter().all(|line| line["done"] == false));
+    let thinking: String = chunks
+        .iter()
+        .filter_map(|line| line["message"]["thinking"].as_str())
+        .collect();
+    let content: String = chunks
+        .iter()
+        .filter_map(|line| line["message"]["content"].as_str())
+        .collect();
+    assert_eq!(thinking, "Hmm let me see");
+    assert_eq!(content, "One two three four five");
+    assert_eq!(chunks.len(), 5);
+    assert_eq!(last["done"], true);
+    assert_eq!(last["done_reason"], "stop");
+    assert_eq!(last["eval_count"], 3);
+}
+
+#[tokio::test]
+async fn chat_answers_in_one_body_without_streaming() {
+    let addr = spawn(quick(Script::default())).await.unwrap();
+
+    let resp = post(addr, "/api/chat", chat_request(false)).await;
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["message"]["role"], "assistant");
+    // Without scripted replies the prompt is echoed.
+    assert_eq!(body["message"]["content"], "You said: hello there");
+    assert_eq!(body["done"], true);
+    assert!(body["message"].get("thinking").is_none());
+}
+
+#[tokio::test]
+async fn chat_rejects_unknown_models() {
+    let addr = spawn(quick(Script::default())).await.unwrap();
+
+    let mut request = chat_request(false);
+    request["model"] = json!("missing:latest");
+    let resp = post(addr, "/api/chat", request).await;
+    assert_eq!(resp.status(), 404);
+    let body: Value = resp.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("missing:latest"));
+}
+
+#[tokio::test]
+async fn embed_returns_one_normalised_vector_per_input() {
+    let addr = spawn(quick(Script {
+        embedding_dims: 16,
+        ..Script::default()
+    }))
+    .await
+    .unwrap();
+
+    let resp = post(
+        addr,
+        "/api/embed",
+        json!({
+            "model": "mock-embed:latest",
+            "input": ["red apples", "red apples", "blue sky"],
+        }),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    let embeddings: Vec<Vec<f64>> = serde_json::from_value(body["embeddings"].clone()).unwrap();
+    assert_eq!(embeddings.len(), 3);
+    assert!(embeddings.iter().all(|v| v.len() == 16));
+    let norm: f64 = embeddings[0].iter().map(|v| v * v).sum::<f64>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-6);
+    // The same text always embeds the same way.
+    assert_eq!(embeddings[0], embeddings[1]);
+
+    let single = post(
+        addr,
+        "/api/embed",
+        json!({ "model": "mock-embed:latest", "input": "just one" }),
+    )
+    .await;
+    let body: Value = single.json().await.unwrap();
+    assert_eq!(body["embeddings"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn pull_reports_progress_and_adds_the_model() {
+    let addr = spawn(quick(Script::default())).await.unwrap();
+
+    let resp = post(addr, "/api/pull", json!({ "model": "new-model:7b" })).await;
+    assert_eq!(resp.status(), 200);
+    let lines = ndjson(resp).await;
+
+    assert_eq!(lines.first().unwrap()["status"], "pulling manifest");
+    assert_eq!(lines.last().unwrap()["status"], "success");
+    let completed: Vec<u64> = lines
+        .iter()
+        .filter_map(|line| line["completed"].as_u64())
+        .collect();
+    assert!(completed.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(completed.last(), lines[1]["total"].as_u64().as_ref());
+
+    let tags: Value = reqwest::get(format!("http://{}/api/tags", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let names: Vec<&str> = tags["models"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|m| m["name"].as_str())
+        .collect();
+    assert!(names.contains(&"new-model:7b"));
+}
+
+#[tokio::test]
+async fn errors_are_injected_every_nth_request() {
+    let addr = spawn(quick(Script {
+        errors: HashMap::from([(
+            "/api/chat".to_string(),
+            ErrorRule {
+                status: 503,
+                message: "overloaded".to_string(),
+                every: Some(2),
+                mid_stream: false,
+            },
+        )]),
+        ..Script::default()
+    }))
+    .await
+    .unwrap();
+
+    let statuses = [
+        post(addr, "/api/chat", chat_request(false)).await.status(),
+        post(addr, "/api/chat", chat_request(false)).await.status(),
+        post(addr, "/api/chat", chat_request(false)).await.status(),
+    ];
+    assert_eq!(statuses.map(|s| s.as_u16()), [200, 503, 200]);
+
+    let resp = post(addr, "/api/chat", chat_request(false)).await;
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"], "overloaded");
+}
+
+#[tokio::test]
+async fn mid_stream_errors_arrive_as_the_last_line() {
+    let addr = spawn(quick(Script {
+        errors: HashMap::from([(
+            "/api/pull".to_string(),
+            ErrorRule {
+                status: 500,
+                message: "disk full".to_string(),
+                every: None,
+                mid_stream: true,
+            },
+        )]),
+        ..Script::default()
+    }))
+    .await
+    .unwrap();
+
+    let resp = post(addr, "/api/pull", json!({ "model": "big:70b" })).await;
+    assert_eq!(resp.status(), 200);
+    let lines = ndjson(resp).await;
+    assert_eq!(lines.last().unwrap()["error"], "disk full");
+    assert!(lines.iter().all(|line| line["status"] != "success"));
+
+    // The pull never finished, so the model wasn't added.
+    let tags: Value = reqwest::get(format!("http://{}/api/tags", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(!tags.to_string().contains("big:70b"));
+}
+
+#[tokio::test]
+async fn latency_and_token_delay_slow_the_response() {
+    let addr = spawn(Script {
+        replies: vec!["one two three".to_string()],
+        latency_ms: 150,
+        token_delay_ms: 50,
+        ..Script::default()
+    })
+    .await
+    .unwrap();
+
+    let started = Instant::now();
+    let resp = post(addr, "/api/chat", chat_request(true)).await;
+    assert!(started.elapsed() >= Duration::from_millis(150));
+    let lines = ndjson(resp).await;
+    // Three chunks and the final line, with a pause before each but the first.
+    assert_eq!(lines.len(), 4);
+    assert!(started.elapsed() >= Duration::from_millis(150 + 3 * 50));
+}
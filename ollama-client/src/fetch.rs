@@ -0,0 +1,81 @@
+use js_sys::{Reflect, Uint8Array};
+use reqwasm::http::{Request, Response};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortSignal, ReadableStreamDefaultReader};
+
+use crate::{Body, Client, Transport};
+
+/// Requests through the browser's `fetch`, for code running in the webview.
+#[derive(Clone, Default)]
+pub struct Fetch {
+    abort: Option<AbortSignal>,
+}
+
+impl Client<Fetch> {
+    /// A client whose requests, including streams already under way, stop when
+    /// `signal` is aborted.
+    pub fn with_abort_signal(mut self, signal: AbortSignal) -> Self {
+        self.transport.abort = Some(signal);
+        self
+    }
+}
+
+impl Transport for Fetch {
+    type Response = Response;
+
+    async fn get(&self, url: &str) -> Result<Self::Response, String> {
+        Request::get(url)
+            .abort_signal(self.abort.as_ref())
+            .send()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn post_json(&self, url: &str, body: String) -> Result<Self::Response, String> {
+        Request::post(url)
+            .header("Content-Type", "application/json")
+            .abort_signal(self.abort.as_ref())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Body for Response {
+    fn status(&self) -> u16 {
+        Response::status(self)
+    }
+
+    async fn text(self) -> Result<String, String> {
+        Response::text(&self)
+            .await
+            .map_err(|e| format!("Network Error: {}", e))
+    }
+
+    async fn read_chunks(
+        self,
+        mut on_chunk: impl FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let Some(body) = self.body() else {
+            return Ok(());
+        };
+        let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+        loop {
+            let result = JsFuture::from(reader.read())
+                .await
+                .map_err(|e| format!("Network Error: {:?}", e))?;
+            let done = Reflect::get(&result, &"done".into())
+                .ok()
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            if done {
+                return Ok(());
+            }
+            if let Ok(value) = Reflect::get(&result, &"value".into()) {
+                on_chunk(&value.unchecked_into::<Uint8Array>().to_vec())?;
+            }
+        }
+    }
+}
@@ -0,0 +1,434 @@
+//! Client for the Ollama HTTP API.
+//!
+//! The request and response types mirror Ollama's JSON. [`Client`] holds no UI state,
+//! so the chat UI, the evaluator and the backend's batch runner all talk to Ollama
+//! through it; only the [`Transport`] underneath differs between the webview and
+//! native code.
+
+#[cfg(feature = "fetch")]
+mod fetch;
+#[cfg(feature = "reqwest")]
+mod native;
+mod transport;
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "fetch")]
+pub use fetch::Fetch;
+#[cfg(feature = "reqwest")]
+pub use native::Reqwest;
+pub use transport::{Body, Transport};
+
+pub const DEFAULT_API_URL: &str = "http://localhost:11434";
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    /// Attached images, base64 encoded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    /// How long the model spent reasoning, in milliseconds. Only known for replies
+    /// generated in this session.
+    #[serde(skip)]
+    pub thinking_ms: Option<f64>,
+    /// Server timing for an assistant reply. Kept with saved conversations and never
+    /// sent back to Ollama.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<GenerationStats>,
+}
+
+/// Token count and generation time reported on the final line of a reply.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub eval_count: u64,
+    /// Nanoseconds spent generating `eval_count` tokens.
+    pub eval_duration: u64,
+}
+
+impl GenerationStats {
+    pub fn tokens_per_second(&self) -> Option<f64> {
+        (self.eval_duration > 0).then(|| self.eval_count as f64 / (self.eval_duration as f64 / 1e9))
+    }
+}
+
+impl Message {
+    pub fn new(role: &str, content: impl Into<String>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content)
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<Message>,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<bool>,
+    /// Sampling options such as `temperature` or `seed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Map<String, Value>>,
+}
+
+/// One line of a streamed `/api/chat` response. Timing fields only appear on the
+/// final line.
+#[derive(Deserialize)]
+pub struct ChatChunk {
+    #[serde(default)]
+    pub message: Option<Message>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub eval_count: Option<u64>,
+    #[serde(default)]
+    pub eval_duration: Option<u64>,
+    #[serde(default)]
+    pub total_duration: Option<u64>,
+}
+
+impl ChatChunk {
+    pub fn stats(&self) -> Option<GenerationStats> {
+        Some(GenerationStats {
+            eval_count: self.eval_count?,
+            eval_duration: self.eval_duration?,
+        })
+    }
+}
+
+/// A complete `/api/chat` reply with the server's timing, durations in nanoseconds.
+#[derive(Clone, PartialEq, Default)]
+pub struct ChatResponse {
+    pub message: Message,
+    pub eval_count: Option<u64>,
+    pub eval_duration: Option<u64>,
+    pub total_duration: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct TagsResponse {
+    pub models: Vec<ModelInfo>,
+}
+
+#[derive(Serialize, Default)]
+pub struct GenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<i64>>,
+    pub raw: bool,
+    pub stream: bool,
+}
+
+/// One line of a streamed `/api/generate` response. The final line carries the
+/// `context` that can be sent back to continue the generation.
+#[derive(Deserialize)]
+pub struct GenerateChunk {
+    #[serde(default)]
+    pub response: String,
+    #[serde(default)]
+    pub context: Option<Vec<i64>>,
+    #[serde(default)]
+    pub eval_count: Option<u64>,
+    #[serde(default)]
+    pub eval_duration: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct EmbedRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct EmbedResponse {
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+/// Structured `/api/create` body. Files and adapters map a file name to the digest of
+/// a blob that has already been uploaded.
+#[derive(Serialize, Default)]
+pub struct CreateRequest {
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub adapters: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub license: Vec<String>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub parameters: Map<String, Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<Message>,
+    pub stream: bool,
+}
+
+/// One status line of a streamed `/api/create` or `/api/pull` response.
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct ProgressChunk {
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ShowRequest<'a> {
+    model: &'a str,
+}
+
+#[derive(Deserialize, Default)]
+pub struct ShowResponse {
+    #[serde(default)]
+    pub modelfile: String,
+}
+
+/// The message in an error body such as `{"error": "model not found"}`, or the status
+/// code when there is none.
+fn api_error(status: u16, body: &str) -> String {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        error: String,
+    }
+    match serde_json::from_str::<ErrorBody>(body) {
+        Ok(body) if !body.error.is_empty() => format!("API Error: {}", body.error),
+        _ => format!("API Error: {}", status),
+    }
+}
+
+/// Reads a newline-delimited JSON body, handing each parsed line to `on_item` as soon
+/// as it arrives.
+async fn read_ndjson<T: DeserializeOwned>(
+    resp: impl Body,
+    mut on_item: impl FnMut(T),
+) -> Result<(), String> {
+    let mut buffer: Vec<u8> = Vec::new();
+
+    let mut handle_line = |line: &[u8]| -> Result<(), String> {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+        let item =
+            serde_json::from_slice(line).map_err(|e| format!("Error parsing response: {}", e))?;
+        on_item(item);
+        Ok(())
+    };
+
+    resp.read_chunks(|chunk| {
+        buffer.extend_from_slice(chunk);
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            handle_line(&line)?;
+        }
+        Ok(())
+    })
+    .await?;
+
+    handle_line(&buffer)
+}
+
+/// Reads a streamed response whose lines may carry an `error`, returning the last one.
+async fn read_stream<T: DeserializeOwned>(
+    resp: impl Body,
+    error_of: impl Fn(&T) -> Option<&String>,
+    mut on_item: impl FnMut(T),
+) -> Result<(), String> {
+    let mut error = None;
+    read_ndjson(resp, |item: T| {
+        if let Some(e) = error_of(&item) {
+            error = Some(format!("API Error: {}", e));
+        }
+        on_item(item);
+    })
+    .await?;
+    error.map_or(Ok(()), Err)
+}
+
+/// Reads a whole JSON body.
+async fn read_json<T: DeserializeOwned>(resp: impl Body) -> Result<T, String> {
+    let text = resp.text().await?;
+    serde_json::from_str(&text).map_err(|e| format!("Error parsing response: {}", e))
+}
+
+/// A connection to one Ollama server, sending its requests through `T`.
+#[derive(Clone)]
+pub struct Client<T> {
+    api_url: String,
+    transport: T,
+}
+
+impl<T: Transport + Default> Client<T> {
+    pub fn new(api_url: &str) -> Self {
+        Self::with_transport(api_url, T::default())
+    }
+}
+
+impl<T: Transport> Client<T> {
+    pub fn with_transport(api_url: &str, transport: T) -> Self {
+        Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            transport,
+        }
+    }
+
+    /// Posts `body` as JSON to `path` and checks the status code, reporting the error
+    /// Ollama gave if there is one.
+    async fn post_json<B: Serialize>(&self, path: &str, body: &B) -> Result<T::Response, String> {
+        let body =
+            serde_json::to_string(body).map_err(|e| format!("Error encoding request: {}", e))?;
+        let resp = self
+            .transport
+            .post_json(&format!("{}{}", self.api_url, path), body)
+            .await
+            .map_err(|e| format!("Network Error: {}", e))?;
+        check_status(resp).await
+    }
+
+    pub async fn models(&self) -> Result<Vec<String>, String> {
+        let resp = self
+            .transport
+            .get(&format!("{}/api/tags", self.api_url))
+            .await
+            .map_err(|e| format!("Connection Failed: {}", e))?;
+        let text = check_status(resp).await?.text().await?;
+        let tags: TagsResponse =
+            serde_json::from_str(&text).map_err(|e| format!("Failed to parse models: {}", e))?;
+        Ok(tags.models.into_iter().map(|m| m.name).collect())
+    }
+
+    /// Streams a chat completion, calling `on_chunk` for every partial message.
+    pub async fn stream_chat(
+        &self,
+        request: &ChatRequest,
+        on_chunk: impl FnMut(ChatChunk),
+    ) -> Result<(), String> {
+        let resp = self.post_json("/api/chat", request).await?;
+        read_stream(resp, |chunk: &ChatChunk| chunk.error.as_ref(), on_chunk).await
+    }
+
+    /// Runs a chat completion to the end and returns the whole reply. Works whether or
+    /// not `request` asks for streaming.
+    pub async fn chat(&self, request: &ChatRequest) -> Result<ChatResponse, String> {
+        let mut response = ChatResponse {
+            message: Message::new("assistant", ""),
+            ..Default::default()
+        };
+        self.stream_chat(request, |chunk| {
+            if let Some(delta) = chunk.message {
+                response.message.content.push_str(&delta.content);
+                if let Some(thinking) = delta.thinking {
+                    response
+                        .message
+                        .thinking
+                        .get_or_insert_with(String::new)
+                        .push_str(&thinking);
+                }
+            }
+            response.eval_count = chunk.eval_count.or(response.eval_count);
+            response.eval_duration = chunk.eval_duration.or(response.eval_duration);
+            response.total_duration = chunk.total_duration.or(response.total_duration);
+        })
+        .await?;
+        Ok(response)
+    }
+
+    /// Streams a raw completion, calling `on_chunk` for every partial response.
+    pub async fn stream_generate(
+        &self,
+        request: &GenerateRequest,
+        on_chunk: impl FnMut(GenerateChunk),
+    ) -> Result<(), String> {
+        let resp = self.post_json("/api/generate", request).await?;
+        read_stream(resp, |chunk: &GenerateChunk| chunk.error.as_ref(), on_chunk).await
+    }
+
+    pub async fn embed(&self, request: &EmbedRequest) -> Result<Vec<Vec<f32>>, String> {
+        let resp = self.post_json("/api/embed", request).await?;
+        let data: EmbedResponse = read_json(resp).await?;
+        Ok(data.embeddings)
+    }
+
+    /// Creates a model, calling `on_status` for every progress line.
+    pub async fn stream_create(
+        &self,
+        request: &CreateRequest,
+        on_status: impl FnMut(ProgressChunk),
+    ) -> Result<(), String> {
+        let resp = self.post_json("/api/create", request).await?;
+        read_stream(
+            resp,
+            |chunk: &ProgressChunk| chunk.error.as_ref(),
+            on_status,
+        )
+        .await
+    }
+
+    /// Fetches details of an installed model, including its Modelfile.
+    pub async fn show_model(&self, model: &str) -> Result<ShowResponse, String> {
+        let resp = self.post_json("/api/show", &ShowRequest { model }).await?;
+        read_json(resp).await
+    }
+}
+
+/// Passes a successful response through, and turns any other into its error.
+async fn check_status<B: Body>(resp: B) -> Result<B, String> {
+    let status = resp.status();
+    if (200..300).contains(&status) {
+        return Ok(resp);
+    }
+    let body = resp.text().await.unwrap_or_default();
+    Err(api_error(status, &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_bodies_are_reported() {
+        assert_eq!(
+            api_error(404, r#"{"error":"model 'x' not found"}"#),
+            "API Error: model 'x' not found"
+        );
+        assert_eq!(api_error(502, "<html>Bad Gateway</html>"), "API Error: 502");
+        assert_eq!(api_error(500, r#"{"error":""}"#), "API Error: 500");
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crate::{Body, Transport};
+
+/// Requests through reqwest, for native code. Clones share one connection pool.
+#[derive(Clone, Default)]
+pub struct Reqwest(reqwest::Client);
+
+impl Reqwest {
+    /// Fails requests, streams included, that take longer than `timeout` in all.
+    pub fn with_timeout(timeout: Duration) -> Result<Self, String> {
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map(Self)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Transport for Reqwest {
+    type Response = reqwest::Response;
+
+    async fn get(&self, url: &str) -> Result<Self::Response, String> {
+        self.0.get(url).send().await.map_err(|e| e.to_string())
+    }
+
+    async fn post_json(&self, url: &str, body: String) -> Result<Self::Response, String> {
+        self.0
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Body for reqwest::Response {
+    fn status(&self) -> u16 {
+        reqwest::Response::status(self).as_u16()
+    }
+
+    async fn text(self) -> Result<String, String> {
+        reqwest::Response::text(self)
+            .await
+            .map_err(|e| format!("Network Error: {}", e))
+    }
+
+    async fn read_chunks(
+        mut self,
+        mut on_chunk: impl FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        while let Some(chunk) = self
+            .chunk()
+            .await
+            .map_err(|e| format!("Network Error: {}", e))?
+        {
+            on_chunk(&chunk)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+/// Sends the HTTP requests of a [`Client`](crate::Client). Errors are plain
+/// descriptions; the client adds what it was doing.
+// Implemented only in this crate, where the futures' auto traits are known.
+#[allow(async_fn_in_trait)]
+pub trait Transport {
+    type Response: Body;
+
+    async fn get(&self, url: &str) -> Result<Self::Response, String>;
+
+    async fn post_json(&self, url: &str, body: String) -> Result<Self::Response, String>;
+}
+
+/// A response whose status has arrived and whose body may still be on its way.
+#[allow(async_fn_in_trait)]
+pub trait Body {
+    fn status(&self) -> u16;
+
+    async fn text(self) -> Result<String, String>;
+
+    /// Hands each piece of the body to `on_chunk` as it arrives, stopping at the first
+    /// error.
+    async fn read_chunks(
+        self,
+        on_chunk: impl FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String>;
+}
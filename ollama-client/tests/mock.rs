@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use mock_ollama::{spawn, ErrorRule, Script};
+use ollama_client::{ChatRequest, Client, EmbedRequest, GenerateRequest, Message, Reqwest};
+
+async fn client(script: Script) -> Client<Reqwest> {
+    let addr = spawn(Script {
+        token_delay_ms: 0,
+        ..script
+    })
+    .await
+    .unwrap();
+    Client::new(&format!("http://{}/", addr))
+}
+
+fn chat_request(model: &str, stream: bool) -> ChatRequest {
+    ChatRequest {
+        model: model.to_string(),
+        messages: vec![Message::new("user", "hello there")],
+        stream,
+        ..Default::default()
+    }
+}
+
+fn failing(path: &str, message: &str, mid_stream: bool) -> Script {
+    Script {
+        errors: HashMap::from([(
+            path.to_string(),
+            ErrorRule {
+                status: 500,
+                message: message.to_string(),
+                every: None,
+                mid_stream,
+            },
+        )]),
+        ..Script::default()
+    }
+}
+
+#[tokio::test]
+async fn lists_the_installed_models() {
+    let client = client(Script::default()).await;
+    assert_eq!(
+        client.models().await.unwrap(),
+        vec!["mock-llm:latest", "mock-embed:latest"]
+    );
+}
+
+#[tokio::test]
+async fn chat_collects_the_streamed_reply() {
+    let client = client(Script {
+        replies: vec!["One two three four".to_string()],
+        thinking: Some("Let me see".to_string()),
+        chunk_words: 2,
+        ..Script::default()
+    })
+    .await;
+
+    let mut request = chat_request("mock-llm:latest", true);
+    request.think = Some(true);
+    let mut chunks = 0;
+    client
+        .stream_chat(&request, |chunk| {
+            chunks += usize::from(chunk.message.is_some());
+        })
+        .await
+        .unwrap();
+    assert!(chunks > 2);
+
+    let response = client.chat(&request).await.unwrap();
+    assert_eq!(response.message.content, "One two three four");
+    assert_eq!(response.message.thinking.as_deref(), Some("Let me see"));
+    assert_eq!(response.eval_count, Some(2));
+}
+
+#[tokio::test]
+async fn chat_works_without_streaming() {
+    let client = client(Script::default()).await;
+    let response = client
+        .chat(&chat_request("mock-llm:latest", false))
+        .await
+        .unwrap();
+    assert_eq!(response.message.content, "You said: hello there");
+}
+
+#[tokio::test]
+async fn error_bodies_are_surfaced() {
+    let client = client(Script::default()).await;
+    let Err(error) = client.chat(&chat_request("missing:latest", false)).await else {
+        panic!("an unknown model was answered");
+    };
+    assert!(error.starts_with("API Error: "), "{}", error);
+    assert!(error.contains("missing:latest"), "{}", error);
+
+    let client = self::client(failing("/api/embed", "out of memory", false)).await;
+    let request = EmbedRequest {
+        model: "mock-embed:latest".to_string(),
+        input: vec!["text".to_string()],
+    };
+    assert_eq!(
+        client.embed(&request).await.unwrap_err(),
+        "API Error: out of memory"
+    );
+}
+
+#[tokio::test]
+async fn errors_partway_through_a_stream_are_reported() {
+    let client = client(failing("/api/generate", "model crashed", true)).await;
+    let request = GenerateRequest {
+        model: "mock-llm:latest".to_string(),
+        prompt: "Once upon a time".to_string(),
+        stream: true,
+        ..Default::default()
+    };
+    let mut text = String::new();
+    let result = client
+        .stream_generate(&request, |chunk| text.push_str(&chunk.response))
+        .await;
+    assert_eq!(result.unwrap_err(), "API Error: model crashed");
+    assert!(!text.is_empty());
+}
+
+#[tokio::test]
+async fn embeds_every_input() {
+    let client = client(Script {
+        embedding_dims: 4,
+        ..Script::default()
+    })
+    .await;
+    let request = EmbedRequest {
+        model: "mock-embed:latest".to_string(),
+        input: vec!["a".to_string(), "b".to_string()],
+    };
+    let embeddings = client.embed(&request).await.unwrap();
+    assert_eq!(embeddings.len(), 2);
+    assert!(embeddings.iter().all(|v| v.len() == 4));
+}
+
+#[tokio::test]
+async fn unreachable_servers_fail_to_connect() {
+    let client: Client<Reqwest> = Client::new("http://127.0.0.1:1");
+    assert!(client
+        .models()
+        .await
+        .unwrap_err()
+        .starts_with("Connection Failed"));
+}
@@ -0,0 +1,336 @@
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+pub type Row = Map<String, Value>;
+
+#[derive(Debug)]
+pub struct Dataset {
+    pub columns: Vec<String>,
+    pub rows: Vec<Row>,
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+}
+
+pub fn read(path: &Path) -> Result<Dataset, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if is_csv(path) {
+        parse_csv(&text)
+    } else {
+        parse_jsonl(&text)
+    }
+}
+
+pub fn parse_csv(text: &str) -> Result<Dataset, String> {
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let columns: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    let mut rows = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Invalid CSV on row {}: {}", i + 1, e))?;
+        rows.push(
+            columns
+                .iter()
+                .zip(record.iter())
+                .map(|(c, v)| (c.clone(), Value::from(v)))
+                .collect(),
+        );
+    }
+    Ok(Dataset { columns, rows })
+}
+
+/// One JSON object per line. Columns are the keys in order of first appearance, since
+/// rows need not all have the same fields.
+pub fn parse_jsonl(text: &str) -> Result<Dataset, String> {
+    let mut columns: Vec<String> = Vec::new();
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: Row = match serde_json::from_str(line) {
+            Ok(Value::Object(row)) => row,
+            Ok(_) => return Err(format!("Line {} is not a JSON object", i + 1)),
+            Err(e) => return Err(format!("Invalid JSON on line {}: {}", i + 1, e)),
+        };
+        for key in row.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        rows.push(row);
+    }
+    Ok(Dataset { columns, rows })
+}
+
+/// The length of `bytes` up to the end of the last whole record. CSV records end at a
+/// newline outside quotes, since a quoted field may span lines; JSONL records at any
+/// newline.
+fn complete_len(bytes: &[u8], csv: bool) -> usize {
+    if !csv {
+        return bytes.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+    }
+    let mut quoted = false;
+    let mut end = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => end = i + 1,
+            _ => {}
+        }
+    }
+    end
+}
+
+/// Drops a half-written last record left behind by a crash, so appending starts on a
+/// clean line.
+fn repair_tail(path: &Path) -> Result<(), String> {
+    let Ok(bytes) = fs::read(path) else {
+        return Ok(());
+    };
+    let keep = complete_len(&bytes, is_csv(path));
+    if keep == bytes.len() {
+        return Ok(());
+    }
+    let file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    file.set_len(keep as u64).map_err(|e| e.to_string())
+}
+
+/// Rows an earlier run of the same job already got a result for. Rows that failed
+/// aren't among them, so they're tried again.
+pub fn completed_rows(path: &Path) -> Result<HashSet<usize>, String> {
+    repair_tail(path)?;
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(HashSet::new());
+    };
+    let mut done = HashSet::new();
+    if is_csv(path) {
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let Some((row_index, error_index)) = reader.headers().ok().and_then(|h| {
+            let row = h.iter().position(|c| c == "row")?;
+            Some((row, h.iter().position(|c| c == "error")))
+        }) else {
+            return Ok(done);
+        };
+        for record in reader.records().flatten() {
+            let failed = error_index
+                .and_then(|i| record.get(i))
+                .is_some_and(|error| !error.is_empty());
+            if failed {
+                continue;
+            }
+            if let Some(row) = record.get(row_index).and_then(|v| v.parse().ok()) {
+                done.insert(row);
+            }
+        }
+    } else {
+        for line in text.lines() {
+            if let Ok(value) = serde_json::from_str::<Value>(line) {
+                if value.get("error").is_some_and(|e| !e.is_null()) {
+                    continue;
+                }
+                if let Some(row) = value["row"].as_u64() {
+                    done.insert(row as usize);
+                }
+            }
+        }
+    }
+    Ok(done)
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Appends results to the output file: CSV when it ends in `.csv`, JSONL otherwise.
+/// Every record carries its input row number, which is what resuming keys on.
+pub enum OutputWriter {
+    Jsonl(BufWriter<File>),
+    Csv(Box<csv::Writer<File>>, Vec<String>),
+}
+
+impl OutputWriter {
+    pub fn open(path: &Path, columns: &[String]) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        if !is_csv(path) {
+            return Ok(Self::Jsonl(BufWriter::new(file)));
+        }
+
+        let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+        let mut writer = csv::Writer::from_writer(file);
+        if is_new {
+            let header = std::iter::once("row")
+                .chain(columns.iter().map(String::as_str))
+                .chain(["output", "error"]);
+            writer.write_record(header).map_err(|e| e.to_string())?;
+        }
+        Ok(Self::Csv(Box::new(writer), columns.to_vec()))
+    }
+
+    pub fn write(
+        &mut self,
+        index: usize,
+        row: &Row,
+        result: &Result<String, String>,
+    ) -> Result<(), String> {
+        let (output, error) = match result {
+            Ok(output) => (Some(output.as_str()), None),
+            Err(error) => (None, Some(error.as_str())),
+        };
+        match self {
+            Self::Jsonl(writer) => {
+                let mut record = Map::new();
+                record.insert("row".to_string(), Value::from(index));
+                record.insert("input".to_string(), Value::Object(row.clone()));
+                record.insert(
+                    "output".to_string(),
+                    output.map_or(Value::Null, Value::from),
+                );
+                if let Some(error) = error {
+                    record.insert("error".to_string(), Value::from(error));
+                }
+                let line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+                writeln!(writer, "{}", line).map_err(|e| e.to_string())?;
+                writer.flush().map_err(|e| e.to_string())
+            }
+            Self::Csv(writer, columns) => {
+                let record = std::iter::once(index.to_string())
+                    .chain(columns.iter().map(|c| cell(row.get(c))))
+                    .chain([
+                        output.unwrap_or_default().to_string(),
+                        error.unwrap_or_default().to_string(),
+                    ]);
+                writer.write_record(record).map_err(|e| e.to_string())?;
+                writer.flush().map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("batch-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn parses_csv_with_quoted_fields() {
+        let data = parse_csv("id,text\n1,\"Hello, world\"\n2,\"multi\nline\"\n").unwrap();
+        assert_eq!(data.columns, vec!["id", "text"]);
+        assert_eq!(data.rows.len(), 2);
+        assert_eq!(data.rows[0]["text"], "Hello, world");
+        assert_eq!(data.rows[1]["text"], "multi\nline");
+    }
+
+    #[test]
+    fn parses_jsonl_and_collects_columns() {
+        let data = parse_jsonl("{\"a\": 1}\n\n{\"a\": 2, \"b\": \"x\"}\n").unwrap();
+        assert_eq!(data.columns, vec!["a", "b"]);
+        assert_eq!(data.rows.len(), 2);
+        assert!(parse_jsonl("{\"a\": 1}\n[1, 2]\n")
+            .unwrap_err()
+            .contains("Line 2"));
+    }
+
+    #[test]
+    fn resumes_jsonl_output_after_a_partial_write() {
+        let path = temp_path("out.jsonl");
+        let row: Row = serde_json::from_str("{\"text\": \"hi\"}").unwrap();
+        let mut writer = OutputWriter::open(&path, &[]).unwrap();
+        writer.write(0, &row, &Ok("greeting".to_string())).unwrap();
+        writer.write(2, &row, &Err("timeout".to_string())).unwrap();
+        drop(writer);
+        // Simulate a crash in the middle of writing row 3.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"row\": 3, \"inp").unwrap();
+
+        // Row 2 failed, so it's tried again.
+        assert_eq!(completed_rows(&path).unwrap(), HashSet::from([0]));
+        assert!(fs::read_to_string(&path).unwrap().ends_with('\n'));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_csv_rows_are_retried() {
+        let path = temp_path("failed.csv");
+        let columns = vec!["text".to_string()];
+        let row: Row = serde_json::from_str("{\"text\": \"hi\"}").unwrap();
+        let mut writer = OutputWriter::open(&path, &columns).unwrap();
+        writer.write(0, &row, &Err("timeout".to_string())).unwrap();
+        writer.write(1, &row, &Ok("hello".to_string())).unwrap();
+        drop(writer);
+        assert_eq!(completed_rows(&path).unwrap(), HashSet::from([1]));
+
+        // A later run that gets row 0 through counts it done.
+        let mut writer = OutputWriter::open(&path, &columns).unwrap();
+        writer.write(0, &row, &Ok("hello".to_string())).unwrap();
+        drop(writer);
+        assert_eq!(completed_rows(&path).unwrap(), HashSet::from([0, 1]));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_csv_record_cut_inside_a_quoted_field_is_dropped() {
+        let whole = "row,text,output,error\n0,hi,\"two\nlines\",\n";
+        assert_eq!(complete_len(whole.as_bytes(), true), whole.len());
+        let cut = format!("{}1,hi,\"first line\n", whole);
+        assert_eq!(complete_len(cut.as_bytes(), true), whole.len());
+        assert_eq!(complete_len(b"{\"row\": 0}\n{\"ro", false), 11);
+
+        let path = temp_path("cut.csv");
+        fs::write(&path, &cut).unwrap();
+        assert_eq!(completed_rows(&path).unwrap(), HashSet::from([0]));
+        assert_eq!(fs::read_to_string(&path).unwrap(), whole);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn csv_output_writes_one_header() {
+        let path = temp_path("out.csv");
+        let columns = vec!["text".to_string()];
+        let row: Row = serde_json::from_str("{\"text\": \"a, b\"}").unwrap();
+        OutputWriter::open(&path, &columns)
+            .unwrap()
+            .write(0, &row, &Ok("x".to_string()))
+            .unwrap();
+        OutputWriter::open(&path, &columns)
+            .unwrap()
+            .write(1, &row, &Ok("y".to_string()))
+            .unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            text,
+            "row,text,output,error\n0,\"a, b\",x,\n1,\"a, b\",y,\n"
+        );
+        assert_eq!(completed_rows(&path).unwrap(), HashSet::from([0, 1]));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,345 @@
+//! Runs one prompt template over every row of a CSV or JSONL file.
+//!
+//! Results are appended to the output file as they arrive, and that file doubles as
+//! the checkpoint: starting a job again skips rows already written, so a paused,
+//! cancelled or crashed job picks up where it stopped.
+
+mod dataset;
+mod template;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{stream, StreamExt};
+use ollama_client::{ChatRequest, Client, Message, Reqwest};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::watch;
+
+use dataset::{Dataset, OutputWriter, Row};
+use template::Template;
+
+const JOB_FILE: &str = "batch-job.json";
+const MAX_CONCURRENCY: usize = 16;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchConfig {
+    pub input_path: String,
+    pub output_path: String,
+    pub api_url: String,
+    pub model: String,
+    pub template: String,
+    #[serde(default)]
+    pub system: String,
+    pub concurrency: usize,
+}
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub total: usize,
+    /// Rows written so far, including those finished by an earlier run.
+    pub done: usize,
+    pub failed: usize,
+    pub resumed: usize,
+    pub running: bool,
+    pub paused: bool,
+    pub error: Option<String>,
+    pub output_path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchPreview {
+    pub columns: Vec<String>,
+    pub sample: Vec<Row>,
+    pub total: usize,
+}
+
+struct Job {
+    paused: watch::Sender<bool>,
+    cancelled: Arc<AtomicBool>,
+    progress: Arc<Mutex<BatchProgress>>,
+}
+
+/// The current (or last) batch job. Only one runs at a time.
+#[derive(Default)]
+pub struct BatchState(Mutex<Option<Job>>);
+
+fn job_file(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(JOB_FILE))
+}
+
+fn emit_progress(app: &AppHandle, progress: &Mutex<BatchProgress>) {
+    let snapshot = progress.lock().unwrap().clone();
+    if let Err(e) = app.emit("batch-progress", snapshot) {
+        log::warn!("Failed to emit batch progress: {}", e);
+    }
+}
+
+async fn complete(
+    client: &Client<Reqwest>,
+    config: &BatchConfig,
+    prompt: String,
+) -> Result<String, String> {
+    let mut messages = Vec::new();
+    if !config.system.trim().is_empty() {
+        messages.push(Message::system(config.system.clone()));
+    }
+    messages.push(Message::new("user", prompt));
+    let request = ChatRequest {
+        model: config.model.clone(),
+        messages,
+        stream: false,
+        ..Default::default()
+    };
+    let response = client.chat(&request).await?;
+    Ok(response.message.content.trim().to_string())
+}
+
+struct Run {
+    config: BatchConfig,
+    dataset: Dataset,
+    template: Template,
+    completed: HashSet<usize>,
+    writer: OutputWriter,
+    paused: watch::Receiver<bool>,
+    cancelled: Arc<AtomicBool>,
+    progress: Arc<Mutex<BatchProgress>>,
+}
+
+async fn run(app: AppHandle, mut job: Run) {
+    let client = match Reqwest::with_timeout(REQUEST_TIMEOUT) {
+        Ok(transport) => Client::with_transport(&job.config.api_url, transport),
+        Err(e) => {
+            job.progress.lock().unwrap().error = Some(e);
+            return;
+        }
+    };
+    let pending: Vec<(usize, Row)> = std::mem::take(&mut job.dataset.rows)
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !job.completed.contains(index))
+        .collect();
+
+    let mut results = stream::iter(pending)
+        .map(|(index, row)| {
+            let client = &client;
+            let config = &job.config;
+            let mut paused = job.paused.clone();
+            let cancelled = job.cancelled.clone();
+            let prompt = job.template.render(&row);
+            async move {
+                // Rows queued behind a pause wait here; requests already sent finish.
+                let _ = paused.wait_for(|paused| !paused).await;
+                if cancelled.load(Ordering::SeqCst) {
+                    return (index, row, None);
+                }
+                let result = complete(client, config, prompt).await;
+                (index, row, Some(result))
+            }
+        })
+        .buffer_unordered(job.config.concurrency);
+
+    let mut write_error = None;
+    while let Some((index, row, result)) = results.next().await {
+        if job.cancelled.load(Ordering::SeqCst) {
+            break;
+        }
+        let Some(result) = result else {
+            continue;
+        };
+        if let Err(e) = job.writer.write(index, &row, &result) {
+            write_error = Some(format!("Failed to write results: {}", e));
+            break;
+        }
+        {
+            let mut progress = job.progress.lock().unwrap();
+            progress.done += 1;
+            if result.is_err() {
+                progress.failed += 1;
+            }
+        }
+        emit_progress(&app, &job.progress);
+    }
+    drop(results);
+
+    // A job that failed to write stays resumable; finished and cancelled ones do not.
+    if write_error.is_none() {
+        if let Ok(path) = job_file(&app) {
+            let _ = fs::remove_file(path);
+        }
+    }
+    {
+        let mut progress = job.progress.lock().unwrap();
+        progress.running = false;
+        progress.paused = false;
+        progress.error = write_error;
+    }
+    emit_progress(&app, &job.progress);
+}
+
+#[tauri::command]
+pub async fn batch_preview(input_path: String) -> Result<BatchPreview, String> {
+    let dataset =
+        tauri::async_runtime::spawn_blocking(move || dataset::read(Path::new(&input_path)))
+            .await
+            .map_err(|e| e.to_string())??;
+    Ok(BatchPreview {
+        total: dataset.rows.len(),
+        sample: dataset.rows.into_iter().take(3).collect(),
+        columns: dataset.columns,
+    })
+}
+
+fn is_running(state: &BatchState) -> bool {
+    state
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|job| job.progress.lock().unwrap().running)
+}
+
+/// Reads the input and the results already written, and opens the output to append to.
+fn open_job(
+    config: &BatchConfig,
+) -> Result<(Dataset, Template, HashSet<usize>, OutputWriter), String> {
+    let dataset = dataset::read(Path::new(&config.input_path))?;
+    let template = Template::parse(&config.template)?;
+    template.check_columns(&dataset.columns)?;
+    let output_path = Path::new(&config.output_path);
+    let completed = dataset::completed_rows(output_path)?;
+    let writer = OutputWriter::open(output_path, &dataset.columns)?;
+    Ok((dataset, template, completed, writer))
+}
+
+/// Starts a job, or resumes one whose output file already holds some results.
+#[tauri::command]
+pub async fn batch_start(app: AppHandle, mut config: BatchConfig) -> Result<BatchProgress, String> {
+    if is_running(&app.state()) {
+        return Err("A batch job is already running".to_string());
+    }
+    if config.model.is_empty() {
+        return Err("Select a model first".to_string());
+    }
+    if Path::new(&config.input_path) == Path::new(&config.output_path) {
+        return Err("The output file must differ from the input file".to_string());
+    }
+    config.concurrency = config.concurrency.clamp(1, MAX_CONCURRENCY);
+
+    let (config, (dataset, template, completed, writer)) =
+        tauri::async_runtime::spawn_blocking(move || open_job(&config).map(|job| (config, job)))
+            .await
+            .map_err(|e| e.to_string())??;
+
+    let state = app.state::<BatchState>();
+    let mut current = state.0.lock().unwrap();
+    // Another start may have won while the files were read.
+    if current
+        .as_ref()
+        .is_some_and(|job| job.progress.lock().unwrap().running)
+    {
+        return Err("A batch job is already running".to_string());
+    }
+
+    let job_json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    fs::write(job_file(&app)?, job_json).map_err(|e| e.to_string())?;
+
+    let resumed = completed
+        .iter()
+        .filter(|i| **i < dataset.rows.len())
+        .count();
+    let progress = Arc::new(Mutex::new(BatchProgress {
+        total: dataset.rows.len(),
+        done: resumed,
+        resumed,
+        running: true,
+        output_path: config.output_path.clone(),
+        ..Default::default()
+    }));
+    let (paused_tx, paused_rx) = watch::channel(false);
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let job = Run {
+        config,
+        dataset,
+        template,
+        completed,
+        writer,
+        paused: paused_rx,
+        cancelled: cancelled.clone(),
+        progress: progress.clone(),
+    };
+    tauri::async_runtime::spawn(run(app.clone(), job));
+
+    let snapshot = progress.lock().unwrap().clone();
+    *current = Some(Job {
+        paused: paused_tx,
+        cancelled,
+        progress,
+    });
+    Ok(snapshot)
+}
+
+fn set_paused(app: &AppHandle, state: &BatchState, paused: bool) -> Result<(), String> {
+    let current = state.0.lock().unwrap();
+    let job = current
+        .as_ref()
+        .filter(|job| job.progress.lock().unwrap().running)
+        .ok_or_else(|| "No batch job is running".to_string())?;
+    job.paused.send_replace(paused);
+    job.progress.lock().unwrap().paused = paused;
+    emit_progress(app, &job.progress);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn batch_pause(app: AppHandle, state: State<'_, BatchState>) -> Result<(), String> {
+    set_paused(&app, &state, true)
+}
+
+#[tauri::command]
+pub fn batch_resume(app: AppHandle, state: State<'_, BatchState>) -> Result<(), String> {
+    set_paused(&app, &state, false)
+}
+
+/// Stops the job after the requests in flight. Results written so far are kept, so
+/// starting the same job again continues from there.
+#[tauri::command]
+pub fn batch_cancel(state: State<'_, BatchState>) -> Result<(), String> {
+    let current = state.0.lock().unwrap();
+    let job = current
+        .as_ref()
+        .ok_or_else(|| "No batch job is running".to_string())?;
+    job.cancelled.store(true, Ordering::SeqCst);
+    // Wake rows waiting behind a pause so they can see the cancellation.
+    job.paused.send_replace(false);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn batch_status(state: State<'_, BatchState>) -> Option<BatchProgress> {
+    let current = state.0.lock().unwrap();
+    current
+        .as_ref()
+        .map(|job| job.progress.lock().unwrap().clone())
+}
+
+/// The job that was running when the app last quit, if it never finished.
+#[tauri::command]
+pub fn batch_interrupted(app: AppHandle, state: State<'_, BatchState>) -> Option<BatchConfig> {
+    if state.0.lock().unwrap().is_some() {
+        return None;
+    }
+    let text = fs::read_to_string(job_file(&app).ok()?).ok()?;
+    serde_json::from_str(&text).ok()
+}
@@ -0,0 +1,107 @@
+use serde_json::{Map, Value};
+
+#[derive(Debug, PartialEq)]
+enum Part {
+    Text(String),
+    Column(String),
+}
+
+/// A prompt with `{{column}}` placeholders, filled in once per dataset row.
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| "Unclosed {{ in template".to_string())?;
+            let name = after[..end].trim();
+            if name.is_empty() {
+                return Err("Empty {{}} placeholder in template".to_string());
+            }
+            parts.push(Part::Column(name.to_string()));
+            rest = &after[end + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|p| match p {
+            Part::Column(name) => Some(name.as_str()),
+            Part::Text(_) => None,
+        })
+    }
+
+    /// Checks every placeholder names one of `columns`, so a typo fails before any
+    /// request is made rather than on every row.
+    pub fn check_columns(&self, columns: &[String]) -> Result<(), String> {
+        match self
+            .columns()
+            .find(|c| !columns.iter().any(|known| known == c))
+        {
+            Some(missing) => Err(format!(
+                "Unknown column \"{}\" in template; available: {}",
+                missing,
+                columns.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn render(&self, row: &Map<String, Value>) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Column(name) => match row.get(name) {
+                    Some(Value::String(s)) => out.push_str(s),
+                    Some(Value::Null) | None => {}
+                    Some(other) => out.push_str(&other.to_string()),
+                },
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_columns_from_a_row() {
+        let template = Template::parse("Classify: {{ subject }}\n{{body}} ({{priority}})").unwrap();
+        let row = json!({ "subject": "Login broken", "body": "Can't sign in", "priority": 2 });
+        assert_eq!(
+            template.render(row.as_object().unwrap()),
+            "Classify: Login broken\nCan't sign in (2)"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_placeholders() {
+        assert!(Template::parse("{{ subject").is_err());
+        assert!(Template::parse("{{}}").is_err());
+    }
+
+    #[test]
+    fn reports_unknown_columns() {
+        let template = Template::parse("{{subject}} {{sender}}").unwrap();
+        let columns = vec!["subject".to_string(), "body".to_string()];
+        let error = template.check_columns(&columns).unwrap_err();
+        assert!(error.contains("\"sender\""));
+    }
+}
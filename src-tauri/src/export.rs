@@ -0,0 +1,387 @@
+use printpdf::lopdf::Document;
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use serde::Deserialize;
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const PT_TO_MM: f32 = 0.3528;
+/// Stands in for characters the standard fonts have no glyph for.
+const MISSING_GLYPH: char = '?';
+
+#[derive(Deserialize)]
+pub struct ExportMessage {
+    pub role: String,
+    pub content: String,
+    pub stats: Option<String>,
+}
+
+/// A conversation as sent by the frontend: the header lines are already formatted
+/// so the HTML and PDF exports show the same thing.
+#[derive(Deserialize)]
+pub struct ExportDocument {
+    pub title: String,
+    pub header: Vec<String>,
+    pub messages: Vec<ExportMessage>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Style {
+    Title,
+    Meta,
+    Role,
+    Heading,
+    Body,
+    Code,
+    Stats,
+}
+
+impl Style {
+    fn size(self) -> f32 {
+        match self {
+            Style::Title => 18.0,
+            Style::Heading => 12.0,
+            Style::Body => 10.0,
+            Style::Code => 8.5,
+            Style::Meta => 9.0,
+            Style::Role | Style::Stats => 8.0,
+        }
+    }
+
+    fn line_height(self) -> f32 {
+        self.size() * 1.4 * PT_TO_MM
+    }
+
+    /// Characters that fit on a line. The standard fonts carry no metrics here, so this
+    /// assumes Helvetica's average of about half an em per character (Courier is 0.6).
+    fn max_chars(self) -> usize {
+        let em = if self == Style::Code { 0.6 } else { 0.5 };
+        ((PAGE_WIDTH - 2.0 * MARGIN) / PT_TO_MM / (self.size() * em)) as usize
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Block {
+    style: Style,
+    text: String,
+}
+
+/// Flattens Markdown into headings, paragraphs, list items and code blocks. Inline
+/// formatting is dropped; the standard fonts can't mix styles within a line anyway.
+fn markdown_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut lists: Vec<Option<u64>> = Vec::new();
+
+    fn flush(blocks: &mut Vec<Block>, current: &mut Option<Block>) {
+        if let Some(block) = current.take() {
+            let text = block.text.trim_end().to_string();
+            if !text.trim().is_empty() {
+                blocks.push(Block { text, ..block });
+            }
+        }
+    }
+    fn push(current: &mut Option<Block>, text: &str) {
+        current
+            .get_or_insert_with(|| Block {
+                style: Style::Body,
+                text: String::new(),
+            })
+            .text
+            .push_str(text);
+    }
+
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => {
+                flush(&mut blocks, &mut current);
+                current = Some(Block {
+                    style: Style::Heading,
+                    text: String::new(),
+                });
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                flush(&mut blocks, &mut current);
+                current = Some(Block {
+                    style: Style::Code,
+                    text: String::new(),
+                });
+            }
+            Event::Start(Tag::List(start)) => {
+                flush(&mut blocks, &mut current);
+                lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                flush(&mut blocks, &mut current);
+                let indent = "    ".repeat(lists.len().saturating_sub(1));
+                let marker = match lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "\u{2022} ".to_string(),
+                };
+                push(&mut current, &format!("{}{}", indent, marker));
+            }
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::Item
+                | TagEnd::TableHead
+                | TagEnd::TableRow,
+            ) => flush(&mut blocks, &mut current),
+            Event::End(TagEnd::TableCell) => push(&mut current, " | "),
+            Event::Text(text) | Event::Code(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                push(&mut current, &text)
+            }
+            Event::SoftBreak => push(&mut current, " "),
+            Event::HardBreak => push(&mut current, "\n"),
+            Event::TaskListMarker(done) => push(&mut current, if done { "[x] " } else { "[ ] " }),
+            Event::Rule => flush(&mut blocks, &mut current),
+            _ => {}
+        }
+    }
+    flush(&mut blocks, &mut current);
+
+    for block in blocks.iter_mut().filter(|b| b.text.ends_with(" |")) {
+        block.text.truncate(block.text.len() - 2);
+    }
+    blocks
+}
+
+/// Whether the standard fonts can show `c`. They only cover WinAnsi, roughly Latin-1,
+/// and printpdf silently drops anything else.
+fn has_glyph(c: char) -> bool {
+    let mut buf = [0; 4];
+    Document::encode_text(Some("WinAnsiEncoding"), c.encode_utf8(&mut buf)).len() == 1
+}
+
+/// `text` with tabs expanded and every character the fonts lack replaced by
+/// [`MISSING_GLYPH`], so nothing disappears from the page without a trace.
+fn printable(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' => out.push(c),
+            '\t' => out.push_str("    "),
+            c if has_glyph(c) => out.push(c),
+            _ => out.push(MISSING_GLYPH),
+        }
+    }
+    out
+}
+
+/// Breaks `text` into lines of at most `max_chars`. Code keeps its indentation and is
+/// cut mid-line; prose wraps at spaces.
+fn wrap(text: &str, max_chars: usize, code: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source in text.lines() {
+        if code {
+            let chars: Vec<char> = source.chars().collect();
+            if chars.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(chars.chunks(max_chars).map(|c| c.iter().collect()));
+            continue;
+        }
+        let mut line = String::new();
+        for word in source.split(' ').filter(|w| !w.is_empty()) {
+            let mut word: Vec<char> = word.chars().collect();
+            if !line.is_empty() && line.chars().count() + 1 + word.len() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            while word.len() > max_chars {
+                lines.push(word.drain(..max_chars).collect());
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    y: f32,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    mono: IndirectFontRef,
+}
+
+impl Writer {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+        let font = |f| doc.add_builtin_font(f).map_err(|e| e.to_string());
+        let (regular, bold, mono) = (
+            font(BuiltinFont::Helvetica)?,
+            font(BuiltinFont::HelveticaBold)?,
+            font(BuiltinFont::Courier)?,
+        );
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Self {
+            doc,
+            layer,
+            y: PAGE_HEIGHT - MARGIN,
+            regular,
+            bold,
+            mono,
+        })
+    }
+
+    fn write(&mut self, style: Style, text: &str) {
+        let font = match style {
+            Style::Title | Style::Role | Style::Heading => &self.bold,
+            Style::Code => &self.mono,
+            _ => &self.regular,
+        };
+        let text = printable(text);
+        for line in wrap(&text, style.max_chars(), style == Style::Code) {
+            if self.y - style.line_height() < MARGIN {
+                let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+                self.layer = self.doc.get_page(page).get_layer(layer);
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= style.line_height();
+            self.layer
+                .use_text(line, style.size(), Mm(MARGIN), Mm(self.y), font);
+        }
+    }
+
+    fn gap(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+}
+
+fn render(document: &ExportDocument) -> Result<Vec<u8>, String> {
+    let mut writer = Writer::new(&document.title)?;
+    writer.write(Style::Title, &document.title);
+    writer.gap(2.0);
+    for line in &document.header {
+        writer.write(Style::Meta, line);
+    }
+    let text = std::iter::once(&document.title)
+        .chain(&document.header)
+        .chain(document.messages.iter().map(|m| &m.content));
+    if text
+        .flat_map(|t| t.chars())
+        .any(|c| !c.is_whitespace() && !has_glyph(c))
+    {
+        writer.write(
+            Style::Meta,
+            "Characters the PDF fonts can't show are marked ?; the HTML export keeps them.",
+        );
+    }
+
+    for message in &document.messages {
+        writer.gap(6.0);
+        writer.write(Style::Role, &message.role.to_uppercase());
+        for block in markdown_blocks(&message.content) {
+            writer.gap(1.5);
+            writer.write(block.style, &block.text);
+        }
+        if let Some(stats) = &message.stats {
+            writer.gap(1.0);
+            writer.write(Style::Stats, stats);
+        }
+    }
+    writer.doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+/// Lays the conversation out as an A4 PDF and returns the file's bytes.
+#[tauri::command]
+pub async fn export_pdf(document: ExportDocument) -> Result<Vec<u8>, String> {
+    render(&document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_is_split_into_blocks() {
+        let blocks = markdown_blocks("# Plan\n\nSome *text*\nhere.\n\n1. one\n2. two\n\n- [x] done\n\n```rust\nfn main() {\n    run();\n}\n```");
+        let texts: Vec<(Style, &str)> = blocks.iter().map(|b| (b.style, b.text.as_str())).collect();
+        assert_eq!(
+            texts,
+            vec![
+                (Style::Heading, "Plan"),
+                (Style::Body, "Some text here."),
+                (Style::Body, "1. one"),
+                (Style::Body, "2. two"),
+                (Style::Body, "\u{2022} [x] done"),
+                (Style::Code, "fn main() {\n    run();\n}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_become_rows() {
+        let blocks = markdown_blocks("| a | b |\n|---|---|\n| 1 | 2 |");
+        let texts: Vec<&str> = blocks.iter().map(|b| b.text.as_str()).collect();
+        assert_eq!(texts, vec!["a | b", "1 | 2"]);
+    }
+
+    #[test]
+    fn prose_wraps_at_spaces_and_code_keeps_indentation() {
+        assert_eq!(wrap("aaa bbb ccc", 7, false), vec!["aaa bbb", "ccc"]);
+        assert_eq!(wrap("abcdefghij", 4, false), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(
+            wrap("    let x = 1;\n\n}", 8, true),
+            vec!["    let ", "x = 1;", "", "}"]
+        );
+    }
+
+    #[test]
+    fn characters_without_glyphs_are_marked() {
+        assert_eq!(printable("café – “ok” €5"), "café – “ok” €5");
+        assert_eq!(printable("Привет, 世界 👋"), "??????, ?? ?");
+        assert_eq!(printable("\tx\ny"), "    x\ny");
+
+        let document = ExportDocument {
+            title: "Перевод".to_string(),
+            header: Vec::new(),
+            messages: vec![ExportMessage {
+                role: "assistant".to_string(),
+                content: "你好 means hello".to_string(),
+                stats: None,
+            }],
+        };
+        let pdf = printpdf::lopdf::Document::load_mem(&render(&document).unwrap()).unwrap();
+        let text = pdf.extract_text(&[1]).unwrap();
+        assert!(text.contains("???????"), "{}", text);
+        assert!(text.contains("?? means hello"), "{}", text);
+        assert!(text.contains("marked ?"), "{}", text);
+    }
+
+    #[test]
+    fn renders_long_conversations_across_pages() {
+        let document = ExportDocument {
+            title: "Test".to_string(),
+            header: vec!["Model: llama3.2".to_string()],
+            messages: (0..80)
+                .map(|i| ExportMessage {
+                    role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
+                    content: format!("Message {} with **markdown** and `code`.", i),
+                    stats: None,
+                })
+                .collect(),
+        };
+        let bytes = render(&document).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+        let pdf = printpdf::lopdf::Document::load_mem(&bytes).unwrap();
+        assert!(pdf.get_pages().len() > 1);
+    }
+}
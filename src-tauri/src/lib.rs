@@ -1,3 +1,9 @@
+mod batch;
+mod export;
+mod media;
+mod memory;
+mod notifications;
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -8,7 +14,57 @@ fn greet(name: &str) -> String {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .manage(notifications::PendingConversation::default())
+        .manage(batch::BatchState::default())
+        .manage(memory::MemoryState::default())
+        .manage(media::MediaScope::default())
+        .manage(media::recent::RecentState::default())
+        .manage(media::library::LibraryState::default())
+        .manage(media::frames::FrameFolder::default())
+        .register_asynchronous_uri_scheme_protocol("media", media::protocol::handle)
+        .on_window_event(|window, event| {
+            notifications::on_window_event(window, event);
+            media::on_window_event(window, event);
+        })
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            notifications::notify_reply_ready,
+            batch::batch_preview,
+            batch::batch_start,
+            batch::batch_pause,
+            batch::batch_resume,
+            batch::batch_cancel,
+            batch::batch_status,
+            batch::batch_interrupted,
+            export::export_pdf,
+            memory::memory_list,
+            memory::memory_add,
+            memory::memory_update,
+            memory::memory_delete,
+            media::playlist::playlist_save,
+            media::playlist::playlist_open,
+            media::media_open_dialog,
+            media::recent::media_recent_list,
+            media::recent::media_recent_add,
+            media::recent::media_recent_position,
+            media::recent::media_recent_clear,
+            media::library::library_get,
+            media::library::library_add_folder,
+            media::library::library_remove_folder,
+            media::library::library_refresh,
+            media::edit::image_edit_preview,
+            media::edit::image_edit_save,
+            media::metadata::media_metadata,
+            media::metadata::media_strip_metadata,
+            media::frames::frames_folder,
+            media::frames::frames_choose_folder,
+            media::frames::frames_save,
+            media::subtitles::subtitles_find,
+            media::subtitles::subtitles_open_dialog,
+            media::subtitles::subtitles_read
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,374 @@
+//! Crop, rotate, flip, resize and convert an image. The original is only ever read:
+//! previews go to the app cache dir, and results to wherever the user saves them.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use super::metadata::open_upright;
+use super::MediaScope;
+
+const PREVIEW_DIR: &str = "edit-preview";
+/// The largest width or height an image may be resized to.
+const MAX_SIDE: u32 = 16384;
+
+static NEXT_PREVIEW: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Applied in order: crop, in the original's pixels; turn clockwise; flip; resize.
+#[derive(Clone, Default, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Edits {
+    pub crop: Option<Rect>,
+    pub quarter_turns: u8,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub resize: Option<Dimensions>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum Export {
+    Png,
+    /// `quality` runs from 1 to 100.
+    Jpeg {
+        quality: u8,
+    },
+    /// Lossless.
+    Webp,
+}
+
+impl Export {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg { .. } => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+
+    fn filter_name(self) -> &'static str {
+        match self {
+            Self::Png => "PNG image",
+            Self::Jpeg { .. } => "JPEG image",
+            Self::Webp => "WebP image",
+        }
+    }
+}
+
+/// An edited image written for the viewer to show.
+#[derive(Serialize)]
+pub struct Preview {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// Bytes once encoded, as the saved file would be.
+    pub size: u64,
+}
+
+pub fn apply(image: DynamicImage, edits: &Edits) -> Result<DynamicImage, String> {
+    let mut image = image;
+    if let Some(crop) = edits.crop {
+        let fits = crop
+            .x
+            .checked_add(crop.width)
+            .is_some_and(|r| r <= image.width())
+            && crop
+                .y
+                .checked_add(crop.height)
+                .is_some_and(|b| b <= image.height());
+        if crop.width == 0 || crop.height == 0 || !fits {
+            return Err(format!(
+                "The crop {}×{} at {}, {} doesn't fit in the {}×{} image",
+                crop.width,
+                crop.height,
+                crop.x,
+                crop.y,
+                image.width(),
+                image.height()
+            ));
+        }
+        image = image.crop_imm(crop.x, crop.y, crop.width, crop.height);
+    }
+    image = match edits.quarter_turns % 4 {
+        1 => image.rotate90(),
+        2 => image.rotate180(),
+        3 => image.rotate270(),
+        _ => image,
+    };
+    if edits.flip_x {
+        image = image.fliph();
+    }
+    if edits.flip_y {
+        image = image.flipv();
+    }
+    if let Some(Dimensions { width, height }) = edits.resize {
+        if width == 0 || height == 0 || width > MAX_SIDE || height > MAX_SIDE {
+            return Err(format!(
+                "Images can be resized to between 1 and {} pixels a side",
+                MAX_SIDE
+            ));
+        }
+        if (width, height) != (image.width(), image.height()) {
+            image = image.resize_exact(width, height, FilterType::Lanczos3);
+        }
+    }
+    Ok(image)
+}
+
+pub fn encode(image: &DynamicImage, export: Export) -> Result<Vec<u8>, String> {
+    let mut out = Cursor::new(Vec::new());
+    let result = match export {
+        Export::Png => image.write_with_encoder(PngEncoder::new(&mut out)),
+        // JPEG has no alpha, so transparency is flattened first.
+        Export::Jpeg { quality } => {
+            let rgb = image.to_rgb8();
+            JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100)).write_image(
+                rgb.as_raw(),
+                rgb.width(),
+                rgb.height(),
+                image::ExtendedColorType::Rgb8,
+            )
+        }
+        Export::Webp => {
+            let rgba = image.to_rgba8();
+            WebPEncoder::new_lossless(&mut out).write_image(
+                rgba.as_raw(),
+                rgba.width(),
+                rgba.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+        }
+    };
+    result.map_err(|e| e.to_string())?;
+    Ok(out.into_inner())
+}
+
+/// Reads `source`, which must be a file the player was allowed to open, and edits it.
+fn edited(app: &AppHandle, source: &str, edits: &Edits) -> Result<(PathBuf, DynamicImage), String> {
+    let path = app
+        .state::<MediaScope>()
+        .resolve(Path::new(source))
+        .ok_or_else(|| format!("{} hasn't been opened in the app", source))?;
+    // Upright, as the viewer shows it, so crops line up with what was chosen there.
+    let image = open_upright(&path).map_err(|e| format!("Couldn't read {}: {}", source, e))?;
+    Ok((path, apply(image, edits)?))
+}
+
+/// Renders `edits` to a file in the cache dir, replacing the previous preview. Each
+/// gets a new name, so the webview never shows a stale one.
+#[tauri::command]
+pub async fn image_edit_preview(
+    app: AppHandle,
+    source: String,
+    edits: Edits,
+    export: Export,
+) -> Result<Preview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (_, image) = edited(&app, &source, &edits)?;
+        let bytes = encode(&image, export)?;
+
+        let dir = app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| e.to_string())?
+            .join(PREVIEW_DIR);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let id = NEXT_PREVIEW.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("preview-{}.{}", id, export.extension()));
+        fs::write(&path, &bytes).map_err(|e| e.to_string())?;
+        app.state::<MediaScope>()
+            .allow_file(&path)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Preview {
+            path: path.to_string_lossy().into_owned(),
+            width: image.width(),
+            height: image.height(),
+            size: bytes.len() as u64,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Asks where to save the edited image and writes it there. Returns the saved path,
+/// or `None` if the dialog was cancelled. The original can't be chosen.
+#[tauri::command]
+pub async fn image_edit_save(
+    app: AppHandle,
+    source: String,
+    edits: Edits,
+    export: Export,
+) -> Result<Option<String>, String> {
+    let stem = Path::new(&source)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+    let mut dialog = app
+        .dialog()
+        .file()
+        .add_filter(export.filter_name(), &[export.extension()])
+        .set_file_name(format!("{}-edited.{}", stem, export.extension()));
+    if let Some(folder) = Path::new(&source).parent() {
+        dialog = dialog.set_directory(folder);
+    }
+    let Some(chosen) = dialog.blocking_save_file() else {
+        return Ok(None);
+    };
+    let target: PathBuf = chosen.into_path().map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (original, image) = edited(&app, &source, &edits)?;
+        if target.canonicalize().is_ok_and(|target| target == original) {
+            return Err("Choose another name; the original is never overwritten".to_string());
+        }
+        let bytes = encode(&image, export)?;
+        fs::write(&target, bytes)
+            .map_err(|e| format!("Couldn't save {}: {}", target.display(), e))?;
+        app.state::<MediaScope>()
+            .allow_file(&target)
+            .map_err(|e| e.to_string())?;
+        Ok(Some(target.to_string_lossy().into_owned()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Rgba, RgbaImage};
+
+    /// A 4×2 image whose pixels are numbered left to right, top to bottom.
+    fn numbered() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 2, |x, y| {
+            Rgba([(y * 4 + x) as u8, 0, 0, 255])
+        }))
+    }
+
+    fn numbers(image: &DynamicImage) -> Vec<Vec<u8>> {
+        (0..image.height())
+            .map(|y| {
+                (0..image.width())
+                    .map(|x| image.get_pixel(x, y)[0])
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn edits_crop_then_turn_then_flip() {
+        let crop = Rect {
+            x: 1,
+            y: 0,
+            width: 2,
+            height: 2,
+        };
+        let cropped = apply(
+            numbered(),
+            &Edits {
+                crop: Some(crop),
+                ..Edits::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(numbers(&cropped), vec![vec![1, 2], vec![5, 6]]);
+
+        let turned = Edits {
+            quarter_turns: 1,
+            ..Edits::default()
+        };
+        assert_eq!(
+            numbers(&apply(numbered(), &turned).unwrap()),
+            vec![vec![4, 0], vec![5, 1], vec![6, 2], vec![7, 3]]
+        );
+
+        let flipped = Edits {
+            flip_x: true,
+            flip_y: true,
+            ..Edits::default()
+        };
+        assert_eq!(
+            numbers(&apply(numbered(), &flipped).unwrap()),
+            vec![vec![7, 6, 5, 4], vec![3, 2, 1, 0]]
+        );
+    }
+
+    #[test]
+    fn bad_crops_and_sizes_are_refused() {
+        let outside = Edits {
+            crop: Some(Rect {
+                x: 3,
+                y: 0,
+                width: 2,
+                height: 1,
+            }),
+            ..Edits::default()
+        };
+        assert!(apply(numbered(), &outside)
+            .unwrap_err()
+            .contains("doesn't fit"));
+        let empty = Edits {
+            resize: Some(Dimensions {
+                width: 0,
+                height: 4,
+            }),
+            ..Edits::default()
+        };
+        assert!(apply(numbered(), &empty).is_err());
+
+        let resized = Edits {
+            resize: Some(Dimensions {
+                width: 8,
+                height: 3,
+            }),
+            ..Edits::default()
+        };
+        assert_eq!(apply(numbered(), &resized).unwrap().dimensions(), (8, 3));
+    }
+
+    #[test]
+    fn exports_decode_as_their_format() {
+        let image = numbered();
+        for (export, format) in [
+            (Export::Png, image::ImageFormat::Png),
+            (Export::Jpeg { quality: 80 }, image::ImageFormat::Jpeg),
+            (Export::Webp, image::ImageFormat::WebP),
+        ] {
+            let bytes = encode(&image, export).unwrap();
+            assert_eq!(image::guess_format(&bytes).unwrap(), format);
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!(decoded.dimensions(), (4, 2));
+        }
+        let lossless = image::load_from_memory(&encode(&image, Export::Webp).unwrap()).unwrap();
+        assert_eq!(numbers(&lossless), numbers(&image));
+
+        let export: Export = serde_json::from_str(r#"{"format": "jpeg", "quality": 60}"#).unwrap();
+        assert_eq!(export, Export::Jpeg { quality: 60 });
+    }
+}
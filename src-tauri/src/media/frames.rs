@@ -0,0 +1,138 @@
+//! Still frames captured from the video player. The webview draws each frame and
+//! sends it here as a PNG, which is written to a folder the user picks once per
+//! session, named after the video and the moment captured.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+use super::MediaScope;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Where captured frames go, once chosen.
+#[derive(Default)]
+pub struct FrameFolder(Mutex<Option<PathBuf>>);
+
+/// E.g. `holiday_00-01-23.450.png` for 83.45 seconds into `holiday.mp4`. Colons
+/// aren't allowed in Windows file names, so the parts of the time are dashed.
+pub fn frame_file_name(video_name: &str, seconds: f64) -> String {
+    let stem = Path::new(video_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "frame".to_string());
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{}_{:02}-{:02}-{:02}.{:03}.png",
+        stem,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// `dir/name`, numbered so an earlier capture of the same moment isn't overwritten.
+fn unused_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+    let stem = name.strip_suffix(".png").unwrap_or(name);
+    (2..)
+        .map(|n| dir.join(format!("{} ({}).png", stem, n)))
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+#[tauri::command]
+pub fn frames_folder(state: State<'_, FrameFolder>) -> Option<String> {
+    let folder = state.0.lock().unwrap();
+    folder.as_ref().map(|f| f.to_string_lossy().into_owned())
+}
+
+/// Asks for the folder to save frames in. Returns the folder now in use, which is
+/// the previous one if the dialog was cancelled.
+#[tauri::command]
+pub async fn frames_choose_folder(app: AppHandle) -> Result<Option<String>, String> {
+    if let Some(chosen) = app.dialog().file().blocking_pick_folder() {
+        let folder = chosen.into_path().map_err(|e| e.to_string())?;
+        // So saved frames can be opened in the viewer.
+        app.state::<MediaScope>()
+            .allow_dir(&folder)
+            .map_err(|e| e.to_string())?;
+        *app.state::<FrameFolder>().0.lock().unwrap() = Some(folder);
+    }
+    Ok(frames_folder(app.state()))
+}
+
+/// Writes a frame of `name` captured at `time` seconds, given as base64 PNG data,
+/// to the chosen folder. Returns the saved path.
+#[tauri::command]
+pub async fn frames_save(
+    app: AppHandle,
+    name: String,
+    time: f64,
+    png: String,
+) -> Result<String, String> {
+    let folder = app
+        .state::<FrameFolder>()
+        .0
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("Choose a folder for frames first")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = STANDARD
+            .decode(png)
+            .map_err(|e| format!("The frame isn't valid base64: {}", e))?;
+        if !data.starts_with(PNG_SIGNATURE) {
+            return Err("The frame isn't a PNG".to_string());
+        }
+        fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+        let path = unused_path(&folder, &frame_file_name(&name, time));
+        fs::write(&path, data).map_err(|e| format!("Couldn't save {}: {}", path.display(), e))?;
+        Ok(path.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_named_after_the_video_and_time() {
+        assert_eq!(
+            frame_file_name("holiday.mp4", 83.45),
+            "holiday_00-01-23.450.png"
+        );
+        assert_eq!(
+            frame_file_name("talk.final.webm", 3.0 * 3600.0 + 5.0),
+            "talk.final_03-00-05.000.png"
+        );
+        assert_eq!(frame_file_name("", -1.0), "frame_00-00-00.000.png");
+    }
+
+    #[test]
+    fn earlier_captures_are_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("frames-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let name = "clip_00-00-01.000.png";
+        assert_eq!(unused_path(&dir, name), dir.join(name));
+        fs::write(dir.join(name), b"").unwrap();
+        fs::write(dir.join("clip_00-00-01.000 (2).png"), b"").unwrap();
+        assert_eq!(
+            unused_path(&dir, name),
+            dir.join("clip_00-00-01.000 (3).png")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,336 @@
+//! The media library: every image and video under the folders the user added, with
+//! what the library page shows and sorts by. The index is kept in the app data dir,
+//! and image thumbnails in the app cache dir, so a refresh only reads files that
+//! changed since the last one.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use super::metadata::open_upright;
+use super::probe::{self, MediaKind};
+use super::MediaScope;
+
+const LIBRARY_FILE: &str = "media-library.json";
+const THUMBNAIL_DIR: &str = "thumbnails";
+/// The longest side of a thumbnail, in pixels.
+const THUMBNAIL_SIZE: u32 = 320;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    pub path: String,
+    pub kind: MediaKind,
+    /// Bytes.
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub modified: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Seconds, for videos whose container says.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    /// A small JPEG of an image, served like the media itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Library {
+    pub folders: Vec<String>,
+    pub entries: Vec<LibraryEntry>,
+}
+
+/// The library, read from disk on first use.
+#[derive(Default)]
+pub struct LibraryState(Mutex<Option<Library>>);
+
+fn library_file(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(LIBRARY_FILE))
+}
+
+fn thumbnail_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(THUMBNAIL_DIR);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Every image and video under `dir`, at any depth. Hidden folders and symlinks are
+/// skipped, the latter so a link can't lead out of the folder or round in a loop.
+fn media_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if !entry.file_name().to_string_lossy().starts_with('.') {
+                media_files(&path, found);
+            }
+        } else if file_type.is_file() && MediaKind::from_path(&path).is_some() {
+            found.push(path);
+        }
+    }
+}
+
+/// Where the thumbnail of a file with this path, size and modification time is kept.
+/// A changed file gets a new name, so a stale thumbnail is never shown.
+fn thumbnail_path(dir: &Path, path: &Path, size: u64, modified: u64) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    (path, size, modified).hash(&mut hasher);
+    dir.join(format!("{:016x}.jpg", hasher.finish()))
+}
+
+fn make_thumbnail(source: &Path, target: &Path) -> Result<(), String> {
+    if target.is_file() {
+        return Ok(());
+    }
+    let image = open_upright(source)?;
+    // JPEG has no alpha, so transparency is flattened first.
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).into_rgb8();
+    thumbnail.save(target).map_err(|e| e.to_string())
+}
+
+/// Indexes `folders`, reusing what `previous` knew about files that haven't changed,
+/// and deletes thumbnails nothing refers to any more.
+fn scan(folders: &[String], previous: &[LibraryEntry], thumbnails: &Path) -> Vec<LibraryEntry> {
+    let known: HashMap<&str, &LibraryEntry> =
+        previous.iter().map(|e| (e.path.as_str(), e)).collect();
+    let mut paths = Vec::new();
+    for folder in folders {
+        media_files(Path::new(folder), &mut paths);
+    }
+    paths.sort();
+    paths.dedup();
+
+    let mut entries = Vec::with_capacity(paths.len());
+    for path in paths {
+        let (Some(kind), Ok(metadata)) = (MediaKind::from_path(&path), fs::metadata(&path)) else {
+            continue;
+        };
+        let size = metadata.len();
+        let modified = metadata.modified().map(seconds).unwrap_or_default();
+        let path_string = path.to_string_lossy().into_owned();
+
+        let unchanged = known
+            .get(path_string.as_str())
+            .filter(|e| e.size == size && e.modified == modified)
+            .filter(|e| e.thumbnail.as_ref().is_none_or(|t| Path::new(t).is_file()));
+        if let Some(entry) = unchanged {
+            entries.push((*entry).clone());
+            continue;
+        }
+
+        let probed = probe::probe(&path, kind);
+        let thumbnail = (kind == MediaKind::Image)
+            .then(|| thumbnail_path(thumbnails, &path, size, modified))
+            .filter(|target| match make_thumbnail(&path, target) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("No thumbnail for {}: {}", path.display(), e);
+                    false
+                }
+            });
+        entries.push(LibraryEntry {
+            path: path_string,
+            kind,
+            size,
+            modified,
+            width: probed.width,
+            height: probed.height,
+            duration: probed.duration,
+            thumbnail: thumbnail.map(|t| t.to_string_lossy().into_owned()),
+        });
+    }
+
+    let used: HashSet<&str> = entries
+        .iter()
+        .filter_map(|e| e.thumbnail.as_deref())
+        .collect();
+    if let Ok(cached) = fs::read_dir(thumbnails) {
+        for file in cached.flatten() {
+            if !used.contains(file.path().to_string_lossy().as_ref()) {
+                let _ = fs::remove_file(file.path());
+            }
+        }
+    }
+    entries
+}
+
+fn load(app: &AppHandle) -> Result<Library, String> {
+    let state = app.state::<LibraryState>();
+    let mut slot = state.0.lock().unwrap();
+    if slot.is_none() {
+        // A damaged index isn't worth failing over; it's rebuilt on the next refresh.
+        let library = fs::read_to_string(library_file(app)?)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        *slot = Some(library);
+    }
+    Ok(slot.clone().unwrap())
+}
+
+/// Lets the webview load the library's files and thumbnails.
+fn allow(app: &AppHandle, library: &Library) -> Result<(), String> {
+    let scope = app.state::<MediaScope>();
+    for folder in &library.folders {
+        if let Err(e) = scope.allow_dir(folder) {
+            log::warn!("Failed to allow {}: {}", folder, e);
+        }
+    }
+    scope
+        .allow_dir(thumbnail_dir(app)?)
+        .map_err(|e| e.to_string())
+}
+
+/// Indexes `folders` again and saves the result. Reading files can take a while the
+/// first time, so it happens off the async runtime.
+async fn reindex(app: AppHandle, folders: Vec<String>) -> Result<Library, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let previous = load(&app)?;
+        let thumbnails = thumbnail_dir(&app)?;
+        let library = Library {
+            entries: scan(&folders, &previous.entries, &thumbnails),
+            folders,
+        };
+        let json = serde_json::to_string_pretty(&library).map_err(|e| e.to_string())?;
+        fs::write(library_file(&app)?, json).map_err(|e| e.to_string())?;
+        *app.state::<LibraryState>().0.lock().unwrap() = Some(library.clone());
+        allow(&app, &library)?;
+        Ok(library)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The library as last indexed.
+#[tauri::command]
+pub fn library_get(app: AppHandle) -> Result<Library, String> {
+    let library = load(&app)?;
+    allow(&app, &library)?;
+    Ok(library)
+}
+
+/// Asks for a folder to add and indexes it. The library is returned unchanged if the
+/// dialog was cancelled.
+#[tauri::command]
+pub async fn library_add_folder(app: AppHandle) -> Result<Library, String> {
+    let Some(chosen) = app.dialog().file().blocking_pick_folder() else {
+        return load(&app);
+    };
+    let folder = chosen.into_path().map_err(|e| e.to_string())?;
+    let folder = folder.to_string_lossy().into_owned();
+    let mut folders = load(&app)?.folders;
+    if !folders.contains(&folder) {
+        folders.push(folder);
+    }
+    reindex(app, folders).await
+}
+
+#[tauri::command]
+pub async fn library_remove_folder(app: AppHandle, folder: String) -> Result<Library, String> {
+    let mut folders = load(&app)?.folders;
+    folders.retain(|f| *f != folder);
+    reindex(app, folders).await
+}
+
+/// Picks up files added, changed or deleted since the last index.
+#[tauri::command]
+pub async fn library_refresh(app: AppHandle) -> Result<Library, String> {
+    let folders = load(&app)?.folders;
+    reindex(app, folders).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("media-library-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn folders_are_indexed_recursively_with_thumbnails() {
+        let dir = temp_dir("scan");
+        let thumbnails = dir.join("thumbs");
+        fs::create_dir_all(dir.join("shots/2024")).unwrap();
+        fs::create_dir_all(dir.join(".cache")).unwrap();
+        fs::create_dir_all(&thumbnails).unwrap();
+        image::RgbaImage::new(640, 200)
+            .save(dir.join("shots/2024/wide.png"))
+            .unwrap();
+        fs::write(dir.join("shots/clip.webm"), b"not really a video").unwrap();
+        fs::write(dir.join("shots/notes.txt"), b"skip me").unwrap();
+        fs::write(dir.join(".cache/hidden.png"), b"skip me").unwrap();
+        fs::write(thumbnails.join("stale.jpg"), b"old").unwrap();
+        let folders = vec![dir.join("shots").to_string_lossy().into_owned()];
+
+        let entries = scan(&folders, &[], &thumbnails);
+        assert_eq!(entries.len(), 2);
+        let image = &entries[0];
+        assert!(image.path.ends_with("wide.png"));
+        assert_eq!(
+            (image.kind, image.width, image.height),
+            (MediaKind::Image, Some(640), Some(200))
+        );
+        let thumbnail = image::open(image.thumbnail.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            (thumbnail.width(), thumbnail.height()),
+            (THUMBNAIL_SIZE, 100)
+        );
+        let video = &entries[1];
+        assert_eq!(
+            (video.kind, video.size, video.duration),
+            (MediaKind::Video, 18, None)
+        );
+        assert!(video.thumbnail.is_none());
+        assert!(!thumbnails.join("stale.jpg").exists());
+
+        // Unchanged files are taken from the previous index rather than read again.
+        let mut previous = entries.clone();
+        previous[1].duration = Some(3.0);
+        assert_eq!(
+            scan(&folders, &previous, &thumbnails)[1].duration,
+            Some(3.0)
+        );
+        previous[1].size = 1;
+        assert_eq!(scan(&folders, &previous, &thumbnails)[1].duration, None);
+
+        fs::remove_file(dir.join("shots/2024/wide.png")).unwrap();
+        let entries = scan(&folders, &entries, &thumbnails);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(fs::read_dir(&thumbnails).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,545 @@
+//! What's recorded about a media file beyond its pixels: EXIF for photos, container
+//! details for videos. Also writes copies of images with that metadata removed.
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use exif::{Exif, In, Tag, Value};
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use super::edit::{encode, Export};
+use super::probe::{probe, MediaKind, Track};
+use super::MediaScope;
+
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Gps {
+    /// Degrees, north positive.
+    pub latitude: f64,
+    /// Degrees, east positive.
+    pub longitude: f64,
+    /// Metres above sea level.
+    pub altitude: Option<f64>,
+}
+
+/// The EXIF fields worth showing. Anything missing from the file is `None`.
+#[derive(Clone, Default, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Camera {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    /// As written, e.g. `2024-05-01 18:30:12`.
+    pub taken: Option<String>,
+    /// Seconds.
+    pub exposure_time: Option<f64>,
+    pub f_number: Option<f64>,
+    pub iso: Option<u32>,
+    /// Millimetres.
+    pub focal_length: Option<f64>,
+    pub gps: Option<Gps>,
+    /// The EXIF orientation, 1 to 8. 1 is upright; the rest say how the pixels must
+    /// be turned and mirrored to be.
+    pub orientation: u16,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Metadata {
+    #[serde(rename_all = "camelCase")]
+    Image {
+        format: Option<&'static str>,
+        /// As displayed, after the orientation is applied.
+        width: Option<u32>,
+        height: Option<u32>,
+        camera: Option<Camera>,
+        /// Whether a copy without metadata can be saved.
+        strippable: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Video {
+        container: Option<&'static str>,
+        duration: Option<f64>,
+        width: Option<u32>,
+        height: Option<u32>,
+        frame_rate: Option<f64>,
+        tracks: Vec<Track>,
+    },
+}
+
+fn format_name(format: ImageFormat) -> Option<&'static str> {
+    Some(match format {
+        ImageFormat::Png => "PNG",
+        ImageFormat::Jpeg => "JPEG",
+        ImageFormat::WebP => "WebP",
+        ImageFormat::Gif => "GIF",
+        ImageFormat::Bmp => "BMP",
+        ImageFormat::Avif => "AVIF",
+        ImageFormat::Tiff => "TIFF",
+        _ => return None,
+    })
+}
+
+fn text(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(parts) => {
+            let text = String::from_utf8_lossy(parts.first()?).trim().to_string();
+            (!text.is_empty()).then_some(text)
+        }
+        _ => None,
+    }
+}
+
+fn number(exif: &Exif, tag: Tag) -> Option<f64> {
+    let value = match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) => values.first()?.to_f64(),
+        Value::SRational(values) => values.first()?.to_f64(),
+        value => value.get_uint(0)? as f64,
+    };
+    value.is_finite().then_some(value)
+}
+
+/// Degrees from three rationals of degrees, minutes and seconds, made negative by a
+/// reference of `S` or `W`.
+fn coordinate(exif: &Exif, tag: Tag, reference: Tag) -> Option<f64> {
+    let Value::Rational(dms) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+    let degrees = dms
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(part, unit)| part.to_f64() / unit)
+        .sum::<f64>();
+    let negative = matches!(text(exif, reference).as_deref(), Some("S" | "W"));
+    degrees
+        .is_finite()
+        .then_some(if negative { -degrees } else { degrees })
+}
+
+fn camera(exif: &Exif) -> Camera {
+    let gps = coordinate(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef)
+        .zip(coordinate(exif, Tag::GPSLongitude, Tag::GPSLongitudeRef))
+        .map(|(latitude, longitude)| {
+            let below_sea = exif
+                .get_field(Tag::GPSAltitudeRef, In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+                == Some(1);
+            Gps {
+                latitude,
+                longitude,
+                altitude: number(exif, Tag::GPSAltitude).map(|a| if below_sea { -a } else { a }),
+            }
+        });
+    let lens = match (text(exif, Tag::LensMake), text(exif, Tag::LensModel)) {
+        (Some(make), Some(model)) if !model.starts_with(&make) => Some(format!("{make} {model}")),
+        (make, model) => model.or(make),
+    };
+    Camera {
+        make: text(exif, Tag::Make),
+        model: text(exif, Tag::Model),
+        lens,
+        // EXIF writes dates as `2024:05:01 18:30:12`.
+        taken: text(exif, Tag::DateTimeOriginal)
+            .or_else(|| text(exif, Tag::DateTime))
+            .map(|taken| taken.replacen(':', "-", 2)),
+        exposure_time: number(exif, Tag::ExposureTime),
+        f_number: number(exif, Tag::FNumber),
+        iso: number(exif, Tag::PhotographicSensitivity).map(|iso| iso as u32),
+        focal_length: number(exif, Tag::FocalLength),
+        gps,
+        orientation: exif
+            .get_field(Tag::Orientation, In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0))
+            .filter(|o| (1..=8).contains(o))
+            .map_or(1, |o| o as u16),
+    }
+}
+
+fn read_exif(path: &Path) -> Option<Exif> {
+    let file = File::open(path).ok()?;
+    exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()
+}
+
+/// The EXIF orientation of the image at `path`; 1 if it has none.
+pub fn orientation(path: &Path) -> u16 {
+    read_exif(path).map_or(1, |exif| camera(&exif).orientation)
+}
+
+/// Decodes the image at `path` and turns it the way its EXIF orientation says, so it
+/// matches what the webview shows.
+pub fn open_upright(path: &Path) -> Result<DynamicImage, String> {
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| e.to_string())?
+        .into_decoder()
+        .map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+fn image_metadata(path: &Path) -> Metadata {
+    let format = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .ok()
+        .and_then(|reader| reader.format());
+    let camera = read_exif(path).map(|exif| camera(&exif));
+    let probed = probe(path, MediaKind::Image);
+    Metadata::Image {
+        format: format.and_then(format_name),
+        width: probed.width,
+        height: probed.height,
+        camera,
+        strippable: matches!(
+            format,
+            Some(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)
+        ),
+    }
+}
+
+/// A JPEG without its EXIF, XMP, IPTC and comment segments. Colour profiles and the
+/// JFIF and Adobe segments are kept, as they change how the image looks.
+fn strip_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+    if data.get(..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+    let mut out = data[..2].to_vec();
+    let mut at = 2;
+    loop {
+        let marker = *data.get(at + 1)?;
+        if *data.get(at)? != 0xFF {
+            return None;
+        }
+        // Start of scan: the rest is image data.
+        if marker == 0xDA {
+            out.extend_from_slice(&data[at..]);
+            return Some(out);
+        }
+        let len = u16::from_be_bytes([*data.get(at + 2)?, *data.get(at + 3)?]) as usize;
+        let segment = data.get(at..at + 2 + len)?;
+        // APP1 (EXIF, XMP), APP12 (Ducky), APP13 (IPTC) and comments.
+        if !matches!(marker, 0xE1 | 0xEC | 0xED | 0xFE) {
+            out.extend_from_slice(segment);
+        }
+        at += segment.len();
+    }
+}
+
+/// A PNG without its text, EXIF and timestamp chunks.
+fn strip_png(data: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if data.get(..8)? != SIGNATURE {
+        return None;
+    }
+    let mut out = SIGNATURE.to_vec();
+    let mut at = 8;
+    while at < data.len() {
+        let len = u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?) as usize;
+        // Length, type, data and CRC.
+        let chunk = data.get(at..at + 12 + len)?;
+        if !matches!(
+            &chunk[4..8],
+            b"tEXt" | b"zTXt" | b"iTXt" | b"eXIf" | b"tIME"
+        ) {
+            out.extend_from_slice(chunk);
+        }
+        at += chunk.len();
+    }
+    Some(out)
+}
+
+/// A WebP without its EXIF and XMP chunks, and with the flags saying it has them
+/// cleared.
+fn strip_webp(data: &[u8]) -> Option<Vec<u8>> {
+    if data.get(..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    let mut out = data[..12].to_vec();
+    let mut at = 12;
+    while at < data.len() {
+        let len = u32::from_le_bytes(data.get(at + 4..at + 8)?.try_into().ok()?) as usize;
+        // Chunks are padded to an even length.
+        let chunk = data.get(at..(at + 8 + len + len % 2).min(data.len()))?;
+        match &chunk[..4] {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if len >= 1 => {
+                let start = out.len();
+                out.extend_from_slice(chunk);
+                out[start + 8] &= !0x0C;
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+        at += chunk.len();
+    }
+    let riff_len = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    Some(out)
+}
+
+/// The image at `path` as `format`, without metadata. Files whose orientation isn't
+/// upright are turned and encoded again, since dropping the EXIF would otherwise
+/// leave them on their side; the rest have their metadata cut out without touching
+/// the pixels.
+fn stripped(path: &Path, format: ImageFormat) -> Result<Vec<u8>, String> {
+    if orientation(path) != 1 {
+        let export = match format {
+            ImageFormat::Jpeg => Export::Jpeg { quality: 95 },
+            ImageFormat::WebP => Export::Webp,
+            _ => Export::Png,
+        };
+        return encode(&open_upright(path)?, export);
+    }
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let result = match format {
+        ImageFormat::Jpeg => strip_jpeg(&data),
+        ImageFormat::Png => strip_png(&data),
+        ImageFormat::WebP => strip_webp(&data),
+        _ => return Err("Only JPEG, PNG and WebP images can be stripped".to_string()),
+    };
+    result.ok_or_else(|| format!("{} is damaged", path.display()))
+}
+
+/// What's known about a file the player was allowed to open.
+#[tauri::command]
+pub async fn media_metadata(app: AppHandle, path: String) -> Result<Metadata, String> {
+    let resolved = app
+        .state::<MediaScope>()
+        .resolve(Path::new(&path))
+        .ok_or_else(|| format!("{} hasn't been opened in the app", path))?;
+    tauri::async_runtime::spawn_blocking(move || match MediaKind::from_path(&resolved) {
+        Some(MediaKind::Video) => {
+            let probed = probe(&resolved, MediaKind::Video);
+            Ok(Metadata::Video {
+                container: probed.container,
+                duration: probed.duration,
+                width: probed.width,
+                height: probed.height,
+                frame_rate: probed.frame_rate,
+                tracks: probed.tracks,
+            })
+        }
+        Some(MediaKind::Image) => Ok(image_metadata(&resolved)),
+        None => Err(format!("{} isn't an image or a video", path)),
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Asks where to save a copy of `source` without its metadata, and writes it there.
+/// Returns the saved path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn media_strip_metadata(
+    app: AppHandle,
+    source: String,
+) -> Result<Option<String>, String> {
+    let original = app
+        .state::<MediaScope>()
+        .resolve(Path::new(&source))
+        .ok_or_else(|| format!("{} hasn't been opened in the app", source))?;
+    let stem = original
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+    let extension = original
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_file_name(format!("{}-clean.{}", stem, extension));
+    if let Some(folder) = original.parent() {
+        dialog = dialog.set_directory(folder);
+    }
+    let Some(chosen) = dialog.blocking_save_file() else {
+        return Ok(None);
+    };
+    let target: PathBuf = chosen.into_path().map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        if target.canonicalize().is_ok_and(|target| target == original) {
+            return Err("Choose another name; the original is never overwritten".to_string());
+        }
+        let format = ImageReader::open(&original)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| e.to_string())?
+            .format()
+            .ok_or_else(|| format!("{} isn't an image", source))?;
+        let bytes = stripped(&original, format)?;
+        fs::write(&target, bytes)
+            .map_err(|e| format!("Couldn't save {}: {}", target.display(), e))?;
+        app.state::<MediaScope>()
+            .allow_file(&target)
+            .map_err(|e| e.to_string())?;
+        Ok(Some(target.to_string_lossy().into_owned()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::experimental::Writer;
+    use exif::{Field, Rational};
+    use std::io::Cursor;
+
+    fn field(tag: Tag, value: Value) -> Field {
+        Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value,
+        }
+    }
+
+    fn ascii(text: &str) -> Value {
+        Value::Ascii(vec![text.as_bytes().to_vec()])
+    }
+
+    fn rational(num: u32, denom: u32) -> Rational {
+        Rational { num, denom }
+    }
+
+    /// A TIFF block of EXIF as a JPEG or WebP would carry it.
+    fn exif_block() -> Vec<u8> {
+        let fields = [
+            field(Tag::Make, ascii("Fujifilm")),
+            field(Tag::Model, ascii("X100V")),
+            field(Tag::Orientation, Value::Short(vec![6])),
+            field(Tag::DateTimeOriginal, ascii("2024:05:01 18:30:12")),
+            field(Tag::ExposureTime, Value::Rational(vec![rational(1, 250)])),
+            field(Tag::FNumber, Value::Rational(vec![rational(28, 10)])),
+            field(Tag::PhotographicSensitivity, Value::Short(vec![400])),
+            field(Tag::FocalLength, Value::Rational(vec![rational(23, 1)])),
+            field(Tag::GPSLatitudeRef, ascii("S")),
+            field(
+                Tag::GPSLatitude,
+                Value::Rational(vec![rational(33, 1), rational(51, 1), rational(36, 1)]),
+            ),
+            field(Tag::GPSLongitudeRef, ascii("E")),
+            field(
+                Tag::GPSLongitude,
+                Value::Rational(vec![rational(151, 1), rational(12, 1), rational(0, 1)]),
+            ),
+        ];
+        let mut writer = Writer::new();
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut out = Cursor::new(Vec::new());
+        writer.write(&mut out, false).unwrap();
+        out.into_inner()
+    }
+
+    fn jpeg_segment(marker: u8, contents: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xFF, marker];
+        out.extend_from_slice(&((contents.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(contents);
+        out
+    }
+
+    #[test]
+    fn exif_fields_are_read() {
+        let exif = exif::Reader::new().read_raw(exif_block()).unwrap();
+        let camera = camera(&exif);
+        assert_eq!(camera.make.as_deref(), Some("Fujifilm"));
+        assert_eq!(camera.model.as_deref(), Some("X100V"));
+        assert_eq!(camera.taken.as_deref(), Some("2024-05-01 18:30:12"));
+        assert_eq!(camera.exposure_time, Some(0.004));
+        assert_eq!(camera.f_number, Some(2.8));
+        assert_eq!((camera.iso, camera.focal_length), (Some(400), Some(23.0)));
+        assert_eq!(camera.orientation, 6);
+        let gps = camera.gps.unwrap();
+        assert_eq!(gps.latitude, -33.86);
+        assert_eq!((gps.longitude, gps.altitude), (151.2, None));
+    }
+
+    #[test]
+    fn jpeg_metadata_segments_are_dropped() {
+        let exif = [&b"Exif\0\0"[..], &exif_block()].concat();
+        let jfif = jpeg_segment(0xE0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        let icc = jpeg_segment(0xE2, b"ICC_PROFILE\0\x01\x01");
+        let scan = [&jpeg_segment(0xDA, &[1, 2, 3])[..], &[9, 9, 0xFF, 0xD9]].concat();
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &jfif,
+            &jpeg_segment(0xE1, &exif),
+            &icc,
+            &jpeg_segment(0xFE, b"a comment"),
+            &scan,
+        ]
+        .concat();
+
+        let stripped = strip_jpeg(&jpeg).unwrap();
+        assert_eq!(stripped, [&[0xFF, 0xD8][..], &jfif, &icc, &scan].concat());
+        assert!(exif::Reader::new()
+            .read_from_container(&mut Cursor::new(&stripped))
+            .is_err());
+        assert_eq!(strip_jpeg(&jpeg[..30]), None);
+    }
+
+    #[test]
+    fn png_and_webp_metadata_chunks_are_dropped() {
+        let png_chunk = |kind: &[u8; 4], contents: &[u8]| {
+            let mut out = (contents.len() as u32).to_be_bytes().to_vec();
+            out.extend_from_slice(kind);
+            out.extend_from_slice(contents);
+            out.extend_from_slice(&[0; 4]);
+            out
+        };
+        let signature = b"\x89PNG\r\n\x1a\n";
+        let header = png_chunk(b"IHDR", &[0; 13]);
+        let data = png_chunk(b"IDAT", &[1, 2, 3]);
+        let end = png_chunk(b"IEND", &[]);
+        let png = [
+            &signature[..],
+            &header,
+            &png_chunk(b"tEXt", b"Author\0me"),
+            &png_chunk(b"eXIf", &exif_block()),
+            &data,
+            &end,
+        ]
+        .concat();
+        assert_eq!(
+            strip_png(&png).unwrap(),
+            [&signature[..], &header, &data, &end].concat()
+        );
+
+        let webp_chunk = |kind: &[u8; 4], contents: &[u8]| {
+            let mut out = kind.to_vec();
+            out.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+            out.extend_from_slice(contents);
+            if contents.len() % 2 == 1 {
+                out.push(0);
+            }
+            out
+        };
+        let riff = |chunks: &[Vec<u8>]| {
+            let body = chunks.concat();
+            [
+                &b"RIFF"[..],
+                &((body.len() + 4) as u32).to_le_bytes(),
+                b"WEBP",
+                &body,
+            ]
+            .concat()
+        };
+        let image = webp_chunk(b"VP8L", &[1, 2, 3]);
+        let webp = riff(&[
+            webp_chunk(b"VP8X", &[0x0C, 0, 0, 0, 1, 0, 0, 1, 0, 0]),
+            image.clone(),
+            webp_chunk(b"EXIF", &exif_block()),
+            webp_chunk(b"XMP ", b"<x/>"),
+        ]);
+        assert_eq!(
+            strip_webp(&webp).unwrap(),
+            riff(&[webp_chunk(b"VP8X", &[0, 0, 0, 0, 1, 0, 0, 1, 0, 0]), image])
+        );
+    }
+}
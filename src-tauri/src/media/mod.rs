@@ -0,0 +1,148 @@
+//! Local media for the player.
+//!
+//! Files are served to the webview through the `media://` protocol, but only ones the
+//! user has chosen: dropped on the window, listed in a playlist they opened, and so on.
+//! Everything else on disk stays out of reach of the page.
+
+pub mod edit;
+pub mod frames;
+pub mod library;
+pub mod metadata;
+pub mod playlist;
+pub mod probe;
+pub mod protocol;
+pub mod recent;
+pub mod subtitles;
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, DragDropEvent, Manager, Window, WindowEvent};
+use tauri_plugin_dialog::DialogExt;
+
+/// The files and folders the `media://` protocol may serve.
+#[derive(Default)]
+pub struct MediaScope(Mutex<Allowed>);
+
+#[derive(Default)]
+struct Allowed {
+    files: HashSet<PathBuf>,
+    /// Folders whose contents are allowed at any depth.
+    dirs: Vec<PathBuf>,
+}
+
+impl MediaScope {
+    pub fn allow_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref().canonicalize()?;
+        self.0.lock().unwrap().files.insert(path);
+        Ok(())
+    }
+
+    pub fn allow_dir(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref().canonicalize()?;
+        let mut allowed = self.0.lock().unwrap();
+        if !allowed.dirs.contains(&path) {
+            allowed.dirs.push(path);
+        }
+        Ok(())
+    }
+
+    /// The real location of `path` if it may be served. Symlinks and `..` are resolved
+    /// first, so neither can lead out of an allowed folder.
+    pub fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        let path = path.canonicalize().ok()?;
+        let allowed = self.0.lock().unwrap();
+        let permitted =
+            allowed.files.contains(&path) || allowed.dirs.iter().any(|dir| path.starts_with(dir));
+        permitted.then_some(path)
+    }
+}
+
+/// Asks for images and videos to open, letting the webview load the chosen files.
+/// Returns their paths, or nothing if the dialog was cancelled.
+#[tauri::command]
+pub async fn media_open_dialog(app: AppHandle) -> Result<Vec<String>, String> {
+    let Some(chosen) = app
+        .dialog()
+        .file()
+        .add_filter(
+            "Images and videos",
+            &[
+                "mp4", "m4v", "webm", "mov", "mkv", "ogv", "png", "jpg", "jpeg", "gif", "webp",
+                "bmp", "svg", "avif",
+            ],
+        )
+        .blocking_pick_files()
+    else {
+        return Ok(Vec::new());
+    };
+    let scope = app.state::<MediaScope>();
+    let mut paths = Vec::new();
+    for file in chosen {
+        let path = file.into_path().map_err(|e| e.to_string())?;
+        scope
+            .allow_file(&path)
+            .map_err(|e| format!("Couldn't open {}: {}", path.display(), e))?;
+        paths.push(path.to_string_lossy().into_owned());
+    }
+    Ok(paths)
+}
+
+/// Files dropped on the window become playable.
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+        let scope = window.state::<MediaScope>();
+        for path in paths.iter().filter(|p| p.is_file()) {
+            if let Err(e) = scope.allow_file(path) {
+                log::warn!("Failed to allow {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("media-scope-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("inner")).unwrap();
+        fs::write(dir.join("a.mp4"), b"a").unwrap();
+        fs::write(dir.join("b.mp4"), b"b").unwrap();
+        fs::write(dir.join("inner/c.png"), b"c").unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_allowed_files_resolve() {
+        let dir = temp_dir("files");
+        let scope = MediaScope::default();
+        scope.allow_file(dir.join("a.mp4")).unwrap();
+
+        assert!(scope.resolve(&dir.join("a.mp4")).is_some());
+        assert!(scope.resolve(&dir.join("inner/../a.mp4")).is_some());
+        assert!(scope.resolve(&dir.join("b.mp4")).is_none());
+        assert!(scope.resolve(&dir.join("missing.mp4")).is_none());
+        assert!(scope.allow_file(dir.join("missing.mp4")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn allowed_folders_cover_their_contents_only() {
+        let dir = temp_dir("dirs");
+        let scope = MediaScope::default();
+        scope.allow_dir(dir.join("inner")).unwrap();
+
+        assert!(scope.resolve(&dir.join("inner/c.png")).is_some());
+        assert!(scope.resolve(&dir.join("inner/../a.mp4")).is_none());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("b.mp4"), dir.join("inner/link.mp4")).unwrap();
+            assert!(scope.resolve(&dir.join("inner/link.mp4")).is_none());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
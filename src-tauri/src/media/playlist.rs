@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use super::probe::MediaKind;
+use super::MediaScope;
+
+/// One file in a saved playlist.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistFormat {
+    M3u,
+    Json,
+}
+
+impl PlaylistFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::M3u,
+        }
+    }
+
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::M3u => &["m3u", "m3u8"],
+            Self::Json => &["json"],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPlaylist {
+    entries: Vec<PlaylistEntry>,
+}
+
+/// Extended M3U, with each entry's title on an `#EXTINF` line before its path.
+pub fn to_m3u(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for entry in entries {
+        if let Some(title) = &entry.title {
+            out.push_str(&format!(
+                "#EXTINF:-1,{}\n",
+                title.replace(['\r', '\n'], " ")
+            ));
+        }
+        out.push_str(&entry.path);
+        out.push('\n');
+    }
+    out
+}
+
+/// Reads plain or extended M3U. Relative paths are resolved against `base`, the
+/// playlist's folder; URLs are skipped since only local files can be opened.
+pub fn parse_m3u(text: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut title = None;
+    for line in text.trim_start_matches('\u{feff}').lines().map(str::trim) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            title = info
+                .split_once(',')
+                .map(|(_, t)| t.trim().to_string())
+                .filter(|t| !t.is_empty());
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else if line.contains("://") {
+            title = None;
+        } else {
+            let path = base.join(line);
+            entries.push(PlaylistEntry {
+                path: path.to_string_lossy().into_owned(),
+                title: title.take(),
+            });
+        }
+    }
+    entries
+}
+
+fn save(path: &Path, entries: &[PlaylistEntry]) -> Result<(), String> {
+    let text = match PlaylistFormat::from_path(path) {
+        PlaylistFormat::M3u => to_m3u(entries),
+        PlaylistFormat::Json => serde_json::to_string_pretty(&JsonPlaylist {
+            entries: entries.to_vec(),
+        })
+        .map_err(|e| e.to_string())?,
+    };
+    fs::write(path, text).map_err(|e| format!("Couldn't save {}: {}", path.display(), e))
+}
+
+fn load(path: &Path) -> Result<Vec<PlaylistEntry>, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    match PlaylistFormat::from_path(path) {
+        PlaylistFormat::M3u => Ok(parse_m3u(&text, base)),
+        PlaylistFormat::Json => {
+            let playlist: JsonPlaylist = serde_json::from_str(&text)
+                .map_err(|e| format!("{} isn't a playlist: {}", path.display(), e))?;
+            Ok(playlist
+                .entries
+                .into_iter()
+                .map(|entry| PlaylistEntry {
+                    path: base.join(&entry.path).to_string_lossy().into_owned(),
+                    ..entry
+                })
+                .collect())
+        }
+    }
+}
+
+/// Asks where to save `entries` and writes them there. Returns the chosen path, or
+/// `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn playlist_save(
+    app: AppHandle,
+    entries: Vec<PlaylistEntry>,
+    format: PlaylistFormat,
+) -> Result<Option<String>, String> {
+    let file_name = match format {
+        PlaylistFormat::M3u => "playlist.m3u",
+        PlaylistFormat::Json => "playlist.json",
+    };
+    let Some(chosen) = app
+        .dialog()
+        .file()
+        .add_filter("Playlist", format.extensions())
+        .set_file_name(file_name)
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let path: PathBuf = chosen.into_path().map_err(|e| e.to_string())?;
+    save(&path, &entries)?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+/// The entries that are images or videos still on disk, which the webview is then
+/// allowed to load. A playlist can come from anyone, so anything else it lists stays
+/// out of reach.
+fn allow_entries(scope: &MediaScope, entries: Vec<PlaylistEntry>) -> Vec<PlaylistEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| {
+            let path = Path::new(&entry.path);
+            MediaKind::from_path(path).is_some() && path.is_file()
+        })
+        .filter(|entry| scope.allow_file(&entry.path).is_ok())
+        .collect()
+}
+
+/// Asks for a playlist and returns its media files that still exist, letting the
+/// webview load them. An empty list means the dialog was cancelled or nothing was left.
+#[tauri::command]
+pub async fn playlist_open(app: AppHandle) -> Result<Vec<PlaylistEntry>, String> {
+    let Some(chosen) = app
+        .dialog()
+        .file()
+        .add_filter("Playlist", &["m3u", "m3u8", "json"])
+        .blocking_pick_file()
+    else {
+        return Ok(Vec::new());
+    };
+    let path: PathBuf = chosen.into_path().map_err(|e| e.to_string())?;
+    let entries = load(&path)?;
+    Ok(allow_entries(&app.state(), entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, title: Option<&str>) -> PlaylistEntry {
+        PlaylistEntry {
+            path: path.to_string(),
+            title: title.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn m3u_round_trips_titles_and_paths() {
+        let entries = vec![
+            entry("/videos/intro.mp4", Some("Intro")),
+            entry("/shots/a.png", None),
+        ];
+        let text = to_m3u(&entries);
+        assert_eq!(
+            text,
+            "#EXTM3U\n#EXTINF:-1,Intro\n/videos/intro.mp4\n/shots/a.png\n"
+        );
+        assert_eq!(parse_m3u(&text, Path::new("/elsewhere")), entries);
+    }
+
+    #[test]
+    fn m3u_resolves_relative_paths_and_skips_urls() {
+        let text = "#EXTM3U\r\n\r\n#EXTINF:12,Remote\r\nhttp://example.com/a.mp4\r\nclip.webm\r\n# a comment\r\nsub/b.png\r\n";
+        assert_eq!(
+            parse_m3u(text, Path::new("/media")),
+            vec![
+                entry("/media/clip.webm", None),
+                entry("/media/sub/b.png", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn json_playlists_round_trip_through_files() {
+        let dir = std::env::temp_dir().join(format!("playlist-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("list.json");
+        let entries = vec![entry(&dir.join("a.mp4").to_string_lossy(), Some("A"))];
+        save(&path, &entries).unwrap();
+        assert_eq!(load(&path).unwrap(), entries);
+
+        fs::write(&path, "[1, 2]").unwrap();
+        assert!(load(&path).unwrap_err().contains("isn't a playlist"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn only_media_files_are_allowed() {
+        let dir = std::env::temp_dir().join(format!("playlist-scope-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("clip.mp4"), b"v").unwrap();
+        fs::write(dir.join("id_rsa"), b"key").unwrap();
+        fs::write(dir.join("notes.txt"), b"text").unwrap();
+        let listed = |name: &str| entry(&dir.join(name).to_string_lossy(), None);
+
+        let scope = MediaScope::default();
+        let entries = vec![
+            listed("clip.mp4"),
+            listed("id_rsa"),
+            listed("notes.txt"),
+            listed("gone.png"),
+        ];
+        assert_eq!(allow_entries(&scope, entries), vec![listed("clip.mp4")]);
+        assert!(scope.resolve(&dir.join("clip.mp4")).is_some());
+        assert!(scope.resolve(&dir.join("id_rsa")).is_none());
+        assert!(scope.resolve(&dir.join("notes.txt")).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,666 @@
+//! What a media file is without decoding it: its kind, upright pixel size and, for videos,
+//! duration, frame rate and tracks. Images are measured from their headers;
+//! MP4/QuickTime and Matroska/WebM videos from their container metadata. Other videos
+//! get a kind and nothing more.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::metadata::orientation;
+use super::protocol::mime_type;
+
+/// The most read of an MP4 `moov` box, which is normally well under a megabyte.
+const MAX_MOOV: u64 = 64 * 1024 * 1024;
+/// How much of a Matroska file is read looking for its `Info` and `Tracks`, which
+/// come before the first cluster.
+const MATROSKA_HEAD: u64 = 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+impl MediaKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let mime = mime_type(path);
+        if mime.starts_with("image/") {
+            Some(Self::Image)
+        } else if mime.starts_with("video/") {
+            Some(Self::Video)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Subtitle,
+    Other,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Track {
+    pub kind: TrackKind,
+    /// A common name where one is known, otherwise the container's codec ID.
+    pub codec: String,
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Probe {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Seconds.
+    pub duration: Option<f64>,
+    /// Of the first video track.
+    pub frame_rate: Option<f64>,
+    pub container: Option<&'static str>,
+    pub tracks: Vec<Track>,
+}
+
+/// Measures `path`, leaving out whatever can't be read from it.
+pub fn probe(path: &Path, kind: MediaKind) -> Probe {
+    let probed = match kind {
+        MediaKind::Image => image::image_dimensions(path).ok().map(|(width, height)| {
+            // Orientations 5 to 8 turn the image on its side.
+            let (width, height) = if orientation(path) >= 5 {
+                (height, width)
+            } else {
+                (width, height)
+            };
+            Probe {
+                width: Some(width),
+                height: Some(height),
+                ..Probe::default()
+            }
+        }),
+        MediaKind::Video => probe_video(path).ok().flatten(),
+    };
+    probed.unwrap_or_default()
+}
+
+fn probe_video(path: &Path) -> io::Result<Option<Probe>> {
+    let mut file = File::open(path)?;
+    // Room for an MP4's `ftyp` header and major brand.
+    let mut magic = [0; 12];
+    let read = file.read(&mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+    if read >= 4 && magic[..4] == [0x1A, 0x45, 0xDF, 0xA3] {
+        let mut head = Vec::new();
+        file.take(MATROSKA_HEAD).read_to_end(&mut head)?;
+        Ok(Some(matroska(&head)))
+    } else if read >= 8 && is_mp4_box(&magic[4..8]) {
+        let quicktime = read == 12 && &magic[4..] == b"ftypqt  ";
+        Ok(find_moov(&mut file)?.map(|moov| Probe {
+            container: Some(if quicktime { "QuickTime" } else { "MP4" }),
+            ..mp4(&moov)
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+fn is_mp4_box(kind: &[u8]) -> bool {
+    matches!(
+        kind,
+        b"ftyp" | b"moov" | b"mdat" | b"free" | b"wide" | b"skip"
+    )
+}
+
+/// The contents of the top-level `moov` box, which may come after the media data.
+fn find_moov(file: &mut File) -> io::Result<Option<Vec<u8>>> {
+    let len = file.metadata()?.len();
+    let mut offset: u64 = 0;
+    while len.saturating_sub(offset) >= 8 {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0; 8];
+        file.read_exact(&mut header)?;
+        let mut size = u32::from_be_bytes(header[..4].try_into().unwrap()) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = len - offset;
+        }
+        if size < header_len {
+            return Ok(None);
+        }
+        if &header[4..] == b"moov" {
+            if size > MAX_MOOV {
+                return Ok(None);
+            }
+            let mut moov = Vec::new();
+            file.take(size - header_len).read_to_end(&mut moov)?;
+            return Ok(Some(moov));
+        }
+        // The size comes from the file, so it may be anything.
+        match offset.checked_add(size) {
+            Some(next) if next <= len => offset = next,
+            _ => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+/// The boxes directly inside `data`, as `(type, contents)`.
+fn boxes(data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        if rest.len() < 8 {
+            return None;
+        }
+        let size = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let (header_len, size) = match size {
+            0 => (8, rest.len()),
+            1 if rest.len() >= 16 => (
+                16,
+                u64::from_be_bytes(rest[8..16].try_into().unwrap()) as usize,
+            ),
+            size => (8, size),
+        };
+        if size < header_len || size > rest.len() {
+            return None;
+        }
+        let (this, next) = rest.split_at(size);
+        rest = next;
+        Some((&this[4..8], &this[header_len..]))
+    })
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn be_u64(data: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(at..at + 8)?.try_into().ok()?))
+}
+
+/// A friendlier name for a codec given as an MP4 sample entry type or a Matroska
+/// codec ID.
+fn codec_name(id: &str) -> String {
+    let name = match id {
+        "avc1" | "avc3" | "V_MPEG4/ISO/AVC" => "H.264",
+        "hvc1" | "hev1" | "V_MPEGH/ISO/HEVC" => "H.265",
+        "av01" | "V_AV1" => "AV1",
+        "vp08" | "V_VP8" => "VP8",
+        "vp09" | "V_VP9" => "VP9",
+        "mp4v" => "MPEG-4 Part 2",
+        "mp4a" | "A_AAC" => "AAC",
+        "Opus" | "A_OPUS" => "Opus",
+        "A_VORBIS" => "Vorbis",
+        "fLaC" | "A_FLAC" => "FLAC",
+        "ac-3" | "A_AC3" => "AC-3",
+        "ec-3" | "A_EAC3" => "E-AC-3",
+        ".mp3" | "A_MPEG/L3" => "MP3",
+        "tx3g" => "3GPP timed text",
+        "wvtt" | "S_TEXT/WEBVTT" => "WebVTT",
+        "S_TEXT/UTF8" => "SubRip",
+        "S_TEXT/ASS" | "S_TEXT/SSA" => "ASS",
+        other => other.trim(),
+    };
+    name.to_string()
+}
+
+/// What an MP4 `trak` holds, and its frame rate if it's video.
+fn mp4_track(trak: &[u8]) -> Option<(Track, Option<f64>)> {
+    let (_, mdia) = boxes(trak).find(|(kind, _)| *kind == b"mdia")?;
+    let mut kind = TrackKind::Other;
+    let mut timescale = None;
+    let mut codec = None;
+    // Samples and their total duration in `timescale` ticks.
+    let mut timing = None;
+    for (box_kind, data) in boxes(mdia) {
+        match box_kind {
+            b"hdlr" => {
+                kind = match data.get(8..12)? {
+                    b"vide" => TrackKind::Video,
+                    b"soun" => TrackKind::Audio,
+                    b"text" | b"sbtl" | b"subt" => TrackKind::Subtitle,
+                    _ => TrackKind::Other,
+                }
+            }
+            b"mdhd" => {
+                let at = if data.first() == Some(&1) { 20 } else { 12 };
+                timescale = be_u32(data, at);
+            }
+            b"minf" => {
+                let Some((_, stbl)) = boxes(data).find(|(kind, _)| *kind == b"stbl") else {
+                    continue;
+                };
+                for (box_kind, data) in boxes(stbl) {
+                    match box_kind {
+                        // The first sample entry's type names the codec.
+                        b"stsd" => {
+                            codec = data
+                                .get(12..16)
+                                .map(|id| codec_name(&String::from_utf8_lossy(id)));
+                        }
+                        // Runs of (sample count, sample duration).
+                        b"stts" => {
+                            let runs = be_u32(data, 4).unwrap_or_default() as usize;
+                            let (mut samples, mut ticks) = (0u64, 0u64);
+                            for run in 0..runs {
+                                let (Some(count), Some(delta)) =
+                                    (be_u32(data, 8 + run * 8), be_u32(data, 12 + run * 8))
+                                else {
+                                    break;
+                                };
+                                samples += count as u64;
+                                ticks += count as u64 * delta as u64;
+                            }
+                            timing = Some((samples, ticks));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let frame_rate = match (kind, timescale, timing) {
+        (TrackKind::Video, Some(timescale), Some((samples, ticks @ 1..))) => {
+            Some(samples as f64 * timescale as f64 / ticks as f64)
+        }
+        _ => None,
+    };
+    Some((
+        Track {
+            kind,
+            codec: codec.unwrap_or_else(|| "unknown".to_string()),
+        },
+        frame_rate,
+    ))
+}
+
+/// Reads the movie header's duration, the first video track's size and frame rate,
+/// and what every track holds from `moov`.
+fn mp4(moov: &[u8]) -> Probe {
+    let mut probe = Probe::default();
+    for (kind, data) in boxes(moov) {
+        match kind {
+            b"mvhd" => {
+                let version = data.first().copied().unwrap_or_default();
+                let (timescale, duration) = if version == 1 {
+                    (be_u32(data, 20), be_u64(data, 24))
+                } else {
+                    (be_u32(data, 12), be_u32(data, 16).map(u64::from))
+                };
+                if let (Some(timescale @ 1..), Some(duration)) = (timescale, duration) {
+                    probe.duration = Some(duration as f64 / timescale as f64);
+                }
+            }
+            b"trak" => {
+                let Some((track, frame_rate)) = mp4_track(data) else {
+                    continue;
+                };
+                let first_video = track.kind == TrackKind::Video && probe.width.is_none();
+                probe.tracks.push(track);
+                if !first_video {
+                    continue;
+                }
+                probe.frame_rate = frame_rate;
+                let Some((_, tkhd)) = boxes(data).find(|(kind, _)| *kind == b"tkhd") else {
+                    continue;
+                };
+                // Width and height are 16.16 fixed point, after the matrix.
+                let at = if tkhd.first() == Some(&1) { 88 } else { 76 };
+                let (width, height) = (be_u32(tkhd, at), be_u32(tkhd, at + 4));
+                if let (Some(width), Some(height)) = (width, height) {
+                    if width >> 16 > 0 && height >> 16 > 0 {
+                        probe.width = Some(width >> 16);
+                        probe.height = Some(height >> 16);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    probe
+}
+
+const EBML_HEADER: u64 = 0x1A45_DFA3;
+const EBML_DOC_TYPE: u64 = 0x4282;
+const EBML_SEGMENT: u64 = 0x1853_8067;
+const EBML_INFO: u64 = 0x1549_A966;
+const EBML_TIMECODE_SCALE: u64 = 0x2A_D7B1;
+const EBML_DURATION: u64 = 0x4489;
+const EBML_TRACKS: u64 = 0x1654_AE6B;
+const EBML_TRACK_ENTRY: u64 = 0xAE;
+const EBML_TRACK_TYPE: u64 = 0x83;
+const EBML_CODEC_ID: u64 = 0x86;
+/// Nanoseconds per frame.
+const EBML_DEFAULT_DURATION: u64 = 0x23_E383;
+const EBML_VIDEO: u64 = 0xE0;
+const EBML_PIXEL_WIDTH: u64 = 0xB0;
+const EBML_PIXEL_HEIGHT: u64 = 0xBA;
+const EBML_CLUSTER: u64 = 0x1F43_B675;
+
+/// A variable-length integer at the start of `data` and its length. IDs keep their
+/// length marker bit; sizes don't. A size of all ones means "unknown".
+fn vint(data: &[u8], keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *data.first()?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 || data.len() < len {
+        return None;
+    }
+    let mut value = if keep_marker {
+        first as u64
+    } else {
+        (first as u64) & (0xFF >> len)
+    };
+    for byte in &data[1..len] {
+        value = (value << 8) | *byte as u64;
+    }
+    if !keep_marker && value == (1 << (7 * len)) - 1 {
+        value = u64::MAX;
+    }
+    Some((value, len))
+}
+
+/// The elements directly inside `data`, as `(id, contents)`. An element of unknown
+/// size, as live recordings write, runs to the end of `data`; one cut off by the end
+/// of what was read is returned truncated.
+fn elements(data: &[u8]) -> impl Iterator<Item = (u64, &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        let (id, id_len) = vint(rest, true)?;
+        let (size, size_len) = vint(&rest[id_len..], false)?;
+        let start = id_len + size_len;
+        let end = usize::try_from(size)
+            .ok()
+            .and_then(|size| start.checked_add(size))
+            .map_or(rest.len(), |end| end.min(rest.len()));
+        let contents = &rest[start..end];
+        rest = &rest[end..];
+        Some((id, contents))
+    })
+}
+
+fn ebml_uint(data: &[u8]) -> Option<u64> {
+    (data.len() <= 8).then(|| {
+        data.iter()
+            .fold(0, |value, byte| (value << 8) | *byte as u64)
+    })
+}
+
+fn ebml_float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_be_bytes(data.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(data.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+/// Reads the duration, the first video track's size and frame rate, and what every
+/// track holds from the start of a Matroska or WebM file.
+fn matroska(head: &[u8]) -> Probe {
+    let mut probe = Probe::default();
+    let webm = elements(head)
+        .find(|(id, _)| *id == EBML_HEADER)
+        .and_then(|(_, header)| elements(header).find(|(id, _)| *id == EBML_DOC_TYPE))
+        .is_some_and(|(_, doc_type)| doc_type == b"webm");
+    let Some((_, segment)) = elements(head).find(|(id, _)| *id == EBML_SEGMENT) else {
+        return probe;
+    };
+    probe.container = Some(if webm { "WebM" } else { "Matroska" });
+    for (id, data) in elements(segment) {
+        match id {
+            EBML_INFO => {
+                // Timestamps are in nanoseconds unless the file says otherwise.
+                let mut scale = 1_000_000;
+                let mut duration = None;
+                for (id, data) in elements(data) {
+                    match id {
+                        EBML_TIMECODE_SCALE => scale = ebml_uint(data).unwrap_or(scale),
+                        EBML_DURATION => duration = ebml_float(data),
+                        _ => {}
+                    }
+                }
+                probe.duration = duration.map(|d| d * scale as f64 / 1e9);
+            }
+            EBML_TRACKS => {
+                for (_, entry) in elements(data).filter(|(id, _)| *id == EBML_TRACK_ENTRY) {
+                    let mut kind = TrackKind::Other;
+                    let mut codec = "unknown".to_string();
+                    let mut frame_rate = None;
+                    let mut video = None;
+                    for (id, data) in elements(entry) {
+                        match id {
+                            EBML_TRACK_TYPE => {
+                                kind = match ebml_uint(data) {
+                                    Some(1) => TrackKind::Video,
+                                    Some(2) => TrackKind::Audio,
+                                    Some(17) => TrackKind::Subtitle,
+                                    _ => TrackKind::Other,
+                                }
+                            }
+                            EBML_CODEC_ID => codec = codec_name(&String::from_utf8_lossy(data)),
+                            EBML_DEFAULT_DURATION => {
+                                frame_rate = ebml_uint(data)
+                                    .filter(|ns| *ns > 0)
+                                    .map(|ns| 1e9 / ns as f64);
+                            }
+                            EBML_VIDEO => video = Some(data),
+                            _ => {}
+                        }
+                    }
+                    if kind == TrackKind::Video && probe.width.is_none() {
+                        probe.frame_rate = frame_rate;
+                        for (id, data) in elements(video.unwrap_or_default()) {
+                            match id {
+                                EBML_PIXEL_WIDTH => probe.width = ebml_uint(data).map(|w| w as u32),
+                                EBML_PIXEL_HEIGHT => {
+                                    probe.height = ebml_uint(data).map(|h| h as u32)
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    probe.tracks.push(Track { kind, codec });
+                }
+            }
+            EBML_CLUSTER => break,
+            _ => {}
+        }
+    }
+    probe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(kind: &[u8; 4], contents: &[u8]) -> Vec<u8> {
+        let mut out = ((contents.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(contents);
+        out
+    }
+
+    fn ebml(id: &[u8], contents: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.push(0x80 | contents.len() as u8);
+        out.extend_from_slice(contents);
+        out
+    }
+
+    #[test]
+    fn mp4_duration_and_size_come_from_the_moov_box() {
+        let mut mvhd = vec![0; 100];
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&90_500u32.to_be_bytes());
+        let mut tkhd = vec![0; 84];
+        tkhd[76..80].copy_from_slice(&(1920u32 << 16).to_be_bytes());
+        tkhd[80..84].copy_from_slice(&(1080u32 << 16).to_be_bytes());
+        let trak = |handler: &[u8; 4], codec: &[u8; 4], timescale: u32, stts: &[u32]| {
+            let mut mdhd = vec![0; 24];
+            mdhd[12..16].copy_from_slice(&timescale.to_be_bytes());
+            let hdlr = [&[0; 8], &handler[..], &[0; 12]].concat();
+            let stsd = [&[0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 16], &codec[..], &[0; 8]].concat();
+            let stts: Vec<u8> = [0, stts.len() as u32 / 2]
+                .iter()
+                .chain(stts)
+                .flat_map(|n| n.to_be_bytes())
+                .collect();
+            let stbl = [mp4_box(b"stsd", &stsd), mp4_box(b"stts", &stts)].concat();
+            let mdia = [
+                mp4_box(b"mdhd", &mdhd),
+                mp4_box(b"hdlr", &hdlr),
+                mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+            ]
+            .concat();
+            mp4_box(
+                b"trak",
+                &[mp4_box(b"tkhd", &tkhd), mp4_box(b"mdia", &mdia)].concat(),
+            )
+        };
+        let moov = [
+            mp4_box(b"mvhd", &mvhd),
+            trak(b"soun", b"mp4a", 48_000, &[100, 1024]),
+            // 25 fps: 240 frames of 512 ticks, then 10 of 256 and 5 of 1024.
+            trak(b"vide", b"avc1", 12_800, &[240, 512, 10, 256, 5, 1024]),
+        ]
+        .concat();
+
+        let path = std::env::temp_dir().join(format!("probe-{}.mp4", std::process::id()));
+        let file = [
+            mp4_box(b"ftyp", b"isom\0\0\0\0"),
+            mp4_box(b"mdat", &[7; 300]),
+            mp4_box(b"moov", &moov),
+        ]
+        .concat();
+        std::fs::write(&path, file).unwrap();
+        let probed = probe(&path, MediaKind::Video);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            probed,
+            Probe {
+                width: Some(1920),
+                height: Some(1080),
+                duration: Some(90.5),
+                frame_rate: Some(25.0),
+                container: Some("MP4"),
+                tracks: vec![
+                    Track {
+                        kind: TrackKind::Audio,
+                        codec: "AAC".to_string(),
+                    },
+                    Track {
+                        kind: TrackKind::Video,
+                        codec: "H.264".to_string(),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn mp4_box_sizes_past_the_end_are_not_followed() {
+        let path = std::env::temp_dir().join(format!("probe-bad-{}.mp4", std::process::id()));
+        let ftyp = mp4_box(b"ftyp", b"isom\0\0\0\0");
+        // A 64-bit size that wraps the offset back round to the start of the file.
+        let mut mdat = 1u32.to_be_bytes().to_vec();
+        mdat.extend_from_slice(b"mdat");
+        mdat.extend_from_slice(&(u64::MAX - ftyp.len() as u64 + 1).to_be_bytes());
+        std::fs::write(&path, [ftyp.clone(), mdat, vec![0; 16]].concat()).unwrap();
+        let wrapped = find_moov(&mut File::open(&path).unwrap()).unwrap();
+
+        let mut huge = 1u32.to_be_bytes().to_vec();
+        huge.extend_from_slice(b"free");
+        huge.extend_from_slice(&u64::MAX.to_be_bytes());
+        std::fs::write(&path, [ftyp, huge, mp4_box(b"moov", &[])].concat()).unwrap();
+        let overflowed = find_moov(&mut File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(wrapped, None);
+        assert_eq!(overflowed, None);
+    }
+
+    #[test]
+    fn matroska_duration_and_size_come_from_info_and_tracks() {
+        let info = [
+            ebml(&[0x2A, 0xD7, 0xB1], &1_000_000u32.to_be_bytes()),
+            ebml(&[0x44, 0x89], &12_345.0f64.to_be_bytes()),
+        ]
+        .concat();
+        let video = [ebml(&[0xB0], &[0x05, 0x00]), ebml(&[0xBA], &[0x02, 0xD0])].concat();
+        let tracks = [
+            ebml(
+                &[0xAE],
+                &[
+                    ebml(&[0xD7], &[1]),
+                    ebml(&[0x83], &[2]),
+                    ebml(&[0x86], b"A_OPUS"),
+                ]
+                .concat(),
+            ),
+            ebml(
+                &[0xAE],
+                &[
+                    ebml(&[0xD7], &[2]),
+                    ebml(&[0x83], &[1]),
+                    ebml(&[0x86], b"V_VP9"),
+                    ebml(&[0x23, 0xE3, 0x83], &40_000_000u32.to_be_bytes()),
+                    ebml(&[0xE0], &video),
+                ]
+                .concat(),
+            ),
+        ]
+        .concat();
+        // A segment of unknown size, as written while recording.
+        let mut head = ebml(
+            &[0x1A, 0x45, 0xDF, 0xA3],
+            &[ebml(&[0x42, 0x82], b"webm")].concat(),
+        );
+        head.extend_from_slice(&[0x18, 0x53, 0x80, 0x67, 0xFF]);
+        head.extend(ebml(&[0x15, 0x49, 0xA9, 0x66], &info));
+        head.extend(ebml(&[0x16, 0x54, 0xAE, 0x6B], &tracks));
+        head.extend(ebml(&[0x1F, 0x43, 0xB6, 0x75], &[0; 20]));
+
+        assert_eq!(
+            matroska(&head),
+            Probe {
+                width: Some(1280),
+                height: Some(720),
+                duration: Some(12.345),
+                frame_rate: Some(25.0),
+                container: Some("WebM"),
+                tracks: vec![
+                    Track {
+                        kind: TrackKind::Audio,
+                        codec: "Opus".to_string(),
+                    },
+                    Track {
+                        kind: TrackKind::Video,
+                        codec: "VP9".to_string(),
+                    },
+                ],
+            }
+        );
+        assert_eq!(matroska(&head[..10]), Probe::default());
+    }
+
+    #[test]
+    fn kinds_follow_the_extension() {
+        assert_eq!(
+            MediaKind::from_path(Path::new("a.WEBM")),
+            Some(MediaKind::Video)
+        );
+        assert_eq!(
+            MediaKind::from_path(Path::new("a.png")),
+            Some(MediaKind::Image)
+        );
+        assert_eq!(MediaKind::from_path(Path::new("a.srt")), None);
+    }
+}
@@ -0,0 +1,298 @@
+//! The `media://` protocol: allowed local files with HTTP Range support, so the video
+//! element can seek anywhere in a large file without reading what comes before.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use percent_encoding::percent_decode_str;
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::{Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+
+use super::MediaScope;
+
+/// The most sent for one `Range` request, however much was asked for. The player asks
+/// again for the rest, so a video is never read whole. Requests without a range, from
+/// `<img>` or `fetch()`, get the whole file, since they wouldn't ask for more.
+const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "mp4" => "video/mp4",
+        "m4v" => "video/x-m4v",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "ogv" => "video/ogg",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "avif" => "image/avif",
+        "vtt" => "text/vtt",
+        "srt" => "application/x-subrip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The byte range to send for a `Range` header, inclusive, for a file of `len` bytes:
+/// what was asked for, cut to the file and to `MAX_CHUNK`. Only the first of several
+/// ranges is honoured. `Err` means nothing asked for exists.
+fn parse_range(value: &str, len: u64) -> Result<Option<(u64, u64)>, ()> {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    let first = spec.split(',').next().unwrap_or_default().trim();
+    let Some((start, end)) = first.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // A suffix: the last `end` bytes.
+        let suffix: u64 = end.parse().map_err(|_| ())?;
+        if suffix == 0 || len == 0 {
+            return Err(());
+        }
+        (len.saturating_sub(suffix), len - 1)
+    } else {
+        let start: u64 = start.parse().map_err(|_| ())?;
+        if start >= len {
+            return Err(());
+        }
+        let end = match end {
+            "" => len - 1,
+            end => end.parse::<u64>().map_err(|_| ())?.min(len - 1),
+        };
+        if end < start {
+            return Err(());
+        }
+        (start, end)
+    };
+    let (start, end) = range;
+    Ok(Some((start, end.min(start + MAX_CHUNK - 1))))
+}
+
+/// The file a `media://` URL names. The frontend percent-encodes the whole path into
+/// the URL's first path segment.
+fn requested_path(request: &Request<Vec<u8>>) -> PathBuf {
+    let raw = request.uri().path().trim_start_matches('/');
+    PathBuf::from(percent_decode_str(raw).decode_utf8_lossy().into_owned())
+}
+
+fn plain(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(message.as_bytes().to_vec())
+        .unwrap()
+}
+
+fn serve(path: &Path, range: Option<&str>, head: bool) -> io::Result<Response<Vec<u8>>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type(path))
+        .header(header::ACCEPT_RANGES, "bytes")
+        // Lets the page read the file too, e.g. to draw a video frame on a canvas.
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+    let (builder, start, count) = match range.map(|r| parse_range(r, len)) {
+        Some(Err(())) => {
+            let response = builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                .body(Vec::new())
+                .unwrap();
+            return Ok(response);
+        }
+        Some(Ok(Some((start, end)))) => (
+            builder.status(StatusCode::PARTIAL_CONTENT).header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            ),
+            start,
+            end - start + 1,
+        ),
+        _ => (builder.status(StatusCode::OK), 0, len),
+    };
+
+    let builder = builder.header(header::CONTENT_LENGTH, count);
+    if head {
+        return Ok(builder.body(Vec::new()).unwrap());
+    }
+    let mut body = Vec::with_capacity(count as usize);
+    file.seek(SeekFrom::Start(start))?;
+    file.take(count).read_to_end(&mut body)?;
+    Ok(builder.body(body).unwrap())
+}
+
+pub fn respond(scope: &MediaScope, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let head = match *request.method() {
+        Method::GET => false,
+        Method::HEAD => true,
+        _ => {
+            return plain(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Only GET and HEAD are supported",
+            )
+        }
+    };
+    let Some(path) = scope.resolve(&requested_path(request)) else {
+        return plain(
+            StatusCode::FORBIDDEN,
+            "This file hasn't been opened in the app",
+        );
+    };
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    serve(&path, range, head)
+        .unwrap_or_else(|e| plain(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
+}
+
+/// Handles `media://` requests on the blocking pool, since a range can be megabytes.
+pub fn handle<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let response = respond(&app.state::<MediaScope>(), &request);
+        responder.respond(response);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn request(path: &Path, range: Option<&str>) -> Request<Vec<u8>> {
+        let encoded: String = percent_encoding::utf8_percent_encode(
+            &path.to_string_lossy(),
+            percent_encoding::NON_ALPHANUMERIC,
+        )
+        .to_string();
+        let mut builder = Request::builder().uri(format!("media://localhost/{}", encoded));
+        if let Some(range) = range {
+            builder = builder.header(header::RANGE, range);
+        }
+        builder.body(Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn ranges_are_clamped_to_the_file() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Ok(Some((0, 99))));
+        assert_eq!(parse_range("bytes=900-2000", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=-100", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=-5000", 1000), Ok(Some((0, 999))));
+        assert_eq!(parse_range("bytes=10-19, 50-59", 1000), Ok(Some((10, 19))));
+        assert_eq!(
+            parse_range("bytes=0-", 100 * MAX_CHUNK),
+            Ok(Some((0, MAX_CHUNK - 1)))
+        );
+        assert_eq!(
+            parse_range("bytes=0-99999999999", 100 * MAX_CHUNK),
+            Ok(Some((0, MAX_CHUNK - 1)))
+        );
+        assert_eq!(
+            parse_range("bytes=-99999999999", 100 * MAX_CHUNK),
+            Ok(Some((0, MAX_CHUNK - 1)))
+        );
+        assert_eq!(parse_range("bytes=1000-", 1000), Err(()));
+        assert_eq!(parse_range("bytes=20-10", 1000), Err(()));
+        assert_eq!(parse_range("bytes=-0", 1000), Err(()));
+        assert_eq!(parse_range("bytes=x-1", 1000), Err(()));
+        assert_eq!(parse_range("items=0-1", 1000), Ok(None));
+    }
+
+    #[test]
+    fn mime_types_follow_the_extension() {
+        assert_eq!(mime_type(Path::new("/a/b.MP4")), "video/mp4");
+        assert_eq!(mime_type(Path::new("clip.mkv")), "video/x-matroska");
+        assert_eq!(mime_type(Path::new("shot.jpeg")), "image/jpeg");
+        assert_eq!(mime_type(Path::new("README")), "application/octet-stream");
+    }
+
+    #[test]
+    fn allowed_files_are_served_in_ranges() {
+        let dir = std::env::temp_dir().join(format!("media-protocol-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("my clip.webm");
+        fs::write(&video, b"0123456789").unwrap();
+        let other = dir.join("secret.txt");
+        fs::write(&other, b"no").unwrap();
+        let scope = MediaScope::default();
+        scope.allow_file(&video).unwrap();
+
+        let full = respond(&scope, &request(&video, None));
+        assert_eq!(full.status(), StatusCode::OK);
+        assert_eq!(full.headers()[header::CONTENT_TYPE], "video/webm");
+        assert_eq!(full.body(), b"0123456789");
+
+        let part = respond(&scope, &request(&video, Some("bytes=2-5")));
+        assert_eq!(part.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(part.headers()[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(part.headers()[header::CONTENT_LENGTH], "4");
+        assert_eq!(part.body(), b"2345");
+
+        let past = respond(&scope, &request(&video, Some("bytes=10-")));
+        assert_eq!(past.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(past.headers()[header::CONTENT_RANGE], "bytes */10");
+
+        let denied = respond(&scope, &request(&other, None));
+        assert_eq!(denied.status(), StatusCode::FORBIDDEN);
+
+        let mut head = request(&video, None);
+        *head.method_mut() = Method::HEAD;
+        let head = respond(&scope, &head);
+        assert_eq!(head.headers()[header::CONTENT_LENGTH], "10");
+        assert!(head.body().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn large_files_are_sent_whole_unless_a_range_is_asked_for() {
+        let dir = std::env::temp_dir().join(format!("media-chunks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("long.mp4");
+        let len = MAX_CHUNK + 10;
+        File::create(&video).unwrap().set_len(len).unwrap();
+        let scope = MediaScope::default();
+        scope.allow_file(&video).unwrap();
+
+        // An image or a fetch never asks for the rest, so it gets everything at once.
+        let whole = respond(&scope, &request(&video, None));
+        assert_eq!(whole.status(), StatusCode::OK);
+        assert!(whole.headers().get(header::CONTENT_RANGE).is_none());
+        assert_eq!(whole.headers()[header::CONTENT_LENGTH], len.to_string());
+        assert_eq!(whole.body().len() as u64, len);
+
+        let first = respond(&scope, &request(&video, Some("bytes=0-")));
+        assert_eq!(first.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            first.headers()[header::CONTENT_RANGE],
+            format!("bytes 0-{}/{}", MAX_CHUNK - 1, len)
+        );
+        assert_eq!(first.body().len() as u64, MAX_CHUNK);
+
+        let tail = respond(&scope, &request(&video, Some("bytes=5-99999999999")));
+        assert_eq!(
+            tail.headers()[header::CONTENT_RANGE],
+            format!("bytes 5-{}/{}", MAX_CHUNK + 4, len)
+        );
+        assert_eq!(tail.body().len() as u64, MAX_CHUNK);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
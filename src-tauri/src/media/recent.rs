@@ -0,0 +1,219 @@
+//! Recently opened media, newest first, with where each video was left off. Kept in
+//! the app data dir so the list survives restarts.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use super::MediaScope;
+
+const RECENT_FILE: &str = "recent-media.json";
+const MAX_RECENT: usize = 30;
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFile {
+    pub path: String,
+    /// Seconds into a video where playback stopped, if it stopped before the end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<f64>,
+    /// Seconds since the Unix epoch.
+    pub opened_at: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct RecentList {
+    files: Vec<RecentFile>,
+}
+
+impl RecentList {
+    /// Moves `path` to the top, keeping any saved position.
+    fn touch(&mut self, path: &str, opened_at: u64) {
+        let position = match self.files.iter().position(|f| f.path == path) {
+            Some(index) => self.files.remove(index).position,
+            None => None,
+        };
+        self.files.insert(
+            0,
+            RecentFile {
+                path: path.to_string(),
+                position,
+                opened_at,
+            },
+        );
+        self.files.truncate(MAX_RECENT);
+    }
+
+    /// Records where playback of `path` stopped; `None` once it played to the end.
+    fn set_position(&mut self, path: &str, position: Option<f64>) -> bool {
+        let Some(file) = self.files.iter_mut().find(|f| f.path == path) else {
+            return false;
+        };
+        let position = position.filter(|p| p.is_finite() && *p > 0.0);
+        let changed = file.position != position;
+        file.position = position;
+        changed
+    }
+
+    /// Drops files that no longer exist, returning whether any did.
+    fn prune(&mut self, exists: impl Fn(&Path) -> bool) -> bool {
+        let before = self.files.len();
+        self.files.retain(|f| exists(Path::new(&f.path)));
+        self.files.len() != before
+    }
+}
+
+/// The recent list, read from disk on first use.
+#[derive(Default)]
+pub struct RecentState(Mutex<Option<RecentList>>);
+
+fn recent_file(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(RECENT_FILE))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn loaded<'a>(slot: &'a mut Option<RecentList>, path: &Path) -> &'a mut RecentList {
+    // A damaged list isn't worth failing over; it starts again.
+    slot.get_or_insert_with(|| {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    })
+}
+
+/// Applies `change` to the list, saving it if `change` reports a difference, and
+/// returns the files that can still be opened.
+fn with_recent(
+    app: &AppHandle,
+    state: &RecentState,
+    change: impl FnOnce(&mut RecentList) -> bool,
+) -> Result<Vec<RecentFile>, String> {
+    let path = recent_file(app)?;
+    let mut slot = state.0.lock().unwrap();
+    let list = loaded(&mut slot, &path);
+
+    let pruned = list.prune(Path::is_file);
+    if change(list) || pruned {
+        let json = serde_json::to_string_pretty(&*list).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| e.to_string())?;
+    }
+    Ok(list.files.clone())
+}
+
+/// Lists recent files, forgetting ones that were moved or deleted. Listed files were
+/// all opened by the user before, so they may be played again.
+#[tauri::command]
+pub fn media_recent_list(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    scope: State<'_, MediaScope>,
+) -> Result<Vec<RecentFile>, String> {
+    let files = with_recent(&app, &state, |_| false)?;
+    for file in &files {
+        if let Err(e) = scope.allow_file(&file.path) {
+            log::warn!("Failed to allow {}: {}", file.path, e);
+        }
+    }
+    Ok(files)
+}
+
+/// Records that `paths` were opened. Only files the player may already serve are
+/// added, so the list can't be used to reach anything else.
+#[tauri::command]
+pub fn media_recent_add(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    scope: State<'_, MediaScope>,
+    paths: Vec<String>,
+) -> Result<Vec<RecentFile>, String> {
+    let opened_at = now();
+    with_recent(&app, &state, |list| {
+        let allowed: Vec<&String> = paths
+            .iter()
+            .filter(|path| scope.resolve(Path::new(path)).is_some())
+            .collect();
+        // Added in reverse so the first path ends up on top.
+        for path in allowed.iter().rev() {
+            list.touch(path, opened_at);
+        }
+        !allowed.is_empty()
+    })
+}
+
+#[tauri::command]
+pub fn media_recent_position(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    path: String,
+    position: Option<f64>,
+) -> Result<Vec<RecentFile>, String> {
+    with_recent(&app, &state, |list| list.set_position(&path, position))
+}
+
+#[tauri::command]
+pub fn media_recent_clear(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+) -> Result<Vec<RecentFile>, String> {
+    with_recent(&app, &state, |list| {
+        let had_files = !list.files.is_empty();
+        list.files.clear();
+        had_files
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(list: &RecentList) -> Vec<&str> {
+        list.files.iter().map(|f| f.path.as_str()).collect()
+    }
+
+    #[test]
+    fn touching_moves_a_file_to_the_top_and_keeps_its_position() {
+        let mut list = RecentList::default();
+        list.touch("/a.mp4", 1);
+        list.touch("/b.png", 2);
+        assert!(list.set_position("/a.mp4", Some(93.5)));
+        assert!(!list.set_position("/a.mp4", Some(93.5)));
+        assert!(!list.set_position("/missing.mp4", Some(1.0)));
+
+        list.touch("/a.mp4", 3);
+        assert_eq!(paths(&list), vec!["/a.mp4", "/b.png"]);
+        assert_eq!(list.files[0].position, Some(93.5));
+        assert_eq!(list.files[0].opened_at, 3);
+
+        assert!(list.set_position("/a.mp4", None));
+        assert_eq!(list.files[0].position, None);
+        list.set_position("/a.mp4", Some(f64::NAN));
+        assert_eq!(list.files[0].position, None);
+    }
+
+    #[test]
+    fn the_list_is_capped_and_pruned() {
+        let mut list = RecentList::default();
+        for i in 0..MAX_RECENT + 5 {
+            list.touch(&format!("/{}.png", i), i as u64);
+        }
+        assert_eq!(list.files.len(), MAX_RECENT);
+        assert_eq!(list.files[0].path, format!("/{}.png", MAX_RECENT + 4));
+
+        assert!(list.prune(|path| path != Path::new("/34.png")));
+        assert_eq!(list.files.len(), MAX_RECENT - 1);
+        assert!(!list.prune(|_| true));
+    }
+}
@@ -0,0 +1,136 @@
+//! Subtitle files for the video player. They're found and read here; the webview
+//! parses them and turns SubRip into WebVTT for its `<track>`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+use super::MediaScope;
+
+const EXTENSIONS: &[&str] = &["srt", "vtt"];
+/// Larger files aren't subtitles anyone meant to open.
+const MAX_SUBTITLE_BYTES: u64 = 10 * 1024 * 1024;
+
+fn is_subtitle(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Subtitle files next to `video` named after it, such as `film.srt` or
+/// `film.en.vtt` for `film.mp4`. One with exactly the video's name comes first.
+pub fn sibling_subtitles(video: &Path) -> Vec<PathBuf> {
+    let (Some(folder), Some(stem)) = (video.parent(), video.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy();
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(bool, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && is_subtitle(path))
+        .filter_map(|path| {
+            let sub_stem = path.file_stem()?.to_string_lossy().into_owned();
+            let exact = sub_stem == stem;
+            let tagged = sub_stem
+                .strip_prefix(stem.as_ref())
+                .is_some_and(|rest| rest.starts_with('.'));
+            (exact || tagged).then_some((!exact, path))
+        })
+        .collect();
+    found.sort();
+    found.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Text in UTF-8, or failing that Latin-1, which older SubRip files often are.
+fn decode(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|b| *b as char).collect(),
+    }
+}
+
+/// The subtitle files found next to `video`, which must be open in the player.
+#[tauri::command]
+pub fn subtitles_find(video: String, scope: State<'_, MediaScope>) -> Result<Vec<String>, String> {
+    let video = scope
+        .resolve(Path::new(&video))
+        .ok_or_else(|| format!("{} hasn't been opened in the app", video))?;
+    let mut paths = Vec::new();
+    for path in sibling_subtitles(&video) {
+        scope.allow_file(&path).map_err(|e| e.to_string())?;
+        paths.push(path.to_string_lossy().into_owned());
+    }
+    Ok(paths)
+}
+
+/// Asks for a subtitle file to load. Returns its path, or `None` if cancelled.
+#[tauri::command]
+pub async fn subtitles_open_dialog(app: AppHandle) -> Result<Option<String>, String> {
+    let Some(chosen) = app
+        .dialog()
+        .file()
+        .add_filter("Subtitles", EXTENSIONS)
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = chosen.into_path().map_err(|e| e.to_string())?;
+    app.state::<MediaScope>()
+        .allow_file(&path)
+        .map_err(|e| e.to_string())?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+/// The text of a subtitle file that was found or chosen.
+#[tauri::command]
+pub fn subtitles_read(path: String, scope: State<'_, MediaScope>) -> Result<String, String> {
+    let resolved = scope
+        .resolve(Path::new(&path))
+        .filter(|resolved| is_subtitle(resolved))
+        .ok_or_else(|| format!("{} isn't an open subtitle file", path))?;
+    let len = fs::metadata(&resolved).map_err(|e| e.to_string())?.len();
+    if len > MAX_SUBTITLE_BYTES {
+        return Err(format!("{} is too large for a subtitle file", path));
+    }
+    let bytes = fs::read(&resolved).map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+    Ok(decode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn siblings_named_after_the_video_are_found() {
+        let dir = std::env::temp_dir().join(format!("subtitles-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "film.mp4",
+            "film.en.vtt",
+            "film.SRT",
+            "film.de.srt",
+            "filmography.srt",
+            "other.srt",
+            "film.txt",
+        ] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        let found = sibling_subtitles(&dir.join("film.mp4"));
+        fs::remove_dir_all(&dir).unwrap();
+        let names: Vec<_> = found
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["film.SRT", "film.de.srt", "film.en.vtt"]);
+    }
+
+    #[test]
+    fn latin1_files_are_decoded() {
+        assert_eq!(decode("Grüße".as_bytes().to_vec()), "Grüße");
+        assert_eq!(decode(vec![b'G', 0xFC, b'e']), "Güe");
+    }
+}
@@ -0,0 +1,224 @@
+//! Long-term memory: short facts about the user that are added to the system prompt
+//! of new conversations. Kept in the app data dir so every window and every
+//! frontend build sees the same list.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+const MEMORY_FILE: &str = "memories.json";
+/// Memories go into every new conversation's context, so each one stays short.
+const MAX_MEMORY_CHARS: usize = 500;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Memory {
+    pub id: u64,
+    pub text: String,
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryFile {
+    next_id: u64,
+    memories: Vec<Memory>,
+}
+
+fn clean(text: &str) -> Result<String, String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err("A memory can't be empty".to_string());
+    }
+    if text.chars().count() > MAX_MEMORY_CHARS {
+        return Err(format!(
+            "Keep memories under {} characters",
+            MAX_MEMORY_CHARS
+        ));
+    }
+    Ok(text)
+}
+
+impl MemoryFile {
+    fn position(&self, id: u64) -> Result<usize, String> {
+        self.memories
+            .iter()
+            .position(|m| m.id == id)
+            .ok_or_else(|| format!("No memory with id {}", id))
+    }
+
+    fn is_duplicate(&self, text: &str, except: Option<u64>) -> bool {
+        self.memories
+            .iter()
+            .any(|m| Some(m.id) != except && m.text.eq_ignore_ascii_case(text))
+    }
+
+    fn add(&mut self, text: &str, created_at: u64) -> Result<(), String> {
+        let text = clean(text)?;
+        if self.is_duplicate(&text, None) {
+            return Err("That memory is already saved".to_string());
+        }
+        self.next_id += 1;
+        self.memories.push(Memory {
+            id: self.next_id,
+            text,
+            created_at,
+        });
+        Ok(())
+    }
+
+    fn update(&mut self, id: u64, text: &str) -> Result<(), String> {
+        let text = clean(text)?;
+        let index = self.position(id)?;
+        if self.is_duplicate(&text, Some(id)) {
+            return Err("That memory is already saved".to_string());
+        }
+        self.memories[index].text = text;
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u64) -> Result<(), String> {
+        let index = self.position(id)?;
+        self.memories.remove(index);
+        Ok(())
+    }
+}
+
+/// The memory list, read from disk on first use.
+#[derive(Default)]
+pub struct MemoryState(Mutex<Option<MemoryFile>>);
+
+fn memory_file(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(MEMORY_FILE))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn loaded<'a>(slot: &'a mut Option<MemoryFile>, path: &Path) -> Result<&'a mut MemoryFile, String> {
+    if slot.is_none() {
+        let file = match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| format!("Error reading {}: {}", MEMORY_FILE, e))?,
+            Err(_) => MemoryFile::default(),
+        };
+        *slot = Some(file);
+    }
+    Ok(slot.as_mut().unwrap())
+}
+
+/// Applies `change` to a copy of the memory list and saves it, returning the new list.
+/// Nothing changes when `change` or the write fails.
+fn with_memories(
+    app: &AppHandle,
+    state: &MemoryState,
+    change: impl FnOnce(&mut MemoryFile) -> Result<(), String>,
+) -> Result<Vec<Memory>, String> {
+    let path = memory_file(app)?;
+    let mut slot = state.0.lock().unwrap();
+    let file = loaded(&mut slot, &path)?;
+
+    let mut updated = file.clone();
+    change(&mut updated)?;
+    let json = serde_json::to_string_pretty(&updated).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    *file = updated;
+    Ok(file.memories.clone())
+}
+
+#[tauri::command]
+pub fn memory_list(app: AppHandle, state: State<'_, MemoryState>) -> Result<Vec<Memory>, String> {
+    let path = memory_file(&app)?;
+    let mut slot = state.0.lock().unwrap();
+    Ok(loaded(&mut slot, &path)?.memories.clone())
+}
+
+#[tauri::command]
+pub fn memory_add(
+    app: AppHandle,
+    state: State<'_, MemoryState>,
+    text: String,
+) -> Result<Vec<Memory>, String> {
+    with_memories(&app, &state, |file| file.add(&text, now()))
+}
+
+#[tauri::command]
+pub fn memory_update(
+    app: AppHandle,
+    state: State<'_, MemoryState>,
+    id: u64,
+    text: String,
+) -> Result<Vec<Memory>, String> {
+    with_memories(&app, &state, |file| file.update(id, &text))
+}
+
+#[tauri::command]
+pub fn memory_delete(
+    app: AppHandle,
+    state: State<'_, MemoryState>,
+    id: u64,
+) -> Result<Vec<Memory>, String> {
+    with_memories(&app, &state, |file| file.delete(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(file: &MemoryFile) -> Vec<&str> {
+        file.memories.iter().map(|m| m.text.as_str()).collect()
+    }
+
+    #[test]
+    fn add_normalises_and_rejects_duplicates() {
+        let mut file = MemoryFile::default();
+        file.add("  Our backend is   written in Rust ", 1).unwrap();
+        file.add("Prefers British spelling", 2).unwrap();
+        assert_eq!(
+            texts(&file),
+            vec!["Our backend is written in Rust", "Prefers British spelling"]
+        );
+        assert_eq!(file.memories[1].id, 2);
+
+        assert!(file.add("our backend is written in rust", 3).is_err());
+        assert!(file.add("   ", 3).is_err());
+        assert!(file.add(&"x".repeat(MAX_MEMORY_CHARS + 1), 3).is_err());
+        assert_eq!(file.memories.len(), 2);
+    }
+
+    #[test]
+    fn update_and_delete_by_id() {
+        let mut file = MemoryFile::default();
+        file.add("Uses tabs", 1).unwrap();
+        file.add("Deploys on Fridays", 1).unwrap();
+
+        file.update(1, "Uses four spaces").unwrap();
+        assert!(file.update(2, "uses four spaces").is_err());
+        assert!(file.update(9, "Anything").is_err());
+
+        file.delete(2).unwrap();
+        assert!(file.delete(2).is_err());
+        assert_eq!(texts(&file), vec!["Uses four spaces"]);
+    }
+
+    #[test]
+    fn ids_are_not_reused_after_delete() {
+        let mut file = MemoryFile::default();
+        file.add("One", 1).unwrap();
+        file.add("Two", 1).unwrap();
+        file.delete(2).unwrap();
+        file.add("Three", 1).unwrap();
+        assert_eq!(file.memories[1].id, 3);
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{
+    AppHandle, Emitter, Manager, State, UserAttentionType, WebviewWindow, Window, WindowEvent,
+};
+use tauri_plugin_notification::NotificationExt;
+
+/// How long after a notification is posted that focusing the window still counts as
+/// clicking it.
+const CLICK_WINDOW: Duration = Duration::from_secs(10);
+
+/// Conversation named by the last notification, and when it was posted.
+///
+/// The notification plugin can't report clicks on desktop, so this is a heuristic:
+/// clicking one brings the app to the front, and the window gaining focus soon after a
+/// notification is taken to be that click. Past [`CLICK_WINDOW`] it's more likely the
+/// user came back some other way, so focusing the window leaves the page alone.
+#[derive(Default)]
+pub struct PendingConversation(Mutex<Option<(u32, Instant)>>);
+
+/// Posts "Response ready" when a reply finishes while the window is in the background.
+/// Returns whether a notification was shown.
+#[tauri::command]
+pub fn notify_reply_ready(
+    app: AppHandle,
+    window: WebviewWindow,
+    pending: State<'_, PendingConversation>,
+    model: String,
+    snippet: String,
+    conversation_id: u32,
+) -> Result<bool, String> {
+    if window.is_focused().map_err(|e| e.to_string())? {
+        return Ok(false);
+    }
+
+    app.notification()
+        .builder()
+        .title(format!("Response ready from {}", model))
+        .body(snippet)
+        .show()
+        .map_err(|e| e.to_string())?;
+    *pending.0.lock().unwrap() = Some((conversation_id, Instant::now()));
+
+    if let Err(e) = window.request_user_attention(Some(UserAttentionType::Informational)) {
+        log::warn!("Failed to request attention: {}", e);
+    }
+    Ok(true)
+}
+
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::Focused(true) = event {
+        let pending = window
+            .state::<PendingConversation>()
+            .0
+            .lock()
+            .unwrap()
+            .take();
+        let clicked = pending.filter(|(_, posted)| posted.elapsed() < CLICK_WINDOW);
+        if let Some((id, _)) = clicked {
+            if let Err(e) = window.emit("open-conversation", id) {
+                log::warn!("Failed to emit open-conversation: {}", e);
+            }
+        }
+    }
+}
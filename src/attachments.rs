@@ -0,0 +1,86 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use js_sys::Uint8Array;
+use reqwasm::http::Request;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Blob;
+
+/// Drag data type carrying the URL of the image shown in the media player.
+pub const MEDIA_DRAG_TYPE: &str = "application/x-media-url";
+
+/// The image format of `bytes`, judged from its signature.
+pub fn image_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Base64 for an attachment, the form Ollama takes images in. `None` unless `bytes`
+/// is an image.
+pub fn encode_image(bytes: &[u8]) -> Option<String> {
+    image_mime(bytes).map(|_| STANDARD.encode(bytes))
+}
+
+/// A `data:` URL to preview an attachment stored as base64.
+pub fn data_url(image: &str) -> String {
+    // 16 base64 characters decode to the 12 bytes the signatures need.
+    let head = STANDARD
+        .decode(image.get(..16).unwrap_or(image))
+        .unwrap_or_default();
+    let mime = image_mime(&head).unwrap_or("image/png");
+    format!("data:{};base64,{}", mime, image)
+}
+
+pub async fn read_blob(blob: &Blob) -> Result<Vec<u8>, String> {
+    let buffer = JsFuture::from(blob.array_buffer())
+        .await
+        .map_err(|e| format!("Couldn't read the file: {:?}", e))?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+/// Reads an image the page already holds a URL for, such as the media player's.
+pub async fn fetch_image(url: &str) -> Result<Vec<u8>, String> {
+    let response = Request::get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't read the image: {}", e))?;
+    response
+        .binary()
+        .await
+        .map_err(|e| format!("Couldn't read the image: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn images_are_recognised_by_signature() {
+        assert_eq!(image_mime(PNG), Some("image/png"));
+        assert_eq!(image_mime(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+        assert_eq!(image_mime(b"GIF89a..."), Some("image/gif"));
+        assert_eq!(image_mime(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(image_mime(b"RIFF\0\0\0\0AVI "), None);
+        assert_eq!(image_mime(b"%PDF-1.7"), None);
+        assert_eq!(encode_image(b"plain text"), None);
+    }
+
+    #[test]
+    fn previews_carry_the_right_mime_type() {
+        let png = encode_image(PNG).unwrap();
+        assert!(data_url(&png).starts_with("data:image/png;base64,iVBOR"));
+        let jpeg = encode_image(b"\xff\xd8\xff\xe0\0\x10JFIF\0\x01\x01\0").unwrap();
+        assert!(data_url(&jpeg).starts_with("data:image/jpeg;base64,/9j/"));
+        assert_eq!(data_url("????"), "data:image/png;base64,????");
+    }
+}
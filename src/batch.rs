@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::tauri;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchConfig {
+    pub input_path: String,
+    pub output_path: String,
+    pub api_url: String,
+    pub model: String,
+    pub template: String,
+    pub system: String,
+    pub concurrency: usize,
+}
+
+#[derive(Clone, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub resumed: usize,
+    pub running: bool,
+    pub paused: bool,
+    pub error: Option<String>,
+    pub output_path: String,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct BatchPreview {
+    pub columns: Vec<String>,
+    pub sample: Vec<Map<String, Value>>,
+    pub total: usize,
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PreviewArgs<'a> {
+    input_path: &'a str,
+}
+
+#[derive(Serialize)]
+struct StartArgs<'a> {
+    config: &'a BatchConfig,
+}
+
+/// Suggested output next to the input: `tickets.csv` becomes `tickets.results.csv`.
+pub fn default_output_path(input_path: &str) -> String {
+    match input_path.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains(['/', '\\']) => format!("{}.results.{}", stem, ext),
+        _ => format!("{}.results.jsonl", input_path),
+    }
+}
+
+pub async fn preview(input_path: &str) -> Result<BatchPreview, String> {
+    tauri::invoke("batch_preview", &PreviewArgs { input_path }).await
+}
+
+pub async fn start(config: &BatchConfig) -> Result<BatchProgress, String> {
+    tauri::invoke("batch_start", &StartArgs { config }).await
+}
+
+pub async fn pause() -> Result<(), String> {
+    tauri::invoke("batch_pause", &NoArgs {}).await
+}
+
+pub async fn resume() -> Result<(), String> {
+    tauri::invoke("batch_resume", &NoArgs {}).await
+}
+
+pub async fn cancel() -> Result<(), String> {
+    tauri::invoke("batch_cancel", &NoArgs {}).await
+}
+
+pub async fn status() -> Result<Option<BatchProgress>, String> {
+    tauri::invoke("batch_status", &NoArgs {}).await
+}
+
+pub async fn interrupted() -> Result<Option<BatchConfig>, String> {
+    tauri::invoke("batch_interrupted", &NoArgs {}).await
+}
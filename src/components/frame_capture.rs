@@ -0,0 +1,370 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlInputElement, HtmlVideoElement};
+use yew::prelude::*;
+
+use crate::frames::{self, capture_times, parse_time, MAX_FRAMES};
+use crate::media::{file_name, format_position};
+use crate::tauri;
+
+/// How many of the latest captures are listed.
+const SHOWN_CAPTURES: usize = 12;
+
+#[derive(Properties, PartialEq)]
+pub struct FrameCaptureProps {
+    /// The `<video>` to capture from. Its source must allow being read from a canvas.
+    pub video: NodeRef,
+    /// The video's file name, which saved frames are named after.
+    pub name: AttrValue,
+    /// Shows a saved frame in the image viewer.
+    pub onview: Callback<String>,
+}
+
+/// The frame on screen as base64 PNG data.
+fn grab(video: &HtmlVideoElement) -> Result<String, String> {
+    let (width, height) = (video.video_width(), video.video_height());
+    if width == 0 || height == 0 {
+        return Err("The video hasn't shown a frame yet".to_string());
+    }
+    let canvas: HtmlCanvasElement = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("canvas").ok())
+        .and_then(|element| element.dyn_into().ok())
+        .ok_or("Couldn't create a canvas")?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into().ok())
+        .ok_or("Couldn't draw the frame")?;
+    context
+        .draw_image_with_html_video_element(video, 0.0, 0.0)
+        .map_err(|_| "Couldn't draw the frame")?;
+    // Fails for a video from another origin that doesn't allow it.
+    let url = canvas
+        .to_data_url_with_type("image/png")
+        .map_err(|_| "This video's frames can't be read")?;
+    url.split_once(',')
+        .map(|(_, data)| data.to_string())
+        .ok_or_else(|| "Couldn't encode the frame".to_string())
+}
+
+/// Moves `video` to `time` and waits until the frame there can be drawn.
+async fn seek(video: &HtmlVideoElement, time: f64) {
+    let seeked = js_sys::Promise::new(&mut |resolve, _| video.set_onseeked(Some(&resolve)));
+    video.set_current_time(time);
+    let _ = JsFuture::from(seeked).await;
+    video.set_onseeked(None);
+}
+
+/// The folder frames go in, asking for one the first time.
+async fn ensure_folder(folder: &UseStateHandle<Option<String>>) -> Result<Option<String>, String> {
+    if let Some(chosen) = (**folder).clone() {
+        return Ok(Some(chosen));
+    }
+    let chosen = frames::choose_folder().await?;
+    folder.set(chosen.clone());
+    Ok(chosen)
+}
+
+fn remember(captured: &UseStateHandle<Vec<String>>, latest: &mut Vec<String>, path: String) {
+    latest.insert(0, path);
+    latest.truncate(SHOWN_CAPTURES);
+    captured.set(latest.clone());
+}
+
+/// Saves the current frame of a video, or one every few seconds over a range, as
+/// PNGs in a folder chosen through the backend.
+#[function_component(FrameCapture)]
+pub fn frame_capture(props: &FrameCaptureProps) -> Html {
+    let folder = use_state(|| None::<String>);
+    let every = use_state(|| "5".to_string());
+    let from = use_state(String::new);
+    let to = use_state(String::new);
+    // Frames done and to do while capturing a range.
+    let progress = use_state(|| None::<(usize, usize)>);
+    let stop = use_mut_ref(|| false);
+    // A range cut short to `MAX_FRAMES`, waiting for the go-ahead.
+    let cut_range = use_state(|| None::<Vec<f64>>);
+    let captured = use_state(Vec::<String>::new);
+    let error = use_state(|| None::<String>);
+
+    {
+        let folder = folder.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match frames::folder().await {
+                    Ok(chosen) => folder.set(chosen),
+                    Err(e) => log::warn!("Failed to load the frames folder: {}", e),
+                }
+            });
+        });
+    }
+
+    let on_choose_folder = {
+        let folder = folder.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let folder = folder.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match frames::choose_folder().await {
+                    Ok(chosen) => folder.set(chosen),
+                    Err(e) => error.set(Some(format!("Couldn't choose the folder: {}", e))),
+                }
+            });
+        })
+    };
+
+    let on_capture = {
+        let video = props.video.clone();
+        let name = props.name.clone();
+        let folder = folder.clone();
+        let captured = captured.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(video) = video.cast::<HtmlVideoElement>() else {
+                return;
+            };
+            let time = video.current_time();
+            // Grabbed before anything is awaited, so it's the frame that was showing.
+            let png = grab(&video);
+            let name = name.clone();
+            let folder = folder.clone();
+            let captured = captured.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let saved = match (png, ensure_folder(&folder).await) {
+                    (Ok(png), Ok(Some(_))) => frames::save(&name, time, &png).await,
+                    (_, Ok(None)) => return,
+                    (Err(e), _) | (_, Err(e)) => Err(e),
+                };
+                match saved {
+                    Ok(path) => {
+                        error.set(None);
+                        remember(&captured, &mut (*captured).clone(), path);
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't capture the frame: {}", e))),
+                }
+            });
+        })
+    };
+
+    let start_range = {
+        let video = props.video.clone();
+        let name = props.name.clone();
+        let folder = folder.clone();
+        let progress = progress.clone();
+        let stop = stop.clone();
+        let captured = captured.clone();
+        let error = error.clone();
+        Callback::from(move |times: Vec<f64>| {
+            let Some(video) = video.cast::<HtmlVideoElement>() else {
+                return;
+            };
+            *stop.borrow_mut() = false;
+            let name = name.clone();
+            let folder = folder.clone();
+            let progress = progress.clone();
+            let stop = stop.clone();
+            let captured = captured.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match ensure_folder(&folder).await {
+                    Ok(Some(_)) => {}
+                    Ok(None) => return,
+                    Err(e) => {
+                        error.set(Some(format!("Couldn't choose the folder: {}", e)));
+                        return;
+                    }
+                }
+                error.set(None);
+                let resume_at = video.current_time();
+                let _ = video.pause();
+                let mut latest = (*captured).clone();
+                for (done, time) in times.iter().enumerate() {
+                    if *stop.borrow() {
+                        break;
+                    }
+                    progress.set(Some((done, times.len())));
+                    seek(&video, *time).await;
+                    let saved = match grab(&video) {
+                        Ok(png) => frames::save(&name, *time, &png).await,
+                        Err(e) => Err(e),
+                    };
+                    match saved {
+                        Ok(path) => remember(&captured, &mut latest, path),
+                        Err(e) => {
+                            error.set(Some(format!(
+                                "Stopped at {}: {}",
+                                format_position(*time),
+                                e
+                            )));
+                            break;
+                        }
+                    }
+                }
+                progress.set(None);
+                seek(&video, resume_at).await;
+            });
+        })
+    };
+
+    let on_capture_range = {
+        let video = props.video.clone();
+        let every = every.clone();
+        let from = from.clone();
+        let to = to.clone();
+        let cut_range = cut_range.clone();
+        let error = error.clone();
+        let start_range = start_range.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(video) = video.cast::<HtmlVideoElement>() else {
+                return;
+            };
+            let range = (
+                parse_time(&every).filter(|every| *every > 0.0),
+                if from.trim().is_empty() {
+                    Some(0.0)
+                } else {
+                    parse_time(&from)
+                },
+                if to.trim().is_empty() {
+                    Some(video.duration())
+                } else {
+                    parse_time(&to)
+                },
+            );
+            let (Some(every), Some(start), Some(end)) = range else {
+                error.set(Some(
+                    "Enter the range as seconds or m:ss, and a step above zero".to_string(),
+                ));
+                return;
+            };
+            let (times, cut) = capture_times(start, end.min(video.duration()), every);
+            if times.is_empty() {
+                error.set(Some("There are no frames in that range".to_string()));
+                return;
+            }
+            if cut {
+                cut_range.set(Some(times));
+                return;
+            }
+            cut_range.set(None);
+            start_range.emit(times);
+        })
+    };
+
+    let on_capture_cut = {
+        let cut_range = cut_range.clone();
+        let start_range = start_range.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(times) = (*cut_range).clone() {
+                cut_range.set(None);
+                start_range.emit(times);
+            }
+        })
+    };
+
+    let on_cancel_cut = {
+        let cut_range = cut_range.clone();
+        Callback::from(move |_: MouseEvent| cut_range.set(None))
+    };
+
+    let on_stop = {
+        let stop = stop.clone();
+        Callback::from(move |_: MouseEvent| *stop.borrow_mut() = true)
+    };
+
+    let text_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        let cut_range = cut_range.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            state.set(input.value());
+            // The warning was about the range as it was.
+            cut_range.set(None);
+        })
+    };
+
+    let view = |path: &String| {
+        let onview = props.onview.clone();
+        let path = path.clone();
+        Callback::from(move |_: MouseEvent| onview.emit(path.clone()))
+    };
+
+    let busy = progress.is_some();
+    let input_class =
+        "input input-bordered input-xs w-20 rounded-lg bg-base-100 border-base-content/10";
+
+    html! {
+        <div class="rounded-2xl bg-base-content/5 p-3 space-y-3 text-left text-sm">
+            <div class="flex flex-wrap items-center gap-2">
+                <button onclick={on_capture} class="btn btn-sm btn-primary rounded-xl" disabled={busy}>
+                    { "Capture frame" }
+                </button>
+                <span class="text-xs opacity-60 truncate ml-auto" title={(*folder).clone()}>
+                    { (*folder).as_deref().map_or("No folder chosen", file_name) }
+                </span>
+                <button onclick={on_choose_folder} class="btn btn-xs btn-ghost" disabled={busy}>{ "Folder..." }</button>
+            </div>
+
+            <div class="flex flex-wrap items-center gap-2">
+                <span class="text-xs opacity-60">{ "Every" }</span>
+                <input class={input_class} value={(*every).clone()} oninput={text_input(&every)} />
+                <span class="text-xs opacity-60">{ "s from" }</span>
+                <input class={input_class} placeholder="0:00" value={(*from).clone()} oninput={text_input(&from)} />
+                <span class="text-xs opacity-60">{ "to" }</span>
+                <input class={input_class} placeholder="end" value={(*to).clone()} oninput={text_input(&to)} />
+                if let Some((done, total)) = *progress {
+                    <progress class="progress progress-primary w-24" value={done.to_string()} max={total.to_string()} />
+                    <span class="text-xs opacity-60">{ format!("{} of {}", done, total) }</span>
+                    <button onclick={on_stop} class="btn btn-xs">{ "Stop" }</button>
+                } else {
+                    <button onclick={on_capture_range} class="btn btn-sm rounded-xl">{ "Capture range" }</button>
+                }
+            </div>
+
+            if let Some(times) = (*cut_range).as_ref() {
+                <div class="alert alert-warning rounded-xl py-2 text-sm">
+                    <span class="flex-1">
+                        {
+                            format!(
+                                "That range has more than {} frames, so capturing stops at {}. Use a larger step to cover all of it.",
+                                MAX_FRAMES,
+                                format_position(times.last().copied().unwrap_or_default())
+                            )
+                        }
+                    </span>
+                    <button onclick={on_capture_cut} class="btn btn-xs btn-warning">
+                        { format!("Capture {}", times.len()) }
+                    </button>
+                    <button onclick={on_cancel_cut} class="btn btn-xs btn-ghost">{ "Cancel" }</button>
+                </div>
+            }
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-xl py-2 text-sm">{ message }</div>
+            }
+
+            if !captured.is_empty() {
+                <div class="flex gap-2 overflow-x-auto pb-1">
+                    {
+                        for captured.iter().map(|path| html! {
+                            <button
+                                key={path.clone()}
+                                onclick={view(path)}
+                                class="shrink-0 rounded-lg overflow-hidden ring-1 ring-base-content/10 hover:ring-primary"
+                                title={format!("Show {} in the viewer", file_name(path))}
+                            >
+                                <img src={tauri::file_url(path)} alt={file_name(path).to_string()} class="h-16 w-auto" />
+                            </button>
+                        })
+                    }
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,378 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::ImageViewer;
+use crate::image_edit::{self, Dimensions, Edits, Export, Preview, Rect};
+use crate::library::format_size;
+use crate::tauri;
+use crate::viewer::Size;
+
+#[derive(Properties, PartialEq)]
+pub struct ImageEditorProps {
+    /// The original on disk, which is only ever read.
+    pub path: AttrValue,
+    pub url: AttrValue,
+    #[prop_or_default]
+    pub alt: AttrValue,
+    #[prop_or_default]
+    pub ondragstart: Option<Callback<DragEvent>>,
+    /// Opens a saved copy in the player.
+    pub onopen: Callback<String>,
+    pub onclose: Callback<()>,
+}
+
+fn number(e: &Event) -> Option<u32> {
+    let input: HtmlInputElement = e.target_unchecked_into();
+    input.value().trim().parse().ok()
+}
+
+/// Crop, turn, flip, resize and convert an image, previewed in the viewer before it
+/// is saved as a new file.
+#[function_component(ImageEditor)]
+pub fn image_editor(props: &ImageEditorProps) -> Html {
+    let original = use_state_eq(|| None::<Dimensions>);
+    let edits = use_state(Edits::default);
+    let export = use_state(|| Export::Png);
+    let keep_aspect = use_state(|| true);
+    // The preview shown, and what it was rendered from.
+    let preview = use_state(|| None::<(Preview, Edits, Export)>);
+    let busy = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    let saved = use_state(|| None::<String>);
+
+    {
+        let original = original.clone();
+        let edits = edits.clone();
+        let preview = preview.clone();
+        let error = error.clone();
+        let saved = saved.clone();
+        use_effect_with(props.path.clone(), move |_| {
+            original.set(None);
+            edits.set(Edits::default());
+            preview.set(None);
+            error.set(None);
+            saved.set(None);
+        });
+    }
+
+    let on_image_load = {
+        let original = original.clone();
+        let showing_original = preview.is_none();
+        Callback::from(move |size: Size| {
+            if showing_original {
+                original.set(Some(Dimensions {
+                    width: size.width as u32,
+                    height: size.height as u32,
+                }));
+            }
+        })
+    };
+
+    let Some(size) = *original else {
+        // The editor needs the image's size, which the viewer reports once loaded.
+        return html! {
+            <ImageViewer src={props.url.clone()} alt={props.alt.clone()} onload={on_image_load} />
+        };
+    };
+
+    let whole = Rect {
+        x: 0,
+        y: 0,
+        width: size.width,
+        height: size.height,
+    };
+    let crop = edits.crop.unwrap_or(whole);
+    let turned = edits.turned_size(size);
+    let output = edits.output_size(size);
+
+    let set_crop = |field: fn(&mut Rect, u32)| {
+        let edits = edits.clone();
+        Callback::from(move |e: Event| {
+            let Some(value) = number(&e) else {
+                return;
+            };
+            let mut crop = edits.crop.unwrap_or(whole);
+            field(&mut crop, value);
+            let crop = crop.clamped(size);
+            edits.set(Edits {
+                crop: (crop != whole).then_some(crop),
+                // The old size was for the old crop.
+                resize: None,
+                ..(*edits).clone()
+            });
+        })
+    };
+
+    let set_size = |width: bool| {
+        let edits = edits.clone();
+        let keep_aspect = *keep_aspect;
+        Callback::from(move |e: Event| {
+            let Some(value) = number(&e).filter(|v| *v > 0) else {
+                return;
+            };
+            let turned = edits.turned_size(size);
+            let current = edits.output_size(size);
+            let resized = match (width, keep_aspect) {
+                (true, true) => turned.with_width(value),
+                (false, true) => turned.with_height(value),
+                (true, false) => Dimensions {
+                    width: value,
+                    ..current
+                },
+                (false, false) => Dimensions {
+                    height: value,
+                    ..current
+                },
+            };
+            edits.set(Edits {
+                resize: (resized != turned).then_some(resized),
+                ..(*edits).clone()
+            });
+        })
+    };
+
+    let on_keep_aspect = {
+        let keep_aspect = keep_aspect.clone();
+        Callback::from(move |_: Event| keep_aspect.set(!*keep_aspect))
+    };
+
+    let turn = |clockwise: bool| {
+        let edits = edits.clone();
+        Callback::from(move |_: MouseEvent| edits.set(edits.rotated(clockwise)))
+    };
+
+    let flip = |horizontally: bool| {
+        let edits = edits.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut flipped = (*edits).clone();
+            if horizontally {
+                flipped.flip_x = !flipped.flip_x;
+            } else {
+                flipped.flip_y = !flipped.flip_y;
+            }
+            edits.set(flipped);
+        })
+    };
+
+    let on_format = {
+        let export = export.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            export.set(match select.value().as_str() {
+                "jpeg" => Export::Jpeg { quality: 85 },
+                "webp" => Export::Webp,
+                _ => Export::Png,
+            });
+        })
+    };
+
+    let on_quality = {
+        let export = export.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(quality) = input.value().parse() {
+                export.set(Export::Jpeg { quality });
+            }
+        })
+    };
+
+    let on_preview = {
+        let edits = edits.clone();
+        let export = export.clone();
+        let preview = preview.clone();
+        let busy = busy.clone();
+        let error = error.clone();
+        let path = props.path.clone();
+        Callback::from(move |_: MouseEvent| {
+            let (edits, export) = ((*edits).clone(), *export);
+            let preview = preview.clone();
+            let busy = busy.clone();
+            let error = error.clone();
+            let path = path.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                match image_edit::preview(&path, &edits, export).await {
+                    Ok(rendered) => {
+                        error.set(None);
+                        preview.set(Some((rendered, edits, export)));
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't preview the edits: {}", e))),
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let on_save = {
+        let edits = edits.clone();
+        let export = export.clone();
+        let busy = busy.clone();
+        let error = error.clone();
+        let saved = saved.clone();
+        let path = props.path.clone();
+        Callback::from(move |_: MouseEvent| {
+            let (edits, export) = ((*edits).clone(), *export);
+            let busy = busy.clone();
+            let error = error.clone();
+            let saved = saved.clone();
+            let path = path.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                match image_edit::save(&path, &edits, export).await {
+                    Ok(Some(target)) => {
+                        error.set(None);
+                        saved.set(Some(target));
+                    }
+                    Ok(None) => {}
+                    Err(e) => error.set(Some(format!("Couldn't save the image: {}", e))),
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let on_reset = {
+        let edits = edits.clone();
+        let preview = preview.clone();
+        Callback::from(move |_: MouseEvent| {
+            edits.set(Edits::default());
+            preview.set(None);
+        })
+    };
+
+    let on_show_original = {
+        let preview = preview.clone();
+        Callback::from(move |_: MouseEvent| preview.set(None))
+    };
+
+    let on_open_saved = {
+        let saved = saved.clone();
+        let onopen = props.onopen.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(path) = (*saved).clone() {
+                onopen.emit(path);
+            }
+        })
+    };
+
+    let on_close = {
+        let onclose = props.onclose.clone();
+        Callback::from(move |_: MouseEvent| onclose.emit(()))
+    };
+
+    let shown = match &*preview {
+        Some((rendered, _, _)) => AttrValue::from(tauri::file_url(&rendered.path)),
+        None => props.url.clone(),
+    };
+    let out_of_date = preview
+        .as_ref()
+        .is_some_and(|(_, from_edits, from_export)| {
+            *from_edits != *edits || *from_export != *export
+        });
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60 w-16";
+    let input_class =
+        "input input-bordered input-xs w-20 rounded-lg bg-base-100 border-base-content/10";
+    let crop_input = |label: &'static str, value: u32, field: fn(&mut Rect, u32)| {
+        html! {
+            <label class="flex items-center gap-1 text-xs">
+                <span class="opacity-60">{ label }</span>
+                <input type="number" min="0" class={input_class} value={value.to_string()} onchange={set_crop(field)} />
+            </label>
+        }
+    };
+
+    html! {
+        <div class="space-y-3">
+            <ImageViewer
+                src={shown}
+                alt={props.alt.clone()}
+                ondragstart={props.ondragstart.clone()}
+                onload={on_image_load}
+            />
+
+            <div class="rounded-2xl bg-base-200 ring-1 ring-base-content/5 p-3 space-y-3 text-left">
+                <div class="flex flex-wrap items-center gap-2">
+                    <span class={label_class}>{ "Crop" }</span>
+                    { crop_input("X", crop.x, |r, v| r.x = v) }
+                    { crop_input("Y", crop.y, |r, v| r.y = v) }
+                    { crop_input("W", crop.width, |r, v| r.width = v) }
+                    { crop_input("H", crop.height, |r, v| r.height = v) }
+                </div>
+
+                <div class="flex flex-wrap items-center gap-2">
+                    <span class={label_class}>{ "Turn" }</span>
+                    <div class="join">
+                        <button onclick={turn(false)} class="btn btn-xs join-item" title="Rotate left">{ "⟲ 90°" }</button>
+                        <button onclick={turn(true)} class="btn btn-xs join-item" title="Rotate right">{ "⟳ 90°" }</button>
+                        <button onclick={flip(true)} class={classes!("btn", "btn-xs", "join-item", edits.flip_x.then_some("btn-active"))}>{ "Flip H" }</button>
+                        <button onclick={flip(false)} class={classes!("btn", "btn-xs", "join-item", edits.flip_y.then_some("btn-active"))}>{ "Flip V" }</button>
+                    </div>
+                </div>
+
+                <div class="flex flex-wrap items-center gap-2">
+                    <span class={label_class}>{ "Size" }</span>
+                    <input type="number" min="1" class={input_class} value={output.width.to_string()} onchange={set_size(true)} />
+                    <span class="opacity-60">{ "×" }</span>
+                    <input type="number" min="1" class={input_class} value={output.height.to_string()} onchange={set_size(false)} />
+                    <label class="flex items-center gap-1 text-xs cursor-pointer">
+                        <input type="checkbox" class="checkbox checkbox-xs" checked={*keep_aspect} onchange={on_keep_aspect} />
+                        { "Keep aspect ratio" }
+                    </label>
+                    if output != turned {
+                        <span class="text-xs opacity-60">{ format!("from {} × {}", turned.width, turned.height) }</span>
+                    }
+                </div>
+
+                <div class="flex flex-wrap items-center gap-2">
+                    <span class={label_class}>{ "Format" }</span>
+                    <select class="select select-bordered select-xs rounded-lg" onchange={on_format}>
+                        <option value="png" selected={*export == Export::Png}>{ "PNG" }</option>
+                        <option value="jpeg" selected={matches!(*export, Export::Jpeg { .. })}>{ "JPEG" }</option>
+                        <option value="webp" selected={*export == Export::Webp}>{ "WebP (lossless)" }</option>
+                    </select>
+                    if let Export::Jpeg { quality } = *export {
+                        <input type="range" min="1" max="100" value={quality.to_string()} oninput={on_quality} class="range range-xs w-32" />
+                        <span class="text-xs opacity-60 w-20">{ format!("Quality {}", quality) }</span>
+                    }
+                </div>
+
+                if let Some(message) = (*error).as_ref() {
+                    <div class="alert alert-error rounded-xl py-2 text-sm">{ message }</div>
+                }
+                if let Some(path) = (*saved).as_ref() {
+                    <div class="alert alert-success rounded-xl py-2 text-sm">
+                        <span class="truncate">{ format!("Saved to {}", path) }</span>
+                        <button onclick={on_open_saved} class="btn btn-xs">{ "Open" }</button>
+                    </div>
+                }
+
+                <div class="flex flex-wrap items-center gap-2">
+                    <button onclick={on_preview} class="btn btn-sm rounded-xl" disabled={*busy}>{ "Preview" }</button>
+                    <button onclick={on_save} class="btn btn-sm btn-primary rounded-xl" disabled={*busy}>{ "Save as..." }</button>
+                    <button onclick={on_reset} class="btn btn-sm btn-ghost rounded-xl" disabled={*busy}>{ "Reset" }</button>
+                    if *busy {
+                        <span class="loading loading-spinner loading-xs opacity-60"></span>
+                    }
+                    <span class="text-xs opacity-60 ml-auto">
+                        {
+                            match &*preview {
+                                Some((rendered, _, _)) => format!(
+                                    "Preview {} × {} · {}{}",
+                                    rendered.width,
+                                    rendered.height,
+                                    format_size(rendered.size),
+                                    if out_of_date { " · out of date" } else { "" }
+                                ),
+                                None => format!("Original {} × {}", size.width, size.height),
+                            }
+                        }
+                    </span>
+                    if preview.is_some() {
+                        <button onclick={on_show_original} class="btn btn-xs btn-ghost">{ "Show original" }</button>
+                    }
+                    <button onclick={on_close} class="btn btn-xs btn-ghost">{ "Done" }</button>
+                </div>
+            </div>
+        </div>
+    }
+}
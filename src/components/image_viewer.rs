@@ -0,0 +1,378 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlImageElement, ResizeObserver,
+};
+use yew::prelude::*;
+
+use crate::viewer::{Fit, ImageView, Size};
+
+/// Images larger than this many pixels aren't copied to a canvas for the colour readout.
+const MAX_READOUT_PIXELS: f64 = 64_000_000.0;
+/// How far one wheel notch zooms.
+const WHEEL_ZOOM: f64 = 1.0015;
+
+#[derive(Properties, PartialEq)]
+pub struct ImageViewerProps {
+    pub src: AttrValue,
+    #[prop_or_default]
+    pub alt: AttrValue,
+    /// Shows a handle that can be dragged elsewhere, such as into a chat.
+    #[prop_or_default]
+    pub ondragstart: Option<Callback<DragEvent>>,
+    /// Told the image's size in pixels once it loads.
+    #[prop_or_default]
+    pub onload: Callback<Size>,
+}
+
+/// What's under the cursor: an image pixel and, if the image could be read, its colour.
+#[derive(Clone, Copy, PartialEq)]
+struct Readout {
+    x: u32,
+    y: u32,
+    rgba: Option<[u8; 4]>,
+}
+
+fn size_of(element: &Element) -> Size {
+    let rect = element.get_bounding_client_rect();
+    Size {
+        width: rect.width(),
+        height: rect.height(),
+    }
+}
+
+/// `(x, y)` relative to the centre of `element`.
+fn from_centre(element: &Element, client_x: i32, client_y: i32) -> (f64, f64) {
+    let rect = element.get_bounding_client_rect();
+    (
+        client_x as f64 - rect.left() - rect.width() / 2.0,
+        client_y as f64 - rect.top() - rect.height() / 2.0,
+    )
+}
+
+/// A copy of `image` that its pixels can be read from. Fails for images from another
+/// origin that don't allow it.
+fn readable_copy(image: &HtmlImageElement) -> Option<CanvasRenderingContext2d> {
+    let (width, height) = (image.natural_width(), image.natural_height());
+    if width as f64 * height as f64 > MAX_READOUT_PIXELS {
+        return None;
+    }
+    let canvas: HtmlCanvasElement = web_sys::window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into()
+        .ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"willReadFrequently".into(), &true.into()).ok()?;
+    let context: CanvasRenderingContext2d = canvas
+        .get_context_with_context_options("2d", &options)
+        .ok()??
+        .dyn_into()
+        .ok()?;
+    context
+        .draw_image_with_html_image_element(image, 0.0, 0.0)
+        .ok()?;
+    // Reading a pixel is what fails for a tainted canvas, so it's tried once here.
+    context.get_image_data(0.0, 0.0, 1.0, 1.0).ok()?;
+    Some(context)
+}
+
+fn colour_at(context: &CanvasRenderingContext2d, x: u32, y: u32) -> Option<[u8; 4]> {
+    let data = context
+        .get_image_data(x as f64, y as f64, 1.0, 1.0)
+        .ok()?
+        .data();
+    data.get(..4)?.try_into().ok()
+}
+
+/// An image that can be zoomed with the wheel, panned by dragging, turned and
+/// flipped, with the pixel under the cursor and its colour shown below.
+#[function_component(ImageViewer)]
+pub fn image_viewer(props: &ImageViewerProps) -> Html {
+    let view = use_state(ImageView::default);
+    let natural = use_state_eq(Size::default);
+    let viewport = use_state_eq(Size::default);
+    let readout = use_state_eq(|| None::<Readout>);
+    let panning = use_state_eq(|| false);
+    // Where a pan started and the view at that moment, so each move applies the
+    // whole distance dragged rather than adding up steps.
+    let pan_start = use_mut_ref(|| None::<(i32, i32, ImageView)>);
+    let pixels = use_mut_ref(|| None::<CanvasRenderingContext2d>);
+    let viewport_ref = use_node_ref();
+
+    {
+        // A new image starts over, fitted once it has loaded.
+        let view = view.clone();
+        let readout = readout.clone();
+        let pixels = pixels.clone();
+        use_effect_with(props.src.clone(), move |_| {
+            view.set(ImageView::default());
+            readout.set(None);
+            *pixels.borrow_mut() = None;
+        });
+    }
+
+    {
+        // The viewport changes with the window and with the workspace divider.
+        let viewport = viewport.clone();
+        let viewport_ref = viewport_ref.clone();
+        use_effect_with((), move |_| {
+            let observer = viewport_ref.cast::<Element>().and_then(|element| {
+                let measured = element.clone();
+                let callback =
+                    Closure::<dyn FnMut()>::new(move || viewport.set(size_of(&measured)));
+                let observer = ResizeObserver::new(callback.as_ref().unchecked_ref()).ok()?;
+                observer.observe(&element);
+                Some((observer, callback))
+            });
+            move || {
+                if let Some((observer, _callback)) = observer {
+                    observer.disconnect();
+                }
+            }
+        });
+    }
+
+    {
+        let view = view.clone();
+        use_effect_with((*natural, *viewport), move |(natural, viewport)| {
+            view.set(view.refitted(*natural, *viewport));
+        });
+    }
+
+    let on_load = {
+        let view = view.clone();
+        let natural = natural.clone();
+        let viewport = viewport.clone();
+        let pixels = pixels.clone();
+        let onload = props.onload.clone();
+        Callback::from(move |e: Event| {
+            let image: HtmlImageElement = e.target_unchecked_into();
+            let size = Size {
+                width: image.natural_width() as f64,
+                height: image.natural_height() as f64,
+            };
+            natural.set(size);
+            view.set(ImageView::default().fitted(Fit::Screen, size, *viewport));
+            *pixels.borrow_mut() = readable_copy(&image);
+            onload.emit(size);
+        })
+    };
+
+    let on_wheel = {
+        let view = view.clone();
+        let viewport_ref = viewport_ref.clone();
+        Callback::from(move |e: WheelEvent| {
+            let Some(element) = viewport_ref.cast::<Element>() else {
+                return;
+            };
+            e.prevent_default();
+            // Line-based scrolling, as some mice report, is turned into pixels.
+            let delta = match e.delta_mode() {
+                WheelEvent::DOM_DELTA_LINE => e.delta_y() * 16.0,
+                WheelEvent::DOM_DELTA_PAGE => e.delta_y() * 400.0,
+                _ => e.delta_y(),
+            };
+            let (x, y) = from_centre(&element, e.client_x(), e.client_y());
+            view.set(view.zoomed_at(WHEEL_ZOOM.powf(-delta), x, y));
+        })
+    };
+
+    let on_pointer_down = {
+        let view = view.clone();
+        let panning = panning.clone();
+        let pan_start = pan_start.clone();
+        Callback::from(move |e: PointerEvent| {
+            if e.button() != 0 {
+                return;
+            }
+            let target: Element = e.target_unchecked_into();
+            if target.set_pointer_capture(e.pointer_id()).is_ok() {
+                e.prevent_default();
+                *pan_start.borrow_mut() = Some((e.client_x(), e.client_y(), *view));
+                panning.set(true);
+            }
+        })
+    };
+
+    let on_pointer_move = {
+        let view = view.clone();
+        let natural = natural.clone();
+        let readout = readout.clone();
+        let pan_start = pan_start.clone();
+        let pixels = pixels.clone();
+        let viewport_ref = viewport_ref.clone();
+        Callback::from(move |e: PointerEvent| {
+            let Some(element) = viewport_ref.cast::<Element>() else {
+                return;
+            };
+            let mut current = *view;
+            if let Some((start_x, start_y, start)) = *pan_start.borrow() {
+                current = start.panned(
+                    (e.client_x() - start_x) as f64,
+                    (e.client_y() - start_y) as f64,
+                );
+                view.set(current);
+            }
+            let (x, y) = from_centre(&element, e.client_x(), e.client_y());
+            readout.set(current.pixel_at(x, y, *natural).map(|(x, y)| {
+                Readout {
+                    x,
+                    y,
+                    rgba: pixels
+                        .borrow()
+                        .as_ref()
+                        .and_then(|context| colour_at(context, x, y)),
+                }
+            }));
+        })
+    };
+
+    let on_pointer_up = {
+        let panning = panning.clone();
+        Callback::from(move |_: PointerEvent| {
+            *pan_start.borrow_mut() = None;
+            panning.set(false);
+        })
+    };
+
+    let on_pointer_leave = {
+        let readout = readout.clone();
+        Callback::from(move |_: PointerEvent| readout.set(None))
+    };
+
+    let fit = |fit: Fit| {
+        let view = view.clone();
+        let natural = natural.clone();
+        let viewport = viewport.clone();
+        Callback::from(move |_: MouseEvent| view.set(view.fitted(fit, *natural, *viewport)))
+    };
+
+    let on_double_click = fit(Fit::Screen);
+
+    let turn = |clockwise: bool| {
+        let view = view.clone();
+        let natural = natural.clone();
+        let viewport = viewport.clone();
+        Callback::from(move |_: MouseEvent| {
+            view.set(view.rotated(clockwise).refitted(*natural, *viewport))
+        })
+    };
+
+    let flip = |horizontally: bool| {
+        let view = view.clone();
+        Callback::from(move |_: MouseEvent| view.set(view.flipped(horizontally)))
+    };
+
+    let zoom_by = |factor: f64| {
+        let view = view.clone();
+        Callback::from(move |_: MouseEvent| view.set(view.zoomed_at(factor, 0.0, 0.0)))
+    };
+
+    let image_style = format!(
+        "width: {}px; height: {}px; max-width: none; image-orientation: from-image; transform: {};{}",
+        natural.width,
+        natural.height,
+        view.transform(),
+        // Inspecting single pixels wants them sharp rather than smoothed.
+        if view.zoom >= 4.0 { " image-rendering: pixelated;" } else { "" }
+    );
+    let fit_button = |label: &'static str, mode: Fit, title: &'static str| {
+        html! {
+            <button
+                onclick={fit(mode)}
+                class={classes!("btn", "btn-xs", "join-item", (view.fit == Some(mode)).then_some("btn-active"))}
+                title={title}
+            >
+                { label }
+            </button>
+        }
+    };
+    let hex = |rgba: [u8; 4]| format!("#{:02x}{:02x}{:02x}", rgba[0], rgba[1], rgba[2]);
+
+    html! {
+        <div class="rounded-2xl overflow-hidden bg-base-200 ring-1 ring-base-content/5">
+            <div class="flex flex-wrap items-center gap-2 p-2 border-b border-base-content/5">
+                <div class="join">
+                    { fit_button("Fit", Fit::Screen, "Fit to screen") }
+                    { fit_button("Width", Fit::Width, "Fit to width") }
+                    { fit_button("1:1", Fit::Actual, "Actual size") }
+                </div>
+                <div class="join">
+                    <button onclick={zoom_by(1.0 / 1.25)} class="btn btn-xs join-item" title="Zoom out">{ "−" }</button>
+                    <span class="btn btn-xs join-item no-animation pointer-events-none w-14">{ format!("{:.0}%", view.zoom * 100.0) }</span>
+                    <button onclick={zoom_by(1.25)} class="btn btn-xs join-item" title="Zoom in">{ "+" }</button>
+                </div>
+                <div class="join">
+                    <button onclick={turn(false)} class="btn btn-xs join-item" title="Rotate left">{ "⟲" }</button>
+                    <button onclick={turn(true)} class="btn btn-xs join-item" title="Rotate right">{ "⟳" }</button>
+                    <button
+                        onclick={flip(true)}
+                        class={classes!("btn", "btn-xs", "join-item", view.flip_x.then_some("btn-active"))}
+                        title="Flip horizontally"
+                    >
+                        { "⇋" }
+                    </button>
+                    <button
+                        onclick={flip(false)}
+                        class={classes!("btn", "btn-xs", "join-item", view.flip_y.then_some("btn-active"))}
+                        title="Flip vertically"
+                    >
+                        { "⇵" }
+                    </button>
+                </div>
+                if let Some(ondragstart) = props.ondragstart.clone() {
+                    <span
+                        draggable="true"
+                        ondragstart={ondragstart}
+                        class="btn btn-xs btn-ghost ml-auto cursor-grab"
+                        title="Drag into a chat to attach"
+                    >
+                        { "Drag to chat" }
+                    </span>
+                }
+            </div>
+            <div
+                ref={viewport_ref}
+                onwheel={on_wheel}
+                onpointerdown={on_pointer_down}
+                onpointermove={on_pointer_move}
+                onpointerup={on_pointer_up.clone()}
+                onpointercancel={on_pointer_up}
+                onpointerleave={on_pointer_leave}
+                ondblclick={on_double_click}
+                class={classes!(
+                    "relative", "overflow-hidden", "h-[70vh]", "touch-none", "select-none",
+                    if *panning { "cursor-grabbing" } else { "cursor-crosshair" }
+                )}
+            >
+                <img
+                    src={props.src.clone()}
+                    alt={props.alt.clone()}
+                    crossorigin="anonymous"
+                    draggable="false"
+                    onload={on_load}
+                    style={image_style}
+                    class="absolute left-1/2 top-1/2 origin-center"
+                />
+            </div>
+            <div class="flex items-center gap-3 px-3 py-1.5 text-xs font-mono border-t border-base-content/5 min-h-8">
+                <span class="opacity-60">{ format!("{} × {}", natural.width, natural.height) }</span>
+                if let Some(readout) = *readout {
+                    <span>{ format!("x {}, y {}", readout.x, readout.y) }</span>
+                    if let Some(rgba) = readout.rgba {
+                        <span
+                            class="inline-block w-3 h-3 rounded-sm ring-1 ring-base-content/20"
+                            style={format!("background: rgba({}, {}, {}, {})", rgba[0], rgba[1], rgba[2], rgba[3] as f64 / 255.0)}
+                        />
+                        <span>{ hex(rgba) }</span>
+                        <span class="opacity-60">{ format!("rgba({}, {}, {}, {})", rgba[0], rgba[1], rgba[2], rgba[3]) }</span>
+                    }
+                }
+            </div>
+        </div>
+    }
+}
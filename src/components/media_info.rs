@@ -0,0 +1,182 @@
+use yew::prelude::*;
+
+use crate::media::format_position;
+use crate::metadata::{self, format_frame_rate, orientation_label, Metadata};
+
+#[derive(Properties, PartialEq)]
+pub struct MediaInfoProps {
+    pub path: AttrValue,
+    /// Opens a saved copy in the player.
+    pub onopen: Callback<String>,
+}
+
+fn row(label: &'static str, value: Option<String>) -> Html {
+    match value {
+        Some(value) => html! {
+            <>
+                <dt class="opacity-60">{ label }</dt>
+                <dd class="font-mono break-all">{ value }</dd>
+            </>
+        },
+        None => html! {},
+    }
+}
+
+fn dimensions(width: Option<u32>, height: Option<u32>) -> Option<String> {
+    Some(format!("{} × {}", width?, height?))
+}
+
+/// A collapsible list of what the backend read from a file: EXIF for photos,
+/// container details for videos.
+#[function_component(MediaInfo)]
+pub fn media_info(props: &MediaInfoProps) -> Html {
+    let info = use_state(|| None::<Metadata>);
+    let error = use_state(|| None::<String>);
+    let saved = use_state(|| None::<String>);
+    let busy = use_state(|| false);
+
+    {
+        let info = info.clone();
+        let error = error.clone();
+        let saved = saved.clone();
+        use_effect_with(props.path.clone(), move |path| {
+            let path = path.clone();
+            info.set(None);
+            error.set(None);
+            saved.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match metadata::metadata(&path).await {
+                    Ok(metadata) => info.set(Some(metadata)),
+                    Err(e) => error.set(Some(format!("Couldn't read the file's details: {}", e))),
+                }
+            });
+        });
+    }
+
+    let on_strip = {
+        let path = props.path.clone();
+        let error = error.clone();
+        let saved = saved.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            let path = path.clone();
+            let error = error.clone();
+            let saved = saved.clone();
+            let busy = busy.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                match metadata::strip_metadata(&path).await {
+                    Ok(Some(target)) => {
+                        error.set(None);
+                        saved.set(Some(target));
+                    }
+                    Ok(None) => {}
+                    Err(e) => error.set(Some(format!("Couldn't save the copy: {}", e))),
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let on_open_saved = {
+        let saved = saved.clone();
+        let onopen = props.onopen.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(path) = (*saved).clone() {
+                onopen.emit(path);
+            }
+        })
+    };
+
+    let details = match &*info {
+        Some(Metadata::Image {
+            format,
+            width,
+            height,
+            camera,
+            strippable,
+        }) => {
+            let camera = camera.as_ref();
+            html! {
+                <>
+                    <dl class="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 text-sm">
+                        { row("Format", format.clone()) }
+                        { row("Size", dimensions(*width, *height)) }
+                        { row("Camera", camera.and_then(|c| c.name())) }
+                        { row("Lens", camera.and_then(|c| c.lens.clone())) }
+                        { row("Exposure", camera.and_then(|c| c.exposure())) }
+                        { row("Taken", camera.and_then(|c| c.taken.clone())) }
+                        { row("Location", camera.and_then(|c| c.gps.as_ref()).map(|gps| gps.coordinates())) }
+                        {
+                            row("Altitude", camera
+                                .and_then(|c| c.gps.as_ref())
+                                .and_then(|gps| gps.altitude)
+                                .map(|metres| format!("{:.0} m", metres)))
+                        }
+                        {
+                            row("Orientation", camera
+                                .and_then(|c| orientation_label(c.orientation))
+                                .map(|label| format!("{} (shown upright)", label)))
+                        }
+                    </dl>
+                    if camera.is_none() {
+                        <p class="text-xs opacity-60 mt-2">{ "No EXIF data." }</p>
+                    }
+                    if *strippable {
+                        <button onclick={on_strip} class="btn btn-sm rounded-xl mt-3" disabled={*busy}>
+                            { "Strip metadata and save a copy..." }
+                        </button>
+                    }
+                </>
+            }
+        }
+        Some(Metadata::Video {
+            container,
+            duration,
+            width,
+            height,
+            frame_rate,
+            tracks,
+        }) => html! {
+            <dl class="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 text-sm">
+                { row("Container", container.clone()) }
+                { row("Duration", duration.map(format_position)) }
+                { row("Resolution", dimensions(*width, *height)) }
+                { row("Frame rate", frame_rate.map(format_frame_rate)) }
+                {
+                    for tracks.iter().map(|track| html! {
+                        <>
+                            <dt class="opacity-60">{ track.kind.label() }</dt>
+                            <dd class="font-mono break-all">{ &track.codec }</dd>
+                        </>
+                    })
+                }
+            </dl>
+        },
+        None => html! {
+            if error.is_none() {
+                <span class="loading loading-spinner loading-xs opacity-60"></span>
+            }
+        },
+    };
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-content/5 rounded-2xl text-left">
+            <summary class="collapse-title min-h-0 py-3 text-xs font-bold uppercase tracking-widest opacity-60">
+                { "Info" }
+            </summary>
+            <div class="collapse-content space-y-2">
+                { details }
+                if let Some(message) = (*error).as_ref() {
+                    <div class="alert alert-error rounded-xl py-2 text-sm">{ message }</div>
+                }
+                if let Some(path) = (*saved).as_ref() {
+                    <div class="alert alert-success rounded-xl py-2 text-sm">
+                        <span class="truncate">{ format!("Saved to {}", path) }</span>
+                        <button onclick={on_open_saved} class="btn btn-xs">{ "Open" }</button>
+                    </div>
+                }
+            </div>
+        </details>
+    }
+}
@@ -0,0 +1,255 @@
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+use yew::prelude::*;
+
+use crate::attachments;
+use crate::ollama::Message;
+use crate::virtual_list::{estimate_height, Heights};
+
+/// Extra pixels rendered above and below the viewport so fast scrolling doesn't show
+/// blank space.
+const OVERSCAN: f64 = 1200.0;
+/// How close to the bottom counts as "at the bottom", where new content stays in view.
+const STICK_THRESHOLD: f64 = 48.0;
+
+fn thinking_section(msg: &Message, streaming: bool) -> Html {
+    let Some(thinking) = msg.thinking.as_ref().filter(|t| !t.trim().is_empty()) else {
+        return html! {};
+    };
+    let label = match msg.thinking_ms {
+        Some(ms) => format!("Thought for {:.1}s", ms / 1000.0),
+        None if streaming => "Thinking...".to_string(),
+        None => "Thoughts".to_string(),
+    };
+
+    html! {
+        <details class="collapse collapse-arrow bg-base-content/5 rounded-xl mb-3 text-sm" open={streaming && msg.thinking_ms.is_none()}>
+            <summary class="collapse-title min-h-0 py-2 px-4 text-xs font-bold uppercase tracking-widest opacity-60">
+                if streaming && msg.thinking_ms.is_none() {
+                    <span class="loading loading-spinner loading-xs mr-2 align-middle"></span>
+                }
+                { label }
+            </summary>
+            <div class="collapse-content px-4 opacity-70 italic whitespace-pre-wrap">
+                { thinking.trim() }
+            </div>
+        </details>
+    }
+}
+
+#[derive(Properties)]
+pub struct MessageBubbleProps {
+    pub message: Rc<Message>,
+    #[prop_or_default]
+    pub streaming: bool,
+}
+
+impl PartialEq for MessageBubbleProps {
+    fn eq(&self, other: &Self) -> bool {
+        // Finished messages are shared out of the history and never change in place,
+        // so the same `Rc` means the same message and its text needn't be compared.
+        Rc::ptr_eq(&self.message, &other.message) && self.streaming == other.streaming
+    }
+}
+
+/// One chat message. A component rather than a function so finished messages are
+/// skipped when only the streaming reply changes.
+#[function_component(MessageBubble)]
+pub fn message_bubble(props: &MessageBubbleProps) -> Html {
+    let msg = &props.message;
+    let streaming = props.streaming;
+    let is_user = msg.role == "user";
+    let bubble_class = if is_user {
+        "bg-primary text-primary-content rounded-tr-none ml-auto"
+    } else if msg.role == "system" {
+        "bg-error/10 text-error border border-error/20 rounded-xl mx-auto text-center w-full"
+    } else {
+        "bg-base-100 border border-base-content/10 rounded-tl-none mr-auto"
+    };
+
+    let align_class = if is_user {
+        "justify-end"
+    } else {
+        "justify-start"
+    };
+
+    html! {
+        <div class={classes!("flex", align_class, "gap-4")}>
+            if !is_user {
+                <div class="w-8 h-8 rounded-full bg-base-content/5 flex items-center justify-center flex-shrink-0">
+                     <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5 opacity-50" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13 10V3L4 14h7v7l9-11h-7z" />
+                    </svg>
+                </div>
+            }
+
+            <div class={classes!("max-w-[80%]", "p-4", "rounded-2xl", "whitespace-pre-wrap", "leading-relaxed", bubble_class)}>
+                { thinking_section(msg, streaming) }
+                if !msg.images.is_empty() {
+                    <div class="flex flex-wrap gap-2 mb-2">
+                        { for msg.images.iter().map(|image| html! {
+                            <img src={attachments::data_url(image)} alt="Attachment" class="max-h-40 rounded-xl" />
+                        }) }
+                    </div>
+                }
+                { &msg.content }
+            </div>
+
+             if is_user {
+                <div class="w-8 h-8 rounded-full bg-primary flex items-center justify-center flex-shrink-0">
+                     <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5 text-primary-content" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z" />
+                    </svg>
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties)]
+pub struct MessageListProps {
+    /// The conversation's own list, shared rather than copied.
+    pub messages: Rc<Vec<Rc<Message>>>,
+    /// The reply being streamed, always rendered below the finished messages.
+    pub pending: Option<Message>,
+    /// Shows a typing indicator while waiting for the first token.
+    pub waiting: bool,
+}
+
+impl PartialEq for MessageListProps {
+    fn eq(&self, other: &Self) -> bool {
+        // While a reply streams the history is the same `Rc`, so only the pending
+        // message is compared; a new list is only made when a message is added.
+        Rc::ptr_eq(&self.messages, &other.messages)
+            && self.pending == other.pending
+            && self.waiting == other.waiting
+    }
+}
+
+/// The scrolling message area. Only messages near the viewport are rendered; spacers
+/// stand in for the rest, sized from measured heights where known and estimates
+/// elsewhere.
+#[function_component(MessageList)]
+pub fn message_list(props: &MessageListProps) -> Html {
+    let container_ref = use_node_ref();
+    let items_ref = use_node_ref();
+    let heights = use_mut_ref(Heights::default);
+    // Follow new content while the user is at the bottom; starts there.
+    let stick = use_mut_ref(|| true);
+    let scroll = use_state(|| (0.0, 0.0));
+    let remeasure = use_state(|| 0u32);
+
+    heights.borrow_mut().sync(props.messages.len(), |index| {
+        estimate_height(&props.messages[index].content)
+    });
+    let (scroll_top, viewport) = *scroll;
+    let window = heights.borrow().window(scroll_top, viewport, OVERSCAN);
+    let anchor = heights.borrow().index_at(scroll_top);
+
+    let onscroll = {
+        let container_ref = container_ref.clone();
+        let stick = stick.clone();
+        let scroll = scroll.clone();
+        Callback::from(move |_: Event| {
+            if let Some(el) = container_ref.cast::<Element>() {
+                let top = el.scroll_top() as f64;
+                let viewport = el.client_height() as f64;
+                *stick.borrow_mut() = el.scroll_height() as f64 - top - viewport < STICK_THRESHOLD;
+                scroll.set((top, viewport));
+            }
+        })
+    };
+
+    {
+        // After each render, measure what was drawn. Growth above the first visible
+        // message is scrolled away so the content the user is reading stays put.
+        let container_ref = container_ref.clone();
+        let items_ref = items_ref.clone();
+        let heights = heights.clone();
+        let scroll = scroll.clone();
+        let remeasure = remeasure.clone();
+        let start = window.start;
+        use_effect(move || {
+            let (Some(container), Some(items)) =
+                (container_ref.cast::<Element>(), items_ref.cast::<Element>())
+            else {
+                return;
+            };
+            let mut shift = 0.0;
+            let mut changed = false;
+            let children = items.children();
+            for offset in 0..children.length() {
+                let Some(item) = children
+                    .item(offset)
+                    .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+                else {
+                    continue;
+                };
+                let index = start + offset as usize;
+                let delta = heights
+                    .borrow_mut()
+                    .measure(index, item.offset_height() as f64);
+                if delta != 0.0 {
+                    changed = true;
+                    if index < anchor {
+                        shift += delta;
+                    }
+                }
+            }
+
+            if *stick.borrow() {
+                container.set_scroll_top(container.scroll_height());
+            } else if shift != 0.0 {
+                container.set_scroll_top(container.scroll_top() + shift.round() as i32);
+            }
+            let current = (
+                container.scroll_top() as f64,
+                container.client_height() as f64,
+            );
+            if current != *scroll {
+                scroll.set(current);
+            } else if changed {
+                remeasure.set(*remeasure + 1);
+            }
+        });
+    }
+
+    html! {
+        <div
+            ref={container_ref}
+            {onscroll}
+            class="flex-1 overflow-y-auto min-h-0 mb-6 pr-2 scrollbar-thin scrollbar-thumb-base-content/10"
+        >
+            <div style={format!("height: {}px", window.top)}></div>
+            <div ref={items_ref}>
+                { for props.messages[window.start..window.end].iter().enumerate().map(|(offset, msg)| html! {
+                    <div key={window.start + offset} class="pb-4">
+                        <MessageBubble message={msg.clone()} />
+                    </div>
+                }) }
+            </div>
+            <div style={format!("height: {}px", window.bottom)}></div>
+
+            if let Some(pending) = &props.pending {
+                <div class="pb-4">
+                    <MessageBubble message={Rc::new(pending.clone())} streaming=true />
+                </div>
+            }
+
+            if props.waiting {
+                <div class="flex justify-start gap-4 pb-4">
+                    <div class="w-8 h-8 rounded-full bg-base-content/5 flex items-center justify-center flex-shrink-0">
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5 opacity-50" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13 10V3L4 14h7v7l9-11h-7z" />
+                        </svg>
+                    </div>
+                     <div class="bg-base-100 border border-base-content/10 p-4 rounded-2xl rounded-tl-none flex items-center gap-2">
+                         <span class="loading loading-dots loading-sm opacity-50"></span>
+                     </div>
+                </div>
+            }
+        </div>
+    }
+}
@@ -1,5 +1,19 @@
+pub mod frame_capture;
+pub mod image_editor;
+pub mod image_viewer;
+pub mod media_info;
+pub mod message_list;
 pub mod pages;
 pub mod sidebar;
+pub mod subtitle_controls;
 
-pub use pages::{ChatPage, HomePage, MediaPlayer};
+pub use frame_capture::FrameCapture;
+pub use image_editor::ImageEditor;
+pub use image_viewer::ImageViewer;
+pub use media_info::MediaInfo;
+pub use pages::{
+    BatchPage, ChatPage, EmbeddingsPage, EvalPage, HomePage, LibraryPage, MediaPlayer, MemoryPage,
+    ModelfilePage, PlaygroundPage, WorkspacePage,
+};
 pub use sidebar::Sidebar;
+pub use subtitle_controls::SubtitleControls;
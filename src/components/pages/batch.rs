@@ -0,0 +1,401 @@
+use std::future::Future;
+
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::batch::{self, BatchConfig, BatchPreview, BatchProgress};
+use crate::store::ChatStore;
+use crate::tauri;
+
+const DEFAULT_TEMPLATE: &str = "Classify this support ticket as one of: billing, bug, feature request, other.\nReply with the category only.\n\nSubject: {{subject}}\n{{body}}";
+
+fn input_setter(state: &UseStateHandle<String>) -> Callback<InputEvent> {
+    let state = state.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+fn textarea_setter(state: &UseStateHandle<String>) -> Callback<InputEvent> {
+    let state = state.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlTextAreaElement = e.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+/// Runs a backend call, reporting failure in `error`.
+fn spawn_command(
+    error: &UseStateHandle<Option<String>>,
+    call: impl Future<Output = Result<(), String>> + 'static,
+) {
+    let error = error.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        error.set(call.await.err());
+    });
+}
+
+fn cell_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[function_component(BatchPage)]
+pub fn batch_page() -> Html {
+    let store = use_context::<ChatStore>().expect("BatchPage must be rendered inside ChatProvider");
+
+    let input_path = use_state(String::new);
+    let output_path = use_state(String::new);
+    let template = use_state(|| DEFAULT_TEMPLATE.to_string());
+    let system = use_state(String::new);
+    let model = use_state(String::new);
+    let concurrency = use_state(|| 4usize);
+
+    let preview = use_state(|| None::<BatchPreview>);
+    let progress = use_state(|| None::<BatchProgress>);
+    let interrupted = use_state(|| None::<BatchConfig>);
+    let error = use_state(|| None::<String>);
+
+    let selected_model = if model.is_empty() {
+        store.models.first().cloned().unwrap_or_default()
+    } else {
+        (*model).clone()
+    };
+
+    {
+        let progress = progress.clone();
+        let interrupted = interrupted.clone();
+        use_effect_with((), move |_| {
+            // A job keeps running in the backend while this page is closed.
+            {
+                let progress = progress.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(Some(status)) = batch::status().await {
+                        progress.set(Some(status));
+                    } else if let Ok(Some(config)) = batch::interrupted().await {
+                        interrupted.set(Some(config));
+                    }
+                });
+            }
+            let listener = tauri::listen("batch-progress", move |status: BatchProgress| {
+                progress.set(Some(status));
+            });
+            move || drop(listener)
+        });
+    }
+
+    let config = BatchConfig {
+        input_path: (*input_path).clone(),
+        output_path: if output_path.is_empty() {
+            batch::default_output_path(&input_path)
+        } else {
+            (*output_path).clone()
+        },
+        api_url: store.api_url.clone(),
+        model: selected_model.clone(),
+        template: (*template).clone(),
+        system: (*system).clone(),
+        concurrency: *concurrency,
+    };
+
+    let start = {
+        let progress = progress.clone();
+        let interrupted = interrupted.clone();
+        let error = error.clone();
+        Callback::from(move |config: BatchConfig| {
+            let progress = progress.clone();
+            interrupted.set(None);
+            spawn_command(&error, async move {
+                progress.set(Some(batch::start(&config).await?));
+                Ok(())
+            });
+        })
+    };
+
+    let on_start = {
+        let start = start.clone();
+        let config = config.clone();
+        Callback::from(move |_| start.emit(config.clone()))
+    };
+
+    // Resuming an interrupted job restores its settings so the form matches what runs.
+    let on_resume_interrupted = {
+        let start = start.clone();
+        let interrupted = interrupted.clone();
+        let input_path = input_path.clone();
+        let output_path = output_path.clone();
+        let template = template.clone();
+        let system = system.clone();
+        let model = model.clone();
+        let concurrency = concurrency.clone();
+        Callback::from(move |_| {
+            let Some(config) = (*interrupted).clone() else {
+                return;
+            };
+            input_path.set(config.input_path.clone());
+            output_path.set(config.output_path.clone());
+            template.set(config.template.clone());
+            system.set(config.system.clone());
+            model.set(config.model.clone());
+            concurrency.set(config.concurrency);
+            start.emit(config);
+        })
+    };
+
+    let on_dismiss_interrupted = {
+        let interrupted = interrupted.clone();
+        Callback::from(move |_| interrupted.set(None))
+    };
+
+    let on_preview = {
+        let input_path = input_path.clone();
+        let preview = preview.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            let input_path = (*input_path).clone();
+            let preview = preview.clone();
+            spawn_command(&error, async move {
+                preview.set(Some(batch::preview(&input_path).await?));
+                Ok(())
+            });
+        })
+    };
+
+    let on_pause = {
+        let error = error.clone();
+        Callback::from(move |_| spawn_command(&error, batch::pause()))
+    };
+    let on_resume = {
+        let error = error.clone();
+        Callback::from(move |_| spawn_command(&error, batch::resume()))
+    };
+    let on_cancel = {
+        let error = error.clone();
+        Callback::from(move |_| spawn_command(&error, batch::cancel()))
+    };
+
+    let on_model_change = {
+        let model = model.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            model.set(select.value());
+        })
+    };
+
+    let on_concurrency_change = {
+        let concurrency = concurrency.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<usize>() {
+                concurrency.set(value.clamp(1, 16));
+            }
+        })
+    };
+
+    let insert_column = |column: &str| {
+        let template = template.clone();
+        let placeholder = format!("{{{{{}}}}}", column);
+        Callback::from(move |_| template.set(format!("{}{}", *template, placeholder)))
+    };
+
+    let running = progress.as_ref().is_some_and(|p| p.running);
+    let paused = progress.as_ref().is_some_and(|p| p.paused);
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+    let field_class = "input input-sm w-full rounded-xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 font-mono";
+    let textarea_class = "textarea w-full rounded-2xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 font-mono text-sm";
+
+    html! {
+        <div class="w-full max-w-6xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Batch Jobs" }</h1>
+                    <p class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Run a prompt over every row of a CSV or JSONL file" }</p>
+                </div>
+                <select
+                    class="select select-sm bg-base-100 border-base-content/10 rounded-xl font-bold min-w-[160px]"
+                    disabled={store.models.is_empty() || running}
+                    onchange={on_model_change}
+                >
+                    if store.models.is_empty() {
+                        <option disabled=true selected=true>{ "No models found" }</option>
+                    } else {
+                        { for store.models.iter().map(|m| html! {
+                            <option value={m.clone()} selected={*m == selected_model}>{ m }</option>
+                        }) }
+                    }
+                </select>
+            </div>
+
+            if !tauri::is_tauri() {
+                <div class="alert alert-warning rounded-2xl">
+                    <span class="font-medium">{ "Batch jobs run in the desktop app, which reads and writes the files." }</span>
+                </div>
+            }
+
+            if let Some(config) = (*interrupted).as_ref() {
+                <div class="alert rounded-2xl border border-warning/30">
+                    <span class="font-medium flex-1">
+                        { format!("A batch job over {} did not finish last time.", config.input_path) }
+                    </span>
+                    <button onclick={on_resume_interrupted} class="btn btn-sm btn-warning rounded-xl">{ "Resume" }</button>
+                    <button onclick={on_dismiss_interrupted} class="btn btn-sm btn-ghost rounded-xl">{ "Dismiss" }</button>
+                </div>
+            }
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-2xl">
+                    <span class="font-medium">{ message }</span>
+                </div>
+            }
+
+            <div class="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                <div class="space-y-4">
+                    <div class="space-y-2">
+                        <span class={label_class}>{ "Input file (.csv or .jsonl)" }</span>
+                        <div class="flex gap-2">
+                            <input
+                                type="text"
+                                class={field_class}
+                                value={(*input_path).clone()}
+                                oninput={input_setter(&input_path)}
+                                placeholder="/home/me/tickets.csv"
+                                disabled={running}
+                            />
+                            <button onclick={on_preview} disabled={input_path.is_empty()} class="btn btn-sm btn-ghost border border-base-content/10 rounded-xl">
+                                { "Preview" }
+                            </button>
+                        </div>
+                    </div>
+                    <div class="space-y-2">
+                        <span class={label_class}>{ "Output file (.csv or .jsonl)" }</span>
+                        <input
+                            type="text"
+                            class={field_class}
+                            value={config.output_path.clone()}
+                            oninput={input_setter(&output_path)}
+                            disabled={running}
+                        />
+                    </div>
+                    <div class="space-y-2">
+                        <div class="flex items-center justify-between">
+                            <span class={label_class}>{ "Prompt template" }</span>
+                            if let Some(preview) = (*preview).as_ref() {
+                                <div class="flex flex-wrap gap-1 justify-end">
+                                    { for preview.columns.iter().map(|c| html! {
+                                        <button onclick={insert_column(c)} class="badge badge-outline badge-sm font-mono cursor-pointer hover:badge-primary">
+                                            { format!("{{{{{}}}}}", c) }
+                                        </button>
+                                    }) }
+                                </div>
+                            }
+                        </div>
+                        <textarea
+                            class={classes!(textarea_class, "h-40")}
+                            value={(*template).clone()}
+                            oninput={textarea_setter(&template)}
+                            disabled={running}
+                        />
+                    </div>
+                    <div class="space-y-2">
+                        <span class={label_class}>{ "System prompt (optional)" }</span>
+                        <textarea
+                            class={classes!(textarea_class, "h-20")}
+                            value={(*system).clone()}
+                            oninput={textarea_setter(&system)}
+                            disabled={running}
+                        />
+                    </div>
+                    <div class="flex items-end gap-2">
+                        <label class="space-y-2">
+                            <span class={label_class}>{ "Parallel requests" }</span>
+                            <input
+                                type="number"
+                                min="1"
+                                max="16"
+                                class={classes!(field_class, "w-24")}
+                                value={concurrency.to_string()}
+                                onchange={on_concurrency_change}
+                                disabled={running}
+                            />
+                        </label>
+                        <button
+                            onclick={on_start}
+                            disabled={running || input_path.is_empty() || selected_model.is_empty() || !tauri::is_tauri()}
+                            class="btn btn-primary btn-sm rounded-xl flex-1"
+                            title="Rows already in the output file are skipped"
+                        >
+                            { "Start" }
+                        </button>
+                    </div>
+                </div>
+
+                <div class="space-y-4">
+                    if let Some(status) = (*progress).as_ref() {
+                        <div class="bg-base-100 border border-base-content/10 rounded-2xl p-4 space-y-3">
+                            <div class="flex items-center justify-between">
+                                <span class={label_class}>
+                                    { if status.paused { "Paused" } else if status.running { "Running" } else { "Finished" } }
+                                </span>
+                                <span class="text-xs opacity-60 font-mono">{ format!("{} / {}", status.done, status.total) }</span>
+                            </div>
+                            <progress
+                                class="progress progress-primary w-full"
+                                value={status.done.to_string()}
+                                max={status.total.max(1).to_string()}
+                            />
+                            <div class="flex flex-wrap gap-x-4 text-xs opacity-60">
+                                if status.failed > 0 {
+                                    <span class="text-error">{ format!("{} failed", status.failed) }</span>
+                                }
+                                if status.resumed > 0 {
+                                    <span>{ format!("{} from an earlier run", status.resumed) }</span>
+                                }
+                                <span class="font-mono truncate">{ &status.output_path }</span>
+                            </div>
+                            if let Some(message) = &status.error {
+                                <p class="text-xs text-error">{ message }</p>
+                            }
+                            if running {
+                                <div class="flex gap-2">
+                                    if paused {
+                                        <button onclick={on_resume} class="btn btn-sm btn-primary rounded-xl flex-1">{ "Resume" }</button>
+                                    } else {
+                                        <button onclick={on_pause} class="btn btn-sm btn-ghost border border-base-content/10 rounded-xl flex-1">{ "Pause" }</button>
+                                    }
+                                    <button onclick={on_cancel} class="btn btn-sm btn-ghost rounded-xl" title="Stop; results so far are kept and the job can be resumed">
+                                        { "Stop" }
+                                    </button>
+                                </div>
+                            }
+                        </div>
+                    }
+
+                    if let Some(preview) = (*preview).as_ref() {
+                        <div class="space-y-2">
+                            <span class={label_class}>{ format!("Preview ({} rows)", preview.total) }</span>
+                            <div class="overflow-x-auto bg-base-100 border border-base-content/10 rounded-2xl">
+                                <table class="table table-xs">
+                                    <thead>
+                                        <tr>{ for preview.columns.iter().map(|c| html! { <th>{ c }</th> }) }</tr>
+                                    </thead>
+                                    <tbody>
+                                        { for preview.sample.iter().map(|row| html! {
+                                            <tr>
+                                                { for preview.columns.iter().map(|c| html! {
+                                                    <td class="max-w-[16rem] truncate">{ row.get(c).map(cell_text).unwrap_or_default() }</td>
+                                                }) }
+                                            </tr>
+                                        }) }
+                                    </tbody>
+                                </table>
+                            </div>
+                        </div>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
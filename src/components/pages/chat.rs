@@ -1,189 +1,150 @@
-use reqwasm::http::Request;
-use serde::{Deserialize, Serialize};
-
-use web_sys::HtmlInputElement;
+use wasm_bindgen::JsValue;
+use web_sys::{File, HtmlInputElement};
 use yew::prelude::*;
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
-pub struct Message {
-    pub role: String,
-    pub content: String,
-}
-
-#[derive(Serialize)]
-pub struct ChatRequest {
-    pub model: String,
-    pub messages: Vec<Message>,
-    pub stream: bool,
-}
-
-#[derive(Deserialize)]
-pub struct ChatResponse {
-    pub message: Message,
+use crate::attachments::{self, MEDIA_DRAG_TYPE};
+use crate::components::message_list::MessageList;
+use crate::download::download_bytes;
+use crate::export;
+use crate::memory;
+use crate::store::chat::{cancel_reply, send_message};
+use crate::store::{ChatAction, ChatStore};
+use crate::tauri;
+
+fn export_date() -> String {
+    js_sys::Date::new_0()
+        .to_locale_string("en-US", &js_sys::Object::new())
+        .as_string()
+        .unwrap_or_default()
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq)]
-pub struct ModelInfo {
-    pub name: String,
-}
-
-#[derive(Deserialize, Debug, Clone, PartialEq)]
-pub struct TagsResponse {
-    pub models: Vec<ModelInfo>,
+#[derive(Properties, PartialEq)]
+pub struct ChatPageProps {
+    /// Leaves out the conversation list, for when the page shares the screen.
+    #[prop_or_default]
+    pub compact: bool,
 }
 
 #[function_component(ChatPage)]
-pub fn chat_page() -> Html {
-    let messages = use_state(|| Vec::<Message>::new());
+pub fn chat_page(props: &ChatPageProps) -> Html {
+    let store = use_context::<ChatStore>().expect("ChatPage must be rendered inside ChatProvider");
     let input_value = use_state(|| "".to_string());
-    let is_loading = use_state(|| false);
-
-    let api_url = use_state(|| "http://localhost:11434".to_string());
-    let available_models = use_state(|| Vec::<String>::new());
-    let selected_model = use_state(|| "".to_string());
-    let is_connecting = use_state(|| false);
-    let connection_error = use_state(|| "".to_string());
-
-    {
-        let api_url = api_url.clone();
-        let available_models = available_models.clone();
-        let selected_model = selected_model.clone();
-        let is_connecting = is_connecting.clone();
-        let connection_error = connection_error.clone();
+    let attachments = use_state(Vec::<String>::new);
+    let drop_active = use_state_eq(|| false);
+    let exporting_pdf = use_state(|| false);
+    let error = use_state(|| None::<String>);
 
-        use_effect_with(api_url.clone(), move |url| {
-            let url = url.clone();
-
-            wasm_bindgen_futures::spawn_local(async move {
-                is_connecting.set(true);
-                connection_error.set("".to_string());
-
-                let fetch_url = format!("{}/api/tags", *url);
-                let response = Request::get(&fetch_url).send().await;
-
-                match response {
-                    Ok(resp) => {
-                        if resp.ok() {
-                            let tags_resp: Result<TagsResponse, _> = resp.json().await;
-                            match tags_resp {
-                                Ok(data) => {
-                                    let names: Vec<String> =
-                                        data.models.into_iter().map(|m| m.name).collect();
-                                    if !names.is_empty() {
-                                        if selected_model.is_empty()
-                                            || !names.contains(&*selected_model)
-                                        {
-                                            selected_model.set(names[0].clone());
-                                        }
-                                    }
-                                    available_models.set(names);
-                                }
-                                Err(e) => {
-                                    connection_error.set(format!("Failed to parse models: {}", e));
-                                    available_models.set(Vec::new());
-                                }
-                            }
-                        } else {
-                            connection_error.set(format!("API Error: {}", resp.status()));
-                            available_models.set(Vec::new());
-                        }
-                    }
-                    Err(e) => {
-                        connection_error.set(format!("Connection Failed: {}", e));
-                        available_models.set(Vec::new());
-                    }
-                }
-                is_connecting.set(false);
-            });
-        });
-    }
+    let Some(conversation) = store.active_conversation().cloned() else {
+        return html! {};
+    };
+    let is_loading = conversation.generating();
+    let available_models = &store.models;
+    // A new conversation's system prompt carries the memories, so it waits for them.
+    let waiting_for_memories = conversation.messages.is_empty() && !store.memories_loaded;
 
     let on_submit = {
-        let messages = messages.clone();
+        let store = store.clone();
         let input_value = input_value.clone();
-        let is_loading = is_loading.clone();
-        let api_url = api_url.clone();
-        let selected_model = selected_model.clone();
+        let attachments = attachments.clone();
 
         Callback::from(move |e: SubmitEvent| {
             e.prevent_default();
-            if input_value.is_empty() || selected_model.is_empty() {
+            if input_value.is_empty() && attachments.is_empty() {
                 return;
             }
+            if send_message(&store, (*input_value).clone(), (*attachments).clone()) {
+                input_value.set("".to_string());
+                attachments.set(Vec::new());
+            }
+        })
+    };
 
-            let user_msg = Message {
-                role: "user".to_string(),
-                content: (*input_value).clone(),
-            };
-
-            let mut new_messages = (*messages).clone();
-            new_messages.push(user_msg.clone());
-            messages.set(new_messages.clone());
-
-            input_value.set("".to_string());
-            is_loading.set(true);
+    // Images can be dropped on the composer from the media player or the desktop.
+    let on_drag_over = {
+        let drop_active = drop_active.clone();
+        Callback::from(move |e: DragEvent| {
+            let accepts = e.data_transfer().is_some_and(|data| {
+                let types = data.types();
+                types.includes(&JsValue::from_str(MEDIA_DRAG_TYPE), 0)
+                    || types.includes(&JsValue::from_str("Files"), 0)
+            });
+            if accepts {
+                e.prevent_default();
+                drop_active.set(true);
+            }
+        })
+    };
 
-            let messages_clone = messages.clone();
-            let is_loading_clone = is_loading.clone();
-            let url = (*api_url).clone();
-            let model = (*selected_model).clone();
+    let on_drag_leave = {
+        let drop_active = drop_active.clone();
+        Callback::from(move |_: DragEvent| drop_active.set(false))
+    };
 
+    let on_drop = {
+        let attachments = attachments.clone();
+        let drop_active = drop_active.clone();
+        let error = error.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            drop_active.set(false);
+            let Some(data) = e.data_transfer() else {
+                return;
+            };
+            let media_url = data
+                .get_data(MEDIA_DRAG_TYPE)
+                .ok()
+                .filter(|url| !url.is_empty());
+            let files: Vec<File> = data
+                .files()
+                .map(|list| (0..list.length()).filter_map(|i| list.get(i)).collect())
+                .unwrap_or_default();
+
+            let attachments = attachments.clone();
+            let error = error.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                let request_body = ChatRequest {
-                    model,
-                    messages: new_messages,
-                    stream: false,
-                };
-
-                let fetch_url = format!("{}/api/chat", url);
-                let response = Request::post(&fetch_url)
-                    .header("Content-Type", "application/json")
-                    .body(serde_json::to_string(&request_body).unwrap())
-                    .send()
-                    .await;
-
-                match response {
-                    Ok(resp) => {
-                        if resp.ok() {
-                            let chat_resp: Result<ChatResponse, _> = resp.json().await;
-                            match chat_resp {
-                                Ok(data) => {
-                                    let mut current_msgs = (*messages_clone).clone();
-                                    current_msgs.push(data.message);
-                                    messages_clone.set(current_msgs);
-                                }
-                                Err(e) => {
-                                    let mut current_msgs = (*messages_clone).clone();
-                                    current_msgs.push(Message {
-                                        role: "system".to_string(),
-                                        content: format!("Error parsing response: {}", e),
-                                    });
-                                    messages_clone.set(current_msgs);
-                                }
-                            }
-                        } else {
-                            let mut current_msgs = (*messages_clone).clone();
-                            current_msgs.push(Message {
-                                role: "system".to_string(),
-                                content: format!("API Error: {}", resp.status()),
-                            });
-                            messages_clone.set(current_msgs);
-                        }
-                    }
-                    Err(e) => {
-                        let mut current_msgs = (*messages_clone).clone();
-                        current_msgs.push(Message {
-                            role: "system".to_string(),
-                            content: format!("Network Error: {}", e),
-                        });
-                        messages_clone.set(current_msgs);
+                let mut read = Vec::new();
+                if let Some(url) = media_url {
+                    read.push(attachments::fetch_image(&url).await);
+                }
+                for file in &files {
+                    read.push(attachments::read_blob(file).await);
+                }
+
+                let mut added = (*attachments).clone();
+                for bytes in read {
+                    match bytes.map(|b| attachments::encode_image(&b)) {
+                        Ok(Some(image)) => added.push(image),
+                        Ok(None) => error.set(Some(
+                            "Only PNG, JPEG, GIF and WebP images can be attached.".to_string(),
+                        )),
+                        Err(e) => error.set(Some(e)),
                     }
                 }
-                is_loading_clone.set(false);
+                attachments.set(added);
             });
         })
     };
 
+    let remove_attachment = |index: usize| {
+        let attachments = attachments.clone();
+        Callback::from(move |_| {
+            let mut remaining = (*attachments).clone();
+            remaining.remove(index);
+            attachments.set(remaining);
+        })
+    };
+
+    let on_cancel = {
+        let store = store.clone();
+        let id = conversation.id;
+        Callback::from(move |_| cancel_reply(&store, id))
+    };
+
+    let on_think_toggle = {
+        let store = store.clone();
+        Callback::from(move |_| store.dispatch(ChatAction::ToggleThinking))
+    };
+
     let onplaininput = {
         let input_value = input_value.clone();
         Callback::from(move |e: InputEvent| {
@@ -193,165 +154,366 @@ pub fn chat_page() -> Html {
     };
 
     let on_url_change = {
-        let api_url = api_url.clone();
+        let store = store.clone();
         Callback::from(move |e: Event| {
             let input: HtmlInputElement = e.target_unchecked_into();
-            api_url.set(input.value());
+            store.dispatch(ChatAction::SetApiUrl(input.value()));
         })
     };
 
     let on_model_change = {
-        let selected_model = selected_model.clone();
+        let store = store.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            store.dispatch(ChatAction::SwitchModel(select.value()));
+        })
+    };
+
+    let on_rename = {
+        let store = store.clone();
+        let id = conversation.id;
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            store.dispatch(ChatAction::RenameConversation {
+                id,
+                title: input.value(),
+            });
+        })
+    };
+
+    let on_title_model_change = {
+        let store = store.clone();
         Callback::from(move |e: Event| {
             let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
-            selected_model.set(select.value());
+            store.dispatch(ChatAction::SetTitleModel(select.value()));
         })
     };
 
+    let on_export_html = {
+        let conversation = conversation.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            let html = export::conversation_html(&conversation, &export_date());
+            let filename = export::export_filename(&conversation, "html");
+            error.set(
+                download_bytes(&filename, "text/html", html.as_bytes())
+                    .err()
+                    .map(|e| format!("Export failed: {}", e)),
+            );
+        })
+    };
+
+    let on_export_pdf = {
+        let conversation = conversation.clone();
+        let exporting_pdf = exporting_pdf.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            if *exporting_pdf {
+                return;
+            }
+            exporting_pdf.set(true);
+            let conversation = conversation.clone();
+            let exporting_pdf = exporting_pdf.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = export::conversation_pdf(&conversation, &export_date())
+                    .await
+                    .and_then(|bytes| {
+                        download_bytes(
+                            &export::export_filename(&conversation, "pdf"),
+                            "application/pdf",
+                            &bytes,
+                        )
+                    });
+                error.set(result.err().map(|e| format!("Export failed: {}", e)));
+                exporting_pdf.set(false);
+            });
+        })
+    };
+
+    let resolve_proposal = |text: &str, save: bool| {
+        let store = store.clone();
+        let error = error.clone();
+        let id = conversation.id;
+        let text = text.to_string();
+        Callback::from(move |_| {
+            if !save {
+                store.dispatch(ChatAction::ResolveProposal {
+                    id,
+                    text: text.clone(),
+                });
+                return;
+            }
+            let store = store.clone();
+            let error = error.clone();
+            let text = text.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match memory::add(&text).await {
+                    Ok(memories) => {
+                        store.dispatch(ChatAction::MemoriesLoaded(memories));
+                        store.dispatch(ChatAction::ResolveProposal { id, text });
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't save memory: {}", e))),
+                }
+            });
+        })
+    };
+
+    let on_new_conversation = {
+        let store = store.clone();
+        Callback::from(move |_| store.dispatch(ChatAction::NewConversation))
+    };
+
     html! {
-        <div class="flex flex-col h-full w-full max-w-5xl mx-auto p-4 md:p-6 lg:p-8">
-            <div class="flex-none flex flex-col md:flex-row items-start md:items-center justify-between mb-6 gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
-                 <div>
-                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Ollama Chat" }</h1>
-                    <div class="flex items-center gap-2 text-xs font-bold uppercase tracking-widest opacity-60">
-                        if !(*connection_error).is_empty() {
-                            <span class="w-2 h-2 rounded-full bg-error"></span>
-                            <span class="text-error">{ &*connection_error }</span>
-                        } else if *is_connecting {
-                             <span class="loading loading-spinner loading-xs text-warning"></span>
-                             <span class="text-warning">{ "Connecting..." }</span>
-                        } else {
-                            <span class="w-2 h-2 rounded-full bg-success"></span>
-                            { "Connected" }
+        <div class="flex h-full w-full">
+            <aside class={classes!("hidden", (!props.compact).then_some("xl:flex"), "flex-col", "w-64", "flex-none", "border-r", "border-base-content/5", "bg-base-100/50", "p-4", "gap-2")}>
+                <button onclick={on_new_conversation.clone()} class="btn btn-primary btn-sm rounded-xl mb-2">
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4" />
+                    </svg>
+                    { "New Chat" }
+                </button>
+                <ul class="menu menu-sm p-0 gap-1 overflow-y-auto">
+                    { for store.conversations.iter().map(|conv| {
+                        let id = conv.id;
+                        let onclick = {
+                            let store = store.clone();
+                            Callback::from(move |_| store.dispatch(ChatAction::SelectConversation(id)))
+                        };
+                        let ondelete = {
+                            let store = store.clone();
+                            Callback::from(move |e: MouseEvent| {
+                                e.stop_propagation();
+                                store.dispatch(ChatAction::DeleteConversation(id));
+                            })
+                        };
+                        html! {
+                            <li>
+                                <a
+                                    {onclick}
+                                    class={classes!("rounded-xl", "flex", "items-center", "gap-2", (id == conversation.id).then_some("active"))}
+                                >
+                                    <span class="flex-1 truncate">{ conv.display_title() }</span>
+                                    if conv.generating() {
+                                        <span class="loading loading-dots loading-xs opacity-60"></span>
+                                    } else {
+                                        <button onclick={ondelete} class="opacity-30 hover:opacity-100" title="Delete conversation">
+                                            <svg xmlns="http://www.w3.org/2000/svg" class="h-3 w-3" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
+                                            </svg>
+                                        </button>
+                                    }
+                                </a>
+                            </li>
                         }
-                    </div>
-                </div>
-
-                <div class="flex flex-col sm:flex-row gap-2 w-full md:w-auto">
-                    <div class="flex items-center gap-2 bg-base-100 border border-base-content/10 px-3 py-2 rounded-xl w-full sm:w-64">
-                         <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4 opacity-40" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13.828 10.172a4 4 0 00-5.656 0l-4 4a4 4 0 105.656 5.656l1.102-1.101m-.758-4.899a4 4 0 005.656 0l4-4a2 2 0 00-5.656-5.656l-1.1 1.1" />
-                        </svg>
+                    }) }
+                </ul>
+                <label class="mt-auto pt-2 border-t border-base-content/5 flex flex-col gap-1" title="Model that names new conversations after their first reply">
+                    <span class="text-[10px] font-bold uppercase tracking-widest opacity-50">{ "Titles by" }</span>
+                    <select class="select select-xs bg-base-100 border-base-content/10 rounded-lg" onchange={on_title_model_change}>
+                        <option value="" selected={!available_models.contains(&store.title_model)}>{ "Chat model" }</option>
+                        { for available_models.iter().map(|m| html! {
+                            <option value={m.clone()} selected={*m == store.title_model}>{ m }</option>
+                        }) }
+                    </select>
+                </label>
+            </aside>
+            <div class="flex flex-col h-full w-full max-w-5xl mx-auto p-4 md:p-6 lg:p-8">
+                <div class="flex-none flex flex-col md:flex-row items-start md:items-center justify-between mb-6 gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                     <div>
+                        <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Ollama Chat" }</h1>
                         <input
                             type="text"
-                            class="bg-transparent border-none focus:outline-none text-sm font-medium w-full"
-                            value={(*api_url).clone()}
-                            onchange={on_url_change}
-                            placeholder="API URL (e.g. http://localhost:11434)"
+                            class="bg-transparent border-none focus:outline-none text-sm font-medium w-full mb-1 opacity-80 focus:opacity-100"
+                            value={conversation.display_title()}
+                            onchange={on_rename}
+                            title="Rename conversation (clear to reset)"
                         />
-                    </div>
-
-                    <div class="relative">
-                        <select
-                            class="appearance-none bg-base-100 border border-base-content/10 px-4 py-2 pr-10 rounded-xl text-sm font-bold min-w-[160px] w-full focus:outline-none focus:border-primary/50"
-                            disabled={available_models.is_empty()}
-                            onchange={on_model_change}
-                        >
-                            if available_models.is_empty() {
-                                <option disabled=true selected=true>{ "No models found" }</option>
+                        <div class="flex items-center gap-2 text-xs font-bold uppercase tracking-widest opacity-60">
+                            if !store.connection_error.is_empty() {
+                                <span class="w-2 h-2 rounded-full bg-error"></span>
+                                <span class="text-error">{ &store.connection_error }</span>
+                            } else if store.connecting {
+                                 <span class="loading loading-spinner loading-xs text-warning"></span>
+                                 <span class="text-warning">{ "Connecting..." }</span>
                             } else {
-                                { for available_models.iter().map(|m| {
-                                    html! {
-                                        <option value={m.clone()} selected={m == &*selected_model}>{ m }</option>
-                                    }
-                                }) }
+                                <span class="w-2 h-2 rounded-full bg-success"></span>
+                                { "Connected" }
                             }
-                        </select>
-                        <div class="absolute right-3 top-1/2 -translate-y-1/2 pointer-events-none opacity-50">
-                             <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 9l-7 7-7-7" />
+                        </div>
+                    </div>
+
+                    <div class="flex flex-col sm:flex-row gap-2 w-full md:w-auto">
+                        <div class="flex items-center gap-2 bg-base-100 border border-base-content/10 px-3 py-2 rounded-xl w-full sm:w-64">
+                             <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4 opacity-40" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13.828 10.172a4 4 0 00-5.656 0l-4 4a4 4 0 105.656 5.656l1.102-1.101m-.758-4.899a4 4 0 005.656 0l4-4a2 2 0 00-5.656-5.656l-1.1 1.1" />
                             </svg>
+                            <input
+                                type="text"
+                                class="bg-transparent border-none focus:outline-none text-sm font-medium w-full"
+                                value={store.api_url.clone()}
+                                onchange={on_url_change}
+                                placeholder="API URL (e.g. http://localhost:11434)"
+                            />
+                        </div>
+
+                        <div class="relative">
+                            <select
+                                class="appearance-none bg-base-100 border border-base-content/10 px-4 py-2 pr-10 rounded-xl text-sm font-bold min-w-[160px] w-full focus:outline-none focus:border-primary/50"
+                                disabled={available_models.is_empty()}
+                                onchange={on_model_change}
+                            >
+                                if available_models.is_empty() {
+                                    <option disabled=true selected=true>{ "No models found" }</option>
+                                } else {
+                                    { for available_models.iter().map(|m| {
+                                        html! {
+                                            <option value={m.clone()} selected={*m == conversation.model}>{ m }</option>
+                                        }
+                                    }) }
+                                }
+                            </select>
+                            <div class="absolute right-3 top-1/2 -translate-y-1/2 pointer-events-none opacity-50">
+                                 <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 9l-7 7-7-7" />
+                                </svg>
+                            </div>
+                        </div>
+
+                        <button onclick={on_new_conversation.clone()} class={classes!("btn", "btn-ghost", "btn-sm", "h-auto", "rounded-xl", "border", "border-base-content/10", (!props.compact).then_some("xl:hidden"))}>
+                            { "New Chat" }
+                        </button>
+
+                        <label
+                            class="flex items-center gap-2 bg-base-100 border border-base-content/10 px-3 py-2 rounded-xl text-xs font-bold uppercase tracking-widest cursor-pointer"
+                            title="Ask reasoning models to think before answering"
+                        >
+                            <input
+                                type="checkbox"
+                                class="toggle toggle-primary toggle-xs"
+                                checked={conversation.think}
+                                onchange={on_think_toggle}
+                            />
+                            { "Reasoning" }
+                        </label>
+
+                        <div class="dropdown dropdown-end">
+                            <button
+                                tabindex="0"
+                                disabled={conversation.messages.is_empty()}
+                                class="btn btn-ghost btn-sm h-auto min-h-full rounded-xl border border-base-content/10"
+                                title="Save this conversation as a document"
+                            >
+                                if *exporting_pdf {
+                                    <span class="loading loading-spinner loading-xs"></span>
+                                }
+                                { "Export" }
+                            </button>
+                            <ul tabindex="0" class="dropdown-content menu menu-sm bg-base-100 border border-base-content/10 rounded-xl z-10 w-40 p-2 shadow">
+                                <li><a onclick={on_export_html}>{ "HTML document" }</a></li>
+                                if tauri::is_tauri() {
+                                    <li><a onclick={on_export_pdf}>{ "PDF document" }</a></li>
+                                }
+                            </ul>
                         </div>
                     </div>
                 </div>
-            </div>
 
-            <div class="flex-1 overflow-y-auto min-h-0 mb-6 space-y-4 pr-2 scrollbar-thin scrollbar-thumb-base-content/10">
-                if messages.is_empty() {
-                    <div class="h-full flex flex-col items-center justify-center opacity-20 select-none">
+                if let Some(message) = (*error).as_ref() {
+                    <div class="alert alert-error rounded-2xl mb-4 flex-none">
+                        <span class="font-medium">{ message }</span>
+                    </div>
+                }
+
+                if conversation.messages.is_empty() {
+                    <div class="flex-1 min-h-0 mb-6 flex flex-col items-center justify-center opacity-20 select-none">
                         <svg xmlns="http://www.w3.org/2000/svg" class="h-24 w-24 mb-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 10h.01M12 10h.01M16 10h.01M9 16H5a2 2 0 01-2-2V6a2 2 0 012-2h14a2 2 0 012 2v8a2 2 0 01-2 2h-5l-5 5v-5z" />
                         </svg>
                         <p class="text-xl font-bold uppercase tracking-widest">{ "Start a conversation" }</p>
-                         if !(*available_models).is_empty() {
-                            <p class="mt-2 text-sm opacity-60">{ format!("Connected to {}", *selected_model) }</p>
+                         if !available_models.is_empty() {
+                            <p class="mt-2 text-sm opacity-60">{ format!("Connected to {}", conversation.model) }</p>
                         }
                     </div>
+                } else {
+                    <MessageList
+                        key={conversation.id}
+                        messages={conversation.messages.clone()}
+                        pending={conversation.pending.clone()}
+                        waiting={is_loading && conversation.pending.is_none()}
+                    />
                 }
 
-                { for messages.iter().map(|msg| {
-                    let is_user = msg.role == "user";
-                    let bubble_class = if is_user {
-                        "bg-primary text-primary-content rounded-tr-none ml-auto"
-                    } else if msg.role == "system" {
-                         "bg-error/10 text-error border border-error/20 rounded-xl mx-auto text-center w-full"
-                    } else {
-                        "bg-base-100 border border-base-content/10 rounded-tl-none mr-auto"
-                    };
-
-                    let align_class = if is_user { "justify-end" } else { "justify-start" };
-
-                    html! {
-                        <div class={classes!("flex", align_class, "gap-4")}>
-                            if !is_user {
-                                <div class="w-8 h-8 rounded-full bg-base-content/5 flex items-center justify-center flex-shrink-0">
-                                     <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5 opacity-50" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13 10V3L4 14h7v7l9-11h-7z" />
-                                    </svg>
-                                </div>
-                            }
-
-                            <div class={classes!("max-w-[80%]", "p-4", "rounded-2xl", "whitespace-pre-wrap", "leading-relaxed", bubble_class)}>
-                                { &msg.content }
+                if !conversation.proposals.is_empty() {
+                    <div class="flex-none mb-4 space-y-2">
+                        { for conversation.proposals.iter().map(|text| html! {
+                            <div class="flex items-center gap-3 bg-primary/5 border border-primary/20 px-4 py-2 rounded-2xl">
+                                <span class="text-[10px] font-bold uppercase tracking-widest opacity-60">{ "Remember?" }</span>
+                                <span class="flex-1 text-sm">{ text }</span>
+                                <button onclick={resolve_proposal(text, true)} class="btn btn-primary btn-xs rounded-lg">{ "Save" }</button>
+                                <button onclick={resolve_proposal(text, false)} class="btn btn-ghost btn-xs rounded-lg">{ "Dismiss" }</button>
                             </div>
+                        }) }
+                    </div>
+                }
 
-                             if is_user {
-                                <div class="w-8 h-8 rounded-full bg-primary flex items-center justify-center flex-shrink-0">
-                                     <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5 text-primary-content" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z" />
-                                    </svg>
+                <form
+                    onsubmit={on_submit}
+                    ondragover={on_drag_over}
+                    ondragleave={on_drag_leave}
+                    ondrop={on_drop}
+                    class={classes!("relative", "rounded-2xl", drop_active.then_some("ring-2 ring-primary ring-offset-2 ring-offset-base-200"))}
+                >
+                    if !attachments.is_empty() {
+                        <div class="flex flex-wrap gap-2 mb-2">
+                            { for attachments.iter().enumerate().map(|(index, image)| html! {
+                                <div class="relative">
+                                    <img src={attachments::data_url(image)} alt="Attachment" class="h-16 w-16 object-cover rounded-xl border border-base-content/10" />
+                                    <button
+                                        type="button"
+                                        onclick={remove_attachment(index)}
+                                        class="absolute -top-2 -right-2 btn btn-circle btn-xs"
+                                        title="Remove attachment"
+                                    >
+                                        <svg xmlns="http://www.w3.org/2000/svg" class="h-3 w-3" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
+                                        </svg>
+                                    </button>
                                 </div>
-                            }
+                            }) }
                         </div>
                     }
-                }) }
-
-                 if *is_loading {
-                    <div class="flex justify-start gap-4">
-                        <div class="w-8 h-8 rounded-full bg-base-content/5 flex items-center justify-center flex-shrink-0">
-                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5 opacity-50" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13 10V3L4 14h7v7l9-11h-7z" />
+                    <input
+                        type="text"
+                        value={(*input_value).clone()}
+                        oninput={onplaininput}
+                        placeholder={ if available_models.is_empty() { "Please select a model..." } else { "Message Ollama..." } }
+                        disabled={is_loading || available_models.is_empty()}
+                        class="input input-lg w-full rounded-2xl pr-16 bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 transition-all shadow-sm disabled:opacity-50 disabled:cursor-not-allowed"
+                    />
+                    if is_loading {
+                        <button
+                            type="button"
+                            onclick={on_cancel}
+                            class="absolute right-2 bottom-4 btn btn-circle btn-error btn-sm h-10 w-10 min-h-0"
+                            title="Stop generating"
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="currentColor" viewBox="0 0 24 24">
+                                <rect x="6" y="6" width="12" height="12" rx="2" />
                             </svg>
-                        </div>
-                         <div class="bg-base-100 border border-base-content/10 p-4 rounded-2xl rounded-tl-none flex items-center gap-2">
-                             <span class="loading loading-dots loading-sm opacity-50"></span>
-                         </div>
-                    </div>
-                }
-            </div>
-
-            <form onsubmit={on_submit} class="relative">
-                <input
-                    type="text"
-                    value={(*input_value).clone()}
-                    oninput={onplaininput}
-                    placeholder={ if available_models.is_empty() { "Please select a model..." } else { "Message Ollama..." } }
-                    disabled={*is_loading || available_models.is_empty()}
-                    class="input input-lg w-full rounded-2xl pr-16 bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 transition-all shadow-sm disabled:opacity-50 disabled:cursor-not-allowed"
-                />
-                <button
-                    disabled={*is_loading || input_value.is_empty() || available_models.is_empty()}
-                    class="absolute right-2 top-2 btn btn-circle btn-primary btn-sm h-10 w-10 min-h-0"
-                >
-                     if *is_loading {
-                        <span class="loading loading-spinner loading-xs"></span>
+                        </button>
                     } else {
-                        <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
-                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M5 12h14M12 5l7 7-7 7" />
-                        </svg>
+                        <button
+                            disabled={(input_value.is_empty() && attachments.is_empty()) || available_models.is_empty() || waiting_for_memories}
+                            class="absolute right-2 bottom-4 btn btn-circle btn-primary btn-sm h-10 w-10 min-h-0"
+                        >
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M5 12h14M12 5l7 7-7 7" />
+                            </svg>
+                        </button>
                     }
-                </button>
-            </form>
+                </form>
+            </div>
         </div>
     }
 }
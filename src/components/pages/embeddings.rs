@@ -0,0 +1,386 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::download::download_bytes;
+use crate::embeddings::{nearest_neighbours, project_2d, similarity_matrix, to_csv, to_npy};
+use crate::ollama::EmbedRequest;
+use crate::store::ChatStore;
+
+/// Larger sets are still embedded and exported, but the matrix would be unreadable.
+const MATRIX_LIMIT: usize = 40;
+
+fn short(text: &str, len: usize) -> String {
+    let mut out: String = text.chars().take(len).collect();
+    if text.chars().count() > len {
+        out.push('…');
+    }
+    out
+}
+
+fn similarity_cell(value: f32) -> Html {
+    let alpha = value.clamp(0.0, 1.0);
+    html! {
+        <td
+            class="text-center font-mono text-[10px] p-1"
+            style={format!("background-color: oklch(var(--p) / {:.2})", alpha)}
+            title={format!("{:.4}", value)}
+        >
+            { format!("{:.2}", value) }
+        </td>
+    }
+}
+
+fn scatter_plot(texts: &[String], points: &[(f32, f32)]) -> Html {
+    let max = points
+        .iter()
+        .flat_map(|(x, y)| [x.abs(), y.abs()])
+        .fold(0.0f32, f32::max)
+        .max(1e-6);
+    // Map both axes into a 100x100 view box with a 10 unit margin.
+    let scale = |v: f32| 50.0 + v / max * 40.0;
+
+    html! {
+        <svg viewBox="0 0 100 100" class="w-full aspect-square bg-base-100 rounded-2xl border border-base-content/10">
+            <line x1="50" y1="5" x2="50" y2="95" class="stroke-base-content/10" stroke-width="0.2" />
+            <line x1="5" y1="50" x2="95" y2="50" class="stroke-base-content/10" stroke-width="0.2" />
+            { for texts.iter().zip(points).map(|(text, (x, y))| html! {
+                <g>
+                    <circle cx={scale(*x).to_string()} cy={scale(-*y).to_string()} r="1.2" class="fill-primary">
+                        <title>{ text }</title>
+                    </circle>
+                    <text x={(scale(*x) + 1.8).to_string()} y={(scale(-*y) + 1.0).to_string()} font-size="2.5" class="fill-base-content/60">
+                        { short(text, 18) }
+                    </text>
+                </g>
+            }) }
+        </svg>
+    }
+}
+
+#[function_component(EmbeddingsPage)]
+pub fn embeddings_page() -> Html {
+    let store =
+        use_context::<ChatStore>().expect("EmbeddingsPage must be rendered inside ChatProvider");
+
+    let model = use_state(String::new);
+    let input = use_state(String::new);
+    let texts = use_state(Vec::<String>::new);
+    let vectors = use_state(Vec::<Vec<f32>>::new);
+    // The model the texts were embedded with, which queries must use too.
+    let corpus_model = use_state(String::new);
+    let query = use_state(String::new);
+    let neighbours = use_state(Vec::<(usize, f32)>::new);
+    let error = use_state(|| None::<String>);
+    let is_loading = use_state(|| false);
+    let file_input_ref = use_node_ref();
+
+    let selected_model = if model.is_empty() {
+        store
+            .models
+            .iter()
+            .find(|m| m.contains("embed"))
+            .or(store.models.first())
+            .cloned()
+            .unwrap_or_default()
+    } else {
+        (*model).clone()
+    };
+
+    let on_embed = {
+        let client = store.client();
+        let selected_model = selected_model.clone();
+        let input = input.clone();
+        let texts = texts.clone();
+        let vectors = vectors.clone();
+        let corpus_model = corpus_model.clone();
+        let neighbours = neighbours.clone();
+        let error = error.clone();
+        let is_loading = is_loading.clone();
+        Callback::from(move |_| {
+            let lines: Vec<String> = input
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect();
+            if lines.is_empty() || selected_model.is_empty() {
+                return;
+            }
+
+            let request = EmbedRequest {
+                model: selected_model.clone(),
+                input: lines.clone(),
+            };
+            let client = client.clone();
+            let texts = texts.clone();
+            let vectors = vectors.clone();
+            let corpus_model = corpus_model.clone();
+            let neighbours = neighbours.clone();
+            let error = error.clone();
+            let is_loading = is_loading.clone();
+            is_loading.set(true);
+            error.set(None);
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match client.embed(&request).await {
+                    Ok(result) => {
+                        texts.set(lines);
+                        vectors.set(result);
+                        corpus_model.set(request.model);
+                        neighbours.set(Vec::new());
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+                is_loading.set(false);
+            });
+        })
+    };
+
+    let on_search = {
+        let client = store.client();
+        let corpus_model = corpus_model.clone();
+        let query = query.clone();
+        let vectors = vectors.clone();
+        let neighbours = neighbours.clone();
+        let error = error.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if query.trim().is_empty() || vectors.is_empty() {
+                return;
+            }
+            let request = EmbedRequest {
+                model: (*corpus_model).clone(),
+                input: vec![(*query).clone()],
+            };
+            let client = client.clone();
+            let corpus = (*vectors).clone();
+            let neighbours = neighbours.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match client.embed(&request).await {
+                    Ok(result) => match result.first() {
+                        Some(q) => match nearest_neighbours(q, &corpus, 10) {
+                            Ok(found) => neighbours.set(found),
+                            Err(e) => error.set(Some(e)),
+                        },
+                        None => error.set(Some("No embedding returned for the query".to_string())),
+                    },
+                    Err(e) => error.set(Some(e)),
+                }
+            });
+        })
+    };
+
+    let on_file_change = {
+        let input = input.clone();
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = target.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let input = input.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(text) => input.set(text.as_string().unwrap_or_default()),
+                    Err(err) => error.set(Some(format!("Error reading file: {:?}", err))),
+                }
+            });
+            target.set_value("");
+        })
+    };
+
+    let trigger_file_input = {
+        let file_input_ref = file_input_ref.clone();
+        Callback::from(move |_| {
+            if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    let on_export_csv = {
+        let texts = texts.clone();
+        let vectors = vectors.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            let csv = to_csv(&texts, &vectors);
+            if let Err(e) = download_bytes("embeddings.csv", "text/csv", csv.as_bytes()) {
+                error.set(Some(e));
+            }
+        })
+    };
+
+    let on_export_npy = {
+        let vectors = vectors.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            if let Err(e) = download_bytes(
+                "embeddings.npy",
+                "application/octet-stream",
+                &to_npy(&vectors),
+            ) {
+                error.set(Some(e));
+            }
+        })
+    };
+
+    let on_model_change = {
+        let model = model.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            model.set(select.value());
+        })
+    };
+
+    let on_input = {
+        let input = input.clone();
+        Callback::from(move |e: InputEvent| {
+            let area: HtmlTextAreaElement = e.target_unchecked_into();
+            input.set(area.value());
+        })
+    };
+
+    let on_query = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let field: HtmlInputElement = e.target_unchecked_into();
+            query.set(field.value());
+        })
+    };
+
+    let matrix = use_memo(vectors.clone(), |vectors| {
+        let shown = vectors.len().min(MATRIX_LIMIT);
+        similarity_matrix(&vectors[..shown])
+    });
+    let points = use_memo(vectors.clone(), |vectors| project_2d(vectors));
+
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+    let has_results = !vectors.is_empty();
+
+    html! {
+        <div class="w-full max-w-6xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Embeddings" }</h1>
+                    <p class={label_class}>{ "Similarity explorer via /api/embed" }</p>
+                </div>
+                <select
+                    class="select select-sm bg-base-100 border-base-content/10 rounded-xl font-bold min-w-[160px]"
+                    disabled={store.models.is_empty()}
+                    onchange={on_model_change}
+                >
+                    if store.models.is_empty() {
+                        <option disabled=true selected=true>{ "No models found" }</option>
+                    } else {
+                        { for store.models.iter().map(|m| html! {
+                            <option value={m.clone()} selected={*m == selected_model}>{ m }</option>
+                        }) }
+                    }
+                </select>
+            </div>
+
+            <div class="space-y-2">
+                <div class="flex items-center justify-between">
+                    <span class={label_class}>{ "Texts, one per line" }</span>
+                    <input type="file" accept=".txt,.csv,.md,text/plain" ref={file_input_ref} class="hidden" onchange={on_file_change} />
+                    <button onclick={trigger_file_input} class="btn btn-ghost btn-xs rounded-lg">{ "Load file" }</button>
+                </div>
+                <textarea
+                    class="textarea w-full h-40 rounded-2xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 text-sm"
+                    value={(*input).clone()}
+                    oninput={on_input}
+                    placeholder={"The cat sat on the mat\nA kitten is sleeping\nStock markets fell today"}
+                />
+                <div class="flex flex-wrap gap-2">
+                    <button
+                        onclick={on_embed}
+                        disabled={*is_loading || input.trim().is_empty() || store.models.is_empty()}
+                        class="btn btn-primary rounded-xl"
+                    >
+                        if *is_loading {
+                            <span class="loading loading-spinner loading-xs"></span>
+                        }
+                        { "Embed" }
+                    </button>
+                    <button onclick={on_export_csv} disabled={!has_results} class="btn btn-ghost border border-base-content/10 rounded-xl">{ "Export CSV" }</button>
+                    <button onclick={on_export_npy} disabled={!has_results} class="btn btn-ghost border border-base-content/10 rounded-xl">{ "Export .npy" }</button>
+                    if has_results {
+                        <span class="self-center text-xs opacity-50">
+                            { format!("{} vectors × {} dimensions from {}", vectors.len(), vectors[0].len(), *corpus_model) }
+                        </span>
+                    }
+                </div>
+            </div>
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-2xl">
+                    <span class="font-medium">{ message }</span>
+                </div>
+            }
+
+            if has_results {
+                <div class="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                    <div class="space-y-4">
+                        <form onsubmit={on_search} class="flex gap-2">
+                            <input
+                                type="text"
+                                class="input input-sm flex-1 rounded-xl bg-base-100 border-base-content/10"
+                                value={(*query).clone()}
+                                oninput={on_query}
+                                placeholder="Find nearest neighbours of..."
+                            />
+                            <button class="btn btn-sm btn-primary rounded-xl" disabled={query.trim().is_empty()}>{ "Search" }</button>
+                        </form>
+                        if !neighbours.is_empty() {
+                            <ul class="bg-base-100 border border-base-content/10 rounded-2xl divide-y divide-base-content/5">
+                                { for neighbours.iter().map(|(i, score)| html! {
+                                    <li class="flex items-center gap-3 px-4 py-2 text-sm">
+                                        <span class="font-mono text-xs opacity-60 w-12">{ format!("{:.3}", score) }</span>
+                                        <span class="flex-1 truncate" title={texts[*i].clone()}>{ &texts[*i] }</span>
+                                    </li>
+                                }) }
+                            </ul>
+                        }
+                        <div class="space-y-2">
+                            <span class={label_class}>{ "2D projection (PCA)" }</span>
+                            { scatter_plot(&texts, &points) }
+                        </div>
+                    </div>
+
+                    <div class="space-y-2 overflow-auto">
+                        <span class={label_class}>
+                            { "Cosine similarity" }
+                            if vectors.len() > MATRIX_LIMIT {
+                                { format!(" (first {} texts)", MATRIX_LIMIT) }
+                            }
+                        </span>
+                        <table class="table-fixed border-separate border-spacing-0.5">
+                            <thead>
+                                <tr>
+                                    <th></th>
+                                    { for (0..matrix.len()).map(|j| html! {
+                                        <th class="text-[10px] font-mono opacity-60" title={texts[j].clone()}>{ j + 1 }</th>
+                                    }) }
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { for matrix.iter().enumerate().map(|(i, row)| html! {
+                                    <tr>
+                                        <th class="text-[10px] text-left pr-2 max-w-[10rem] truncate font-medium" title={texts[i].clone()}>
+                                            { format!("{}. {}", i + 1, short(&texts[i], 24)) }
+                                        </th>
+                                        { for row.iter().map(|v| similarity_cell(*v)) }
+                                    </tr>
+                                }) }
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,374 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::download::download_bytes;
+use crate::eval::{self, scoreboard, CaseResult, Report, Suite, EXAMPLE_SUITE};
+use crate::store::ChatStore;
+
+fn file_stem(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("eval-{}", slug.trim_matches('-'))
+}
+
+#[function_component(EvalPage)]
+pub fn eval_page() -> Html {
+    let store = use_context::<ChatStore>().expect("EvalPage must be rendered inside ChatProvider");
+
+    let suite_text = use_state(|| EXAMPLE_SUITE.to_string());
+    let selected = use_state(Vec::<String>::new);
+    let run_suite = use_state(|| None::<Suite>);
+    let run_models = use_state(Vec::<String>::new);
+    let results = use_state(Vec::<CaseResult>::new);
+    let report = use_state(|| None::<Report>);
+    let detail = use_state(|| None::<(String, String)>);
+    let running = use_state(|| false);
+    let stop = use_state(|| Rc::new(Cell::new(false)));
+    let error = use_state(|| None::<String>);
+    let file_input_ref = use_node_ref();
+
+    let parsed = Suite::parse(&suite_text);
+    // Models that disappeared from the server since they were ticked are skipped.
+    let models: Vec<String> = selected
+        .iter()
+        .filter(|m| store.models.contains(m))
+        .cloned()
+        .collect();
+
+    let on_run = {
+        let client = store.client();
+        let parsed = parsed.clone();
+        let models = models.clone();
+        let run_suite = run_suite.clone();
+        let run_models = run_models.clone();
+        let results = results.clone();
+        let report = report.clone();
+        let detail = detail.clone();
+        let running = running.clone();
+        let stop = stop.clone();
+        Callback::from(move |_| {
+            let Ok(suite) = parsed.clone() else {
+                return;
+            };
+            if models.is_empty() || *running {
+                return;
+            }
+            let flag = Rc::new(Cell::new(false));
+            stop.set(flag.clone());
+            run_suite.set(Some(suite.clone()));
+            run_models.set(models.clone());
+            results.set(Vec::new());
+            report.set(None);
+            detail.set(None);
+            running.set(true);
+
+            let client = client.clone();
+            let models = models.clone();
+            let results = results.clone();
+            let report = report.clone();
+            let running = running.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut collected = Vec::new();
+                eval::run(&client, &suite, &models, flag, |result| {
+                    collected.push(result);
+                    results.set(collected.clone());
+                })
+                .await;
+                let created_at = js_sys::Date::new_0()
+                    .to_iso_string()
+                    .as_string()
+                    .unwrap_or_default();
+                report.set(Some(Report::new(&suite, models, collected, created_at)));
+                running.set(false);
+            });
+        })
+    };
+
+    let on_stop = {
+        let stop = stop.clone();
+        Callback::from(move |_| (**stop).set(true))
+    };
+
+    let on_suite_input = {
+        let suite_text = suite_text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            suite_text.set(input.value());
+        })
+    };
+
+    let on_file_change = {
+        let suite_text = suite_text.clone();
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = target.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let suite_text = suite_text.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(text) => suite_text.set(text.as_string().unwrap_or_default()),
+                    Err(err) => error.set(Some(format!("Error reading file: {:?}", err))),
+                }
+            });
+            target.set_value("");
+        })
+    };
+
+    let trigger_file_input = {
+        let file_input_ref = file_input_ref.clone();
+        Callback::from(move |_| {
+            if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    let toggle_model = |model: &str| {
+        let selected = selected.clone();
+        let model = model.to_string();
+        Callback::from(move |_| {
+            let mut next = (*selected).clone();
+            if let Some(pos) = next.iter().position(|m| *m == model) {
+                next.remove(pos);
+            } else {
+                next.push(model.clone());
+            }
+            selected.set(next);
+        })
+    };
+
+    let on_download_json = {
+        let report = report.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            let Some(report) = (*report).as_ref() else {
+                return;
+            };
+            let result = serde_json::to_vec_pretty(report)
+                .map_err(|e| e.to_string())
+                .and_then(|json| {
+                    download_bytes(
+                        &format!("{}.json", file_stem(&report.suite)),
+                        "application/json",
+                        &json,
+                    )
+                });
+            if let Err(e) = result {
+                error.set(Some(e));
+            }
+        })
+    };
+
+    let on_download_markdown = {
+        let report = report.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            let Some(report) = (*report).as_ref() else {
+                return;
+            };
+            let markdown = report.to_markdown();
+            if let Err(e) = download_bytes(
+                &format!("{}.md", file_stem(&report.suite)),
+                "text/markdown",
+                markdown.as_bytes(),
+            ) {
+                error.set(Some(e));
+            }
+        })
+    };
+
+    let scores = scoreboard(&run_models, &results);
+    let case_count = run_suite.as_ref().map_or(0, |s| s.cases.len());
+    let total_runs = case_count * run_models.len();
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+    let selected_result = (*detail).as_ref().and_then(|(model, case)| {
+        results
+            .iter()
+            .find(|r| &r.model == model && &r.case == case)
+    });
+
+    html! {
+        <div class="w-full max-w-6xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Evaluations" }</h1>
+                    <p class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Score models against a test suite" }</p>
+                </div>
+                <div class="flex gap-2">
+                    if *running {
+                        <button onclick={on_stop} class="btn btn-sm btn-ghost border border-base-content/10 rounded-xl">
+                            <span class="loading loading-spinner loading-xs"></span>
+                            { format!("Stop ({}/{})", results.len(), total_runs) }
+                        </button>
+                    } else {
+                        <button
+                            onclick={on_run}
+                            disabled={parsed.is_err() || models.is_empty()}
+                            class="btn btn-sm btn-primary rounded-xl"
+                        >
+                            { "Run suite" }
+                        </button>
+                    }
+                    <button onclick={on_download_json} disabled={report.is_none()} class="btn btn-sm btn-ghost border border-base-content/10 rounded-xl">
+                        { "Report .json" }
+                    </button>
+                    <button onclick={on_download_markdown} disabled={report.is_none()} class="btn btn-sm btn-ghost border border-base-content/10 rounded-xl">
+                        { "Report .md" }
+                    </button>
+                </div>
+            </div>
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-2xl">
+                    <span class="font-medium">{ message }</span>
+                </div>
+            }
+
+            <div class="grid grid-cols-1 lg:grid-cols-3 gap-6">
+                <div class="lg:col-span-2 space-y-2">
+                    <div class="flex items-center justify-between">
+                        <span class={label_class}>{ "Suite (JSON)" }</span>
+                        <input type="file" accept=".json,application/json" ref={file_input_ref} class="hidden" onchange={on_file_change} />
+                        <button onclick={trigger_file_input} class="btn btn-ghost btn-xs rounded-lg">{ "Load file" }</button>
+                    </div>
+                    <textarea
+                        class="textarea w-full h-72 rounded-2xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 font-mono text-xs"
+                        value={(*suite_text).clone()}
+                        oninput={on_suite_input}
+                        disabled={*running}
+                        spellcheck="false"
+                    />
+                    { match &parsed {
+                        Ok(suite) => html! {
+                            <p class="text-xs text-success font-bold">{ format!("{}: {} cases", suite.name, suite.cases.len()) }</p>
+                        },
+                        Err(e) => html! { <p class="text-xs text-error font-mono">{ e }</p> },
+                    } }
+                </div>
+
+                <div class="space-y-2">
+                    <span class={label_class}>{ "Models" }</span>
+                    <div class="bg-base-100 border border-base-content/10 rounded-2xl p-3 space-y-1 max-h-80 overflow-y-auto">
+                        if store.models.is_empty() {
+                            <p class="text-sm opacity-50">{ "No models found" }</p>
+                        }
+                        { for store.models.iter().map(|m| html! {
+                            <label class="flex items-center gap-2 cursor-pointer text-sm">
+                                <input
+                                    type="checkbox"
+                                    class="checkbox checkbox-primary checkbox-xs"
+                                    checked={selected.contains(m)}
+                                    onchange={toggle_model(m)}
+                                    disabled={*running}
+                                />
+                                <span class="truncate">{ m }</span>
+                            </label>
+                        }) }
+                    </div>
+                </div>
+            </div>
+
+            if !run_models.is_empty() {
+                <div class="overflow-x-auto bg-base-100 border border-base-content/10 rounded-2xl">
+                    <table class="table table-sm">
+                        <thead>
+                            <tr>
+                                <th>{ "Model" }</th>
+                                <th>{ "Passed" }</th>
+                                <th class="text-right">{ "Mean" }</th>
+                                <th class="text-right">{ "p50" }</th>
+                                <th class="text-right">{ "p95" }</th>
+                                <th class="text-right">{ "Max" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for scores.iter().map(|s| html! {
+                                <tr>
+                                    <td class="font-bold">{ &s.model }</td>
+                                    <td>
+                                        <div class="flex items-center gap-2">
+                                            <progress class="progress progress-success w-24" value={s.passed.to_string()} max={case_count.max(1).to_string()} />
+                                            <span class="font-mono text-xs">{ format!("{}/{}", s.passed, s.total) }</span>
+                                        </div>
+                                    </td>
+                                    <td class="text-right font-mono text-xs">{ format!("{:.0} ms", s.mean_ms) }</td>
+                                    <td class="text-right font-mono text-xs">{ format!("{:.0} ms", s.p50_ms) }</td>
+                                    <td class="text-right font-mono text-xs">{ format!("{:.0} ms", s.p95_ms) }</td>
+                                    <td class="text-right font-mono text-xs">{ format!("{:.0} ms", s.max_ms) }</td>
+                                </tr>
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+            }
+
+            if let Some(suite) = (*run_suite).as_ref() {
+                <div class="overflow-x-auto bg-base-100 border border-base-content/10 rounded-2xl">
+                    <table class="table table-xs">
+                        <thead>
+                            <tr>
+                                <th>{ "Case" }</th>
+                                { for run_models.iter().map(|m| html! { <th class="text-center">{ m }</th> }) }
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for suite.cases.iter().map(|case| html! {
+                                <tr>
+                                    <td class="max-w-[16rem] truncate" title={case.prompt.clone()}>{ &case.name }</td>
+                                    { for run_models.iter().map(|model| {
+                                        let result = results.iter().find(|r| &r.model == model && r.case == case.name);
+                                        let onclick = {
+                                            let detail = detail.clone();
+                                            let key = (model.clone(), case.name.clone());
+                                            Callback::from(move |_| detail.set(Some(key.clone())))
+                                        };
+                                        html! {
+                                            <td class="text-center">
+                                                { match result {
+                                                    Some(r) => html! {
+                                                        <button {onclick} class={classes!("badge", "badge-sm", "font-mono", if r.passed() { "badge-success" } else { "badge-error" })}>
+                                                            { format!("{} {:.0}ms", if r.passed() { "pass" } else { "fail" }, r.latency_ms) }
+                                                        </button>
+                                                    },
+                                                    None => html! { <span class="opacity-30">{ "·" }</span> },
+                                                } }
+                                            </td>
+                                        }
+                                    }) }
+                                </tr>
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+            }
+
+            if let Some(result) = selected_result {
+                <div class="bg-base-100 border border-base-content/10 rounded-2xl p-4 space-y-2">
+                    <div class="flex items-center justify-between">
+                        <span class={label_class}>{ format!("{} / {}", result.model, result.case) }</span>
+                        <span class="text-xs opacity-60 font-mono">{ format!("{:.0} ms", result.latency_ms) }</span>
+                    </div>
+                    { for result.error.iter().chain(&result.failures).map(|reason| html! {
+                        <p class="text-xs text-error font-mono">{ reason }</p>
+                    }) }
+                    <pre class="bg-base-200 rounded-xl p-3 max-h-64 overflow-auto whitespace-pre-wrap font-mono text-xs">{ &result.output }</pre>
+                </div>
+            }
+        </div>
+    }
+}
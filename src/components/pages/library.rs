@@ -0,0 +1,327 @@
+use std::rc::Rc;
+
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::library::{self, Library, LibraryEntry, LibraryView, SortKey};
+use crate::media::{self, MediaKind, PlaylistEntry};
+use crate::routes::Route;
+use crate::tauri;
+
+fn modified_on(entry: &LibraryEntry) -> String {
+    let date = js_sys::Date::new(&(entry.modified as f64 * 1000.0).into());
+    date.to_locale_date_string("en-US", &js_sys::Object::new())
+        .as_string()
+        .unwrap_or_default()
+}
+
+/// Size, dimensions and duration, whichever are known.
+fn details(entry: &LibraryEntry) -> String {
+    let mut parts = vec![library::format_size(entry.size)];
+    if let (Some(width), Some(height)) = (entry.width, entry.height) {
+        parts.push(format!("{}×{}", width, height));
+    }
+    if let Some(duration) = entry.duration {
+        parts.push(media::format_position(duration));
+    }
+    parts.join(" · ")
+}
+
+fn thumbnail(entry: &LibraryEntry) -> Html {
+    match (entry.kind, &entry.thumbnail) {
+        (MediaKind::Image, Some(thumbnail)) => html! {
+            <img src={tauri::file_url(thumbnail)} loading="lazy" alt="" class="w-full h-full object-cover" />
+        },
+        // SVGs and anything else the backend couldn't shrink are shown as they are.
+        (MediaKind::Image, None) => html! {
+            <img src={tauri::file_url(&entry.path)} loading="lazy" alt="" class="w-full h-full object-cover" />
+        },
+        // Seeking a little way in makes the browser show a frame.
+        (MediaKind::Video, _) => html! {
+            <video
+                src={format!("{}#t=0.1", tauri::file_url(&entry.path))}
+                preload="metadata"
+                muted=true
+                class="w-full h-full object-cover pointer-events-none"
+            />
+        },
+    }
+}
+
+#[function_component(LibraryPage)]
+pub fn library_page() -> Html {
+    let library = use_state(Library::default);
+    let view = use_state(LibraryView::default);
+    let grid = use_state(|| true);
+    let busy = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    let navigator = use_navigator();
+
+    // Every change returns the whole library, which replaces the one shown.
+    let apply = {
+        let library = library.clone();
+        let busy = busy.clone();
+        let error = error.clone();
+        move |result: Result<Library, String>| {
+            match result {
+                Ok(updated) => {
+                    error.set(None);
+                    library.set(updated);
+                }
+                Err(e) => error.set(Some(e)),
+            }
+            busy.set(false);
+        }
+    };
+
+    {
+        let apply = apply.clone();
+        let busy = busy.clone();
+        use_effect_with((), move |_| {
+            if tauri::is_tauri() {
+                busy.set(true);
+                wasm_bindgen_futures::spawn_local(async move { apply(library::get().await) });
+            }
+        });
+    }
+
+    let on_add_folder = {
+        let apply = apply.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            let apply = apply.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move { apply(library::add_folder().await) });
+        })
+    };
+
+    let on_refresh = {
+        let apply = apply.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            let apply = apply.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move { apply(library::refresh().await) });
+        })
+    };
+
+    let on_remove_folder = |folder: String| {
+        let apply = apply.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            let apply = apply.clone();
+            let folder = folder.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                apply(library::remove_folder(&folder).await)
+            });
+        })
+    };
+
+    let on_search = {
+        let view = view.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            view.set(LibraryView {
+                search: input.value(),
+                ..(*view).clone()
+            });
+        })
+    };
+
+    let on_sort = {
+        let view = view.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let sort = SortKey::ALL
+                .into_iter()
+                .find(|key| key.label() == select.value())
+                .unwrap_or(view.sort);
+            view.set(LibraryView {
+                sort,
+                ..(*view).clone()
+            });
+        })
+    };
+
+    let on_toggle_order = {
+        let view = view.clone();
+        Callback::from(move |_: MouseEvent| {
+            view.set(LibraryView {
+                descending: !view.descending,
+                ..(*view).clone()
+            });
+        })
+    };
+
+    let set_kind = |kind: Option<MediaKind>| {
+        let view = view.clone();
+        Callback::from(move |_: MouseEvent| {
+            view.set(LibraryView {
+                kind,
+                ..(*view).clone()
+            })
+        })
+    };
+
+    let set_grid = |value: bool| {
+        let grid = grid.clone();
+        Callback::from(move |_: MouseEvent| grid.set(value))
+    };
+
+    let shown = view.apply(&library.entries);
+
+    // Opens the clicked file in the player, queued after it the rest of what's shown.
+    let shown_paths: Rc<Vec<String>> =
+        Rc::new(shown.iter().map(|entry| entry.path.clone()).collect());
+    let open = |index: usize| {
+        let navigator = navigator.clone();
+        let shown_paths = shown_paths.clone();
+        Callback::from(move |_: MouseEvent| {
+            let entries = shown_paths[index..]
+                .iter()
+                .map(|path| PlaylistEntry {
+                    path: path.clone(),
+                    title: None,
+                })
+                .collect();
+            media::open_in_player(entries);
+            if let Some(navigator) = &navigator {
+                navigator.push(&Route::Media);
+            }
+        })
+    };
+
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+    let kind_button = |label: &'static str, kind: Option<MediaKind>| {
+        html! {
+            <button
+                onclick={set_kind(kind)}
+                class={classes!("btn", "btn-sm", "join-item", (view.kind == kind).then_some("btn-active"))}
+            >
+                { label }
+            </button>
+        }
+    };
+
+    html! {
+        <div class="w-full max-w-6xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Library" }</h1>
+                    <p class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Screenshots and recordings in your folders" }</p>
+                </div>
+                <div class="flex gap-2">
+                    <button onclick={on_refresh} class="btn btn-ghost btn-sm rounded-xl" disabled={*busy || library.folders.is_empty()}>
+                        { "Refresh" }
+                    </button>
+                    <button onclick={on_add_folder} class="btn btn-primary btn-sm rounded-xl" disabled={*busy || !tauri::is_tauri()}>
+                        { "Add folder..." }
+                    </button>
+                </div>
+            </div>
+
+            if !tauri::is_tauri() {
+                <div class="alert alert-warning rounded-2xl">
+                    <span class="font-medium">{ "The library indexes folders on disk, which needs the desktop app." }</span>
+                </div>
+            }
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-2xl">
+                    <span class="font-medium">{ message }</span>
+                </div>
+            }
+
+            if !library.folders.is_empty() {
+                <div class="space-y-2">
+                    <span class={label_class}>{ "Folders" }</span>
+                    <div class="flex flex-wrap gap-2">
+                        { for library.folders.iter().map(|folder| html! {
+                            <span class="badge badge-lg badge-ghost gap-2 rounded-xl" title={folder.clone()}>
+                                <span class="truncate max-w-xs">{ folder }</span>
+                                <button onclick={on_remove_folder(folder.clone())} class="opacity-50 hover:opacity-100" title="Remove from library" disabled={*busy}>
+                                    { "✕" }
+                                </button>
+                            </span>
+                        }) }
+                    </div>
+                </div>
+            }
+
+            <div class="flex flex-wrap items-center gap-2">
+                <input
+                    type="search"
+                    class="input input-bordered input-sm flex-1 min-w-48 rounded-xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50"
+                    value={view.search.clone()}
+                    oninput={on_search}
+                    placeholder="Filter by name"
+                />
+                <div class="join">
+                    { kind_button("All", None) }
+                    { kind_button("Images", Some(MediaKind::Image)) }
+                    { kind_button("Videos", Some(MediaKind::Video)) }
+                </div>
+                <select class="select select-bordered select-sm rounded-xl" onchange={on_sort}>
+                    { for SortKey::ALL.iter().map(|key| html! {
+                        <option value={key.label()} selected={view.sort == *key}>{ key.label() }</option>
+                    }) }
+                </select>
+                <button onclick={on_toggle_order} class="btn btn-sm btn-ghost rounded-xl" title="Reverse the order">
+                    { if view.descending { "↓" } else { "↑" } }
+                </button>
+                <div class="join">
+                    <button onclick={set_grid(true)} class={classes!("btn", "btn-sm", "join-item", grid.then_some("btn-active"))}>{ "Grid" }</button>
+                    <button onclick={set_grid(false)} class={classes!("btn", "btn-sm", "join-item", (!*grid).then_some("btn-active"))}>{ "List" }</button>
+                </div>
+            </div>
+
+            <div class="flex items-center gap-2">
+                <span class={label_class}>
+                    { format!("{} of {} files", shown.len(), library.entries.len()) }
+                </span>
+                if *busy {
+                    <span class="loading loading-spinner loading-xs opacity-60"></span>
+                }
+            </div>
+
+            if library.folders.is_empty() && tauri::is_tauri() && !*busy {
+                <div class="text-center py-16 opacity-50">{ "Add a folder to start your library." }</div>
+            } else if *grid {
+                <div class="grid grid-cols-2 sm:grid-cols-3 lg:grid-cols-4 xl:grid-cols-5 gap-3">
+                    { for shown.iter().enumerate().map(|(index, entry)| html! {
+                        <button
+                            key={entry.path.clone()}
+                            onclick={open(index)}
+                            class="text-left rounded-2xl overflow-hidden bg-base-200 border border-base-content/5 hover:border-primary/40 transition-colors"
+                            title={entry.path.clone()}
+                        >
+                            <div class="aspect-video bg-base-300">{ thumbnail(entry) }</div>
+                            <div class="p-2 space-y-0.5">
+                                <div class="text-sm font-medium truncate">{ entry.name() }</div>
+                                <div class="text-xs opacity-50 truncate">{ details(entry) }</div>
+                            </div>
+                        </button>
+                    }) }
+                </div>
+            } else {
+                <ul class="space-y-1">
+                    { for shown.iter().enumerate().map(|(index, entry)| html! {
+                        <li
+                            key={entry.path.clone()}
+                            onclick={open(index)}
+                            class="flex items-center gap-3 p-2 rounded-xl cursor-pointer hover:bg-base-content/5"
+                            title={entry.path.clone()}
+                        >
+                            <div class="w-16 h-10 flex-none rounded-lg overflow-hidden bg-base-300">{ thumbnail(entry) }</div>
+                            <span class="flex-1 truncate text-sm">{ entry.name() }</span>
+                            <span class="text-xs opacity-50 hidden md:inline">{ details(entry) }</span>
+                            <span class="text-xs opacity-50 w-28 text-right">{ modified_on(entry) }</span>
+                        </li>
+                    }) }
+                </ul>
+            }
+        </div>
+    }
+}
@@ -1,97 +1,613 @@
-use web_sys::{File, HtmlInputElement, Url};
+use js_sys::Array;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+use web_sys::{
+    Blob, BlobPropertyBag, File, HtmlElement, HtmlInputElement, HtmlMediaElement, HtmlTrackElement,
+    TextTrackMode, Url,
+};
 use yew::prelude::*;
 
-#[derive(Clone, Copy, PartialEq)]
-enum MediaKind {
-    Image,
-    Video,
+use crate::attachments::MEDIA_DRAG_TYPE;
+use crate::components::{FrameCapture, ImageEditor, ImageViewer, MediaInfo, SubtitleControls};
+use crate::media::{
+    self, MediaAction, MediaItem, MediaKind, MediaQueue, PlaylistEntry, RecentFile, Repeat,
+};
+use crate::subtitles::{to_vtt, Subtitles};
+use crate::tauri;
+
+/// Payload of Tauri's native drag events. `position` is in physical pixels from the
+/// top left of the webview.
+#[derive(Deserialize)]
+struct NativeDrag {
+    #[serde(default)]
+    paths: Vec<String>,
+    position: NativePosition,
+}
+
+#[derive(Deserialize)]
+struct NativePosition {
+    x: f64,
+    y: f64,
+}
+
+/// Whether a native drag at `position` is over `target`, so drops meant for another
+/// part of the window (such as the chat composer) are left alone.
+fn is_over(target: &NodeRef, position: &NativePosition) -> bool {
+    let (Some(element), Some(window)) = (target.cast::<HtmlElement>(), web_sys::window()) else {
+        return false;
+    };
+    let scale = window.device_pixel_ratio();
+    let (x, y) = (position.x / scale, position.y / scale);
+    let rect = element.get_bounding_client_rect();
+    x >= rect.left() && x <= rect.right() && y >= rect.top() && y <= rect.bottom()
+}
+
+/// An object URL for `text` served as `mime`.
+fn object_url(text: &str, mime: &str) -> Result<String, String> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob =
+        Blob::new_with_str_sequence_and_options(&Array::of1(&JsValue::from_str(text)), &options)
+            .map_err(|e| format!("{:?}", e))?;
+    Url::create_object_url_with_blob(&blob).map_err(|e| format!("{:?}", e))
+}
+
+/// Opens files read through the browser. Returns the names of any that aren't media.
+fn items_from_files(files: Vec<File>) -> (Vec<MediaItem>, Vec<String>) {
+    let mut items = Vec::new();
+    let mut skipped = Vec::new();
+    for file in files {
+        let name = file.name();
+        let kind = MediaKind::from_mime(&file.type_()).or_else(|| MediaKind::from_path(&name));
+        match kind.map(|kind| (kind, Url::create_object_url_with_blob(&file))) {
+            Some((kind, Ok(url))) => items.push(MediaItem {
+                id: 0,
+                name,
+                url,
+                kind,
+                path: None,
+            }),
+            Some((_, Err(e))) => log::warn!("Error creating media URL for {}: {:?}", name, e),
+            None => skipped.push(name),
+        }
+    }
+    (items, skipped)
 }
 
-fn detect_media_kind(file: &File) -> Option<MediaKind> {
-    let mime = file.type_();
-    if mime.starts_with("image/") {
-        Some(MediaKind::Image)
-    } else if mime.starts_with("video/") {
-        Some(MediaKind::Video)
-    } else {
-        None
+/// Opens files on disk by path, as dropped from the desktop onto the app or listed in
+/// a playlist.
+fn items_from_paths(entries: Vec<PlaylistEntry>) -> (Vec<MediaItem>, Vec<String>) {
+    let mut items = Vec::new();
+    let mut skipped = Vec::new();
+    for PlaylistEntry { path, title } in entries {
+        let name = title.unwrap_or_else(|| media::file_name(&path).to_string());
+        match MediaKind::from_path(&path) {
+            Some(kind) => items.push(MediaItem {
+                id: 0,
+                name,
+                url: tauri::file_url(&path),
+                kind,
+                path: Some(path),
+            }),
+            None => skipped.push(name),
+        }
     }
+    (items, skipped)
+}
+
+/// How often, in seconds of playback, a video's position is saved while it plays.
+const POSITION_SAVE_INTERVAL: f64 = 5.0;
+
+fn dropped_paths(paths: Vec<String>) -> Vec<PlaylistEntry> {
+    paths
+        .into_iter()
+        .map(|path| PlaylistEntry { path, title: None })
+        .collect()
 }
 
 #[function_component(MediaPlayer)]
 pub fn media_player() -> Html {
-    let media_url = use_state(|| None::<String>);
-    let media_kind = use_state(|| None::<MediaKind>);
+    let queue = use_reducer(MediaQueue::default);
     let error_message = use_state(|| None::<String>);
+    let drop_hover = use_state_eq(|| false);
     let file_input_ref = use_node_ref();
+    let drop_zone_ref = use_node_ref();
+    let video_ref = use_node_ref();
+    let subtitles = use_reducer(Subtitles::default);
+    let track_url = use_state(|| None::<String>);
 
-    let on_file_change = {
-        let media_url = media_url.clone();
-        let media_kind = media_kind.clone();
+    // The selected subtitles, shifted by the offset, as a file for the `<track>`.
+    {
+        let track_url = track_url.clone();
+        let cues = subtitles.current().map(|source| source.cues.clone());
+        use_effect_with((cues, subtitles.offset), move |(cues, offset)| {
+            let url = cues.as_ref().and_then(|cues| {
+                object_url(&to_vtt(cues, *offset), "text/vtt")
+                    .map_err(|e| log::warn!("Failed to create the subtitle track: {}", e))
+                    .ok()
+            });
+            track_url.set(url.clone());
+            move || {
+                if let Some(url) = url {
+                    let _ = Url::revoke_object_url(&url);
+                }
+            }
+        });
+    }
+
+    let on_track_load = Callback::from(|e: Event| {
+        // A track added after the video started isn't shown by default.
+        let track: HtmlTrackElement = e.target_unchecked_into();
+        if let Some(track) = track.track() {
+            track.set_mode(TextTrackMode::Showing);
+        }
+    });
+    let recent = use_state(Vec::<RecentFile>::new);
+
+    {
+        let recent = recent.clone();
+        use_effect_with((), move |_| {
+            if tauri::is_tauri() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match media::recent_files().await {
+                        Ok(files) => recent.set(files),
+                        Err(e) => log::warn!("Failed to load recent files: {}", e),
+                    }
+                });
+            }
+        });
+    }
+
+    let open = {
+        let dispatcher = queue.dispatcher();
         let error_message = error_message.clone();
+        let recent = recent.clone();
+        move |(items, skipped): (Vec<MediaItem>, Vec<String>)| {
+            let paths: Vec<String> = items.iter().filter_map(|item| item.path.clone()).collect();
+            if !paths.is_empty() {
+                let recent = recent.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match media::add_recent(&paths).await {
+                        Ok(files) => recent.set(files),
+                        Err(e) => log::warn!("Failed to update recent files: {}", e),
+                    }
+                });
+            }
+            if skipped.is_empty() {
+                error_message.set(None);
+            } else {
+                error_message.set(Some(format!(
+                    "Unsupported file type, skipped: {}. Please select images or videos.",
+                    skipped.join(", ")
+                )));
+            }
+            dispatcher.dispatch(MediaAction::Open(items));
+        }
+    };
+
+    {
+        let open = open.clone();
+        use_effect_with((), move |_| {
+            let pending = media::take_pending();
+            if !pending.is_empty() {
+                open(items_from_paths(pending));
+            }
+        });
+    }
+
+    let on_file_change = {
+        let open = open.clone();
         Callback::from(move |e: Event| {
             let input: HtmlInputElement = e.target_unchecked_into();
-            let Some(files) = input.files() else {
+            let files: Vec<File> = input
+                .files()
+                .map(|list| (0..list.length()).filter_map(|i| list.get(i)).collect())
+                .unwrap_or_default();
+            // Lets the same file be picked again after it was closed.
+            input.set_value("");
+            open(items_from_files(files));
+        })
+    };
+
+    // The desktop app asks through the native dialog, which gives real paths that can
+    // be saved in playlists and the recent list.
+    let trigger_file_input = {
+        let file_input_ref = file_input_ref.clone();
+        let open = open.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_| {
+            if tauri::is_tauri() {
+                let open = open.clone();
+                let error_message = error_message.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match media::open_dialog().await {
+                        Ok(paths) if paths.is_empty() => {}
+                        Ok(paths) => open(items_from_paths(dropped_paths(paths))),
+                        Err(e) => {
+                            error_message.set(Some(format!("Couldn't open the files: {}", e)))
+                        }
+                    }
+                });
+            } else if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    // Files dragged in from the browser's own file handling. Inside the desktop app the
+    // webview hands file drops to Tauri instead, which reports them below.
+    let on_drag_over = {
+        let drop_hover = drop_hover.clone();
+        Callback::from(move |e: DragEvent| {
+            let has_files = e
+                .data_transfer()
+                .is_some_and(|data| data.types().includes(&"Files".into(), 0));
+            if has_files {
+                e.prevent_default();
+                drop_hover.set(true);
+            }
+        })
+    };
+
+    let on_drag_leave = {
+        let drop_hover = drop_hover.clone();
+        Callback::from(move |_: DragEvent| drop_hover.set(false))
+    };
+
+    let on_drop = {
+        let drop_hover = drop_hover.clone();
+        let open = open.clone();
+        Callback::from(move |e: DragEvent| {
+            drop_hover.set(false);
+            let Some(list) = e.data_transfer().and_then(|data| data.files()) else {
                 return;
             };
-            let Some(file) = files.get(0) else {
+            if list.length() == 0 {
                 return;
+            }
+            e.prevent_default();
+            let files = (0..list.length()).filter_map(|i| list.get(i)).collect();
+            open(items_from_files(files));
+        })
+    };
+
+    {
+        let drop_hover = drop_hover.clone();
+        let drop_zone_ref = drop_zone_ref.clone();
+        let open = open.clone();
+        use_effect_with((), move |_| {
+            let enter = {
+                let drop_hover = drop_hover.clone();
+                let drop_zone_ref = drop_zone_ref.clone();
+                tauri::listen("tauri://drag-enter", move |drag: NativeDrag| {
+                    drop_hover.set(is_over(&drop_zone_ref, &drag.position));
+                })
+            };
+            let over = {
+                let drop_hover = drop_hover.clone();
+                let drop_zone_ref = drop_zone_ref.clone();
+                tauri::listen("tauri://drag-over", move |drag: NativeDrag| {
+                    drop_hover.set(is_over(&drop_zone_ref, &drag.position));
+                })
             };
+            let leave = {
+                let drop_hover = drop_hover.clone();
+                tauri::listen("tauri://drag-leave", move |_: IgnoredAny| {
+                    drop_hover.set(false)
+                })
+            };
+            let dropped = tauri::listen("tauri://drag-drop", move |drag: NativeDrag| {
+                drop_hover.set(false);
+                if is_over(&drop_zone_ref, &drag.position) {
+                    open(items_from_paths(dropped_paths(drag.paths)));
+                }
+            });
+            move || drop((enter, over, leave, dropped))
+        });
+    }
 
-            if let Some(old_url) = (*media_url).as_ref() {
-                Url::revoke_object_url(old_url).unwrap_or_else(|_| log::warn!("Failed to revoke media URL"));
+    {
+        // Object URLs hold the file in memory until revoked, so each is revoked once its
+        // item is closed, and all of them when the player goes away.
+        let live = use_mut_ref(Vec::<String>::new);
+        let urls: Vec<String> = queue
+            .items
+            .iter()
+            .filter(|item| item.path.is_none())
+            .map(|item| item.url.clone())
+            .collect();
+        {
+            let live = live.clone();
+            use_effect_with(urls, move |urls| {
+                for url in live.borrow().iter().filter(|url| !urls.contains(url)) {
+                    Url::revoke_object_url(url)
+                        .unwrap_or_else(|_| log::warn!("Failed to revoke media URL"));
+                }
+                *live.borrow_mut() = urls.clone();
+            });
+        }
+        use_effect_with((), move |_| {
+            move || {
+                for url in live.borrow().iter() {
+                    Url::revoke_object_url(url)
+                        .unwrap_or_else(|_| log::warn!("Failed to revoke media URL on unmount"));
+                }
             }
+        });
+    }
 
-            match detect_media_kind(&file) {
-                Some(kind) => {
-                    match Url::create_object_url_with_blob(&file) {
-                        Ok(url) => {
-                            media_url.set(Some(url));
-                            media_kind.set(Some(kind));
-                            error_message.set(None);
-                        }
-                        Err(err) => {
-                            media_url.set(None);
-                            media_kind.set(None);
-                            error_message.set(Some(format!("Error creating media URL: {:?}", err)));
+    // Lets the image be dropped on the chat composer as an attachment.
+    let on_image_drag_start = {
+        let url = queue.current_item().map(|item| item.url.clone());
+        Callback::from(move |e: DragEvent| {
+            if let (Some(url), Some(data)) = (url.as_ref(), e.data_transfer()) {
+                let _ = data.set_data(MEDIA_DRAG_TYPE, url);
+                data.set_effect_allowed("copy");
+            }
+        })
+    };
+
+    // The id of the image open in the editor, so moving on to another item closes it.
+    let editing = use_state_eq(|| None::<u32>);
+    let on_edit = {
+        let editing = editing.clone();
+        let id = queue.current_item().map(|item| item.id);
+        Callback::from(move |_: MouseEvent| editing.set(id))
+    };
+    let on_edit_close = {
+        let editing = editing.clone();
+        Callback::from(move |_: ()| editing.set(None))
+    };
+    // Opens a copy saved by the editor or the info panel.
+    let open_saved = {
+        let open = open.clone();
+        Callback::from(move |path: String| open(items_from_paths(dropped_paths(vec![path]))))
+    };
+
+    let notice = use_state(|| None::<String>);
+    let dragged = use_state(|| None::<usize>);
+
+    let action = |make: fn() -> MediaAction| {
+        let queue = queue.clone();
+        Callback::from(move |_: MouseEvent| queue.dispatch(make()))
+    };
+
+    let on_shuffle = {
+        let queue = queue.clone();
+        Callback::from(move |_: MouseEvent| {
+            let seed = (js_sys::Math::random() * u64::MAX as f64) as u64;
+            queue.dispatch(MediaAction::ToggleShuffle(seed));
+        })
+    };
+
+    // Where the current video is, so it can resume there when opened again. A video
+    // played to the end starts from the beginning next time.
+    let last_saved = use_mut_ref(|| 0.0_f64);
+    let current_path = queue.current_item().and_then(|item| item.path.clone());
+
+    let save_position = {
+        let recent = recent.clone();
+        let current_path = current_path.clone();
+        move |position: Option<f64>| {
+            let Some(path) = current_path.clone() else {
+                return;
+            };
+            let recent = recent.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match media::remember_position(&path, position).await {
+                    Ok(files) => recent.set(files),
+                    Err(e) => log::warn!("Failed to save the playback position: {}", e),
+                }
+            });
+        }
+    };
+
+    let on_loaded_metadata = {
+        let last_saved = last_saved.clone();
+        let resume_at = current_path
+            .as_ref()
+            .and_then(|path| recent.iter().find(|file| &file.path == path))
+            .and_then(|file| file.position);
+        Callback::from(move |e: Event| {
+            let video: HtmlMediaElement = e.target_unchecked_into();
+            let mut start = 0.0;
+            if let Some(position) = resume_at {
+                if position < video.duration() - POSITION_SAVE_INTERVAL {
+                    video.set_current_time(position);
+                    start = position;
+                }
+            }
+            *last_saved.borrow_mut() = start;
+        })
+    };
+
+    let on_time_update = {
+        let last_saved = last_saved.clone();
+        let save_position = save_position.clone();
+        Callback::from(move |e: Event| {
+            let video: HtmlMediaElement = e.target_unchecked_into();
+            let time = video.current_time();
+            if (time - *last_saved.borrow()).abs() >= POSITION_SAVE_INTERVAL {
+                *last_saved.borrow_mut() = time;
+                save_position(Some(time));
+            }
+        })
+    };
+
+    let on_pause = {
+        let save_position = save_position.clone();
+        Callback::from(move |e: Event| {
+            let video: HtmlMediaElement = e.target_unchecked_into();
+            // Pausing at the end is part of ending, handled below.
+            if !video.ended() {
+                *last_saved.borrow_mut() = video.current_time();
+                save_position(Some(video.current_time()));
+            }
+        })
+    };
+
+    let on_ended = {
+        let queue = queue.clone();
+        Callback::from(move |_: Event| {
+            save_position(None);
+            queue.dispatch(MediaAction::Ended);
+        })
+    };
+
+    let open_recent = |path: String| {
+        let open = open.clone();
+        Callback::from(move |_: MouseEvent| {
+            open(items_from_paths(dropped_paths(vec![path.clone()])))
+        })
+    };
+
+    let on_clear_recent = {
+        let recent = recent.clone();
+        Callback::from(move |_: MouseEvent| {
+            let recent = recent.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match media::clear_recent().await {
+                    Ok(files) => recent.set(files),
+                    Err(e) => log::warn!("Failed to clear recent files: {}", e),
+                }
+            });
+        })
+    };
+
+    let on_save_playlist = |format: &'static str| {
+        let queue = queue.clone();
+        let error_message = error_message.clone();
+        let notice = notice.clone();
+        Callback::from(move |_: MouseEvent| {
+            // Files read through the browser have no path to save.
+            let entries: Vec<PlaylistEntry> = queue
+                .items
+                .iter()
+                .filter_map(|item| {
+                    item.path.clone().map(|path| PlaylistEntry {
+                        path,
+                        title: Some(item.name.clone()),
+                    })
+                })
+                .collect();
+            let left_out = queue.items.len() - entries.len();
+            let error_message = error_message.clone();
+            let notice = notice.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match media::save_playlist(&entries, format).await {
+                    Ok(Some(path)) => {
+                        error_message.set(None);
+                        let mut message = format!("Saved {} files to {}.", entries.len(), path);
+                        if left_out > 0 {
+                            message.push_str(&format!(
+                                " {} opened through the browser had no path and were left out.",
+                                left_out
+                            ));
                         }
+                        notice.set(Some(message));
                     }
+                    Ok(None) => {}
+                    Err(e) => error_message.set(Some(format!("Couldn't save the playlist: {}", e))),
                 }
-                None => {
-                    media_url.set(None);
-                    media_kind.set(None);
-                    error_message.set(Some("Unsupported file type. Please select an image or video.".to_string()));
+            });
+        })
+    };
+
+    let on_open_playlist = {
+        let open = open.clone();
+        let error_message = error_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let open = open.clone();
+            let error_message = error_message.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match media::open_playlist().await {
+                    Ok(entries) => open(items_from_paths(entries)),
+                    Err(e) => error_message.set(Some(format!("Couldn't open the playlist: {}", e))),
                 }
+            });
+        })
+    };
+
+    // Queue rows are reordered by dragging one onto another.
+    let on_row_drag_start = |index: usize| {
+        let dragged = dragged.clone();
+        Callback::from(move |e: DragEvent| {
+            if let Some(data) = e.data_transfer() {
+                let _ = data.set_data("text/plain", &index.to_string());
+                data.set_effect_allowed("move");
             }
+            dragged.set(Some(index));
         })
     };
 
-    let trigger_file_input = {
-        let file_input_ref = file_input_ref.clone();
-        Callback::from(move |_| {
-            if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
-                input.click();
+    let on_row_drag_over = {
+        let dragged = dragged.clone();
+        Callback::from(move |e: DragEvent| {
+            if dragged.is_some() {
+                e.prevent_default();
             }
         })
     };
 
-    {
-        let media_url_handle = media_url.clone();
-        use_effect_with(media_url_handle, move |url_state_handle| {
-            let url_to_revoke = (**url_state_handle).clone();
-            move || {
-                if let Some(url) = url_to_revoke {
-                    Url::revoke_object_url(&url)
-                        .unwrap_or_else(|_| log::warn!("Failed to revoke media URL on unmount/change"));
-                }
+    let on_row_drop = |to: usize| {
+        let queue = queue.clone();
+        let dragged = dragged.clone();
+        Callback::from(move |e: DragEvent| {
+            if let Some(from) = *dragged {
+                e.prevent_default();
+                e.stop_propagation();
+                queue.dispatch(MediaAction::Move { from, to });
             }
-        });
-    }
+            dragged.set(None);
+        })
+    };
+
+    let on_row_drag_end = {
+        let dragged = dragged.clone();
+        Callback::from(move |_: DragEvent| dragged.set(None))
+    };
+
+    let select = |index: usize| {
+        let queue = queue.clone();
+        Callback::from(move |_| queue.dispatch(MediaAction::Select(index)))
+    };
+
+    let close = |index: usize| {
+        let queue = queue.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            queue.dispatch(MediaAction::Close(index));
+        })
+    };
 
-    let has_media = (*media_url).is_some() && (*media_kind).is_some();
+    let drop_zone_class = classes!(
+        "rounded-2xl",
+        "py-20",
+        "px-4",
+        "border-2",
+        "border-dashed",
+        "flex",
+        "flex-col",
+        "items-center",
+        "gap-4",
+        "transition-colors",
+        "duration-300",
+        if *drop_hover {
+            "border-primary bg-primary/5"
+        } else {
+            "border-base-300 hover:border-primary/50"
+        }
+    );
 
     html! {
-        <div class="max-w-3xl mx-auto flex flex-col items-center text-center gap-8 py-12">
+        <div
+            ref={drop_zone_ref}
+            ondragover={on_drag_over}
+            ondragleave={on_drag_leave}
+            ondrop={on_drop}
+            class="max-w-3xl mx-auto flex flex-col items-center text-center gap-8 py-12"
+        >
             <div class="space-y-2">
                 <h2 class="text-3xl font-black tracking-tight self-center">{ "Media Player" }</h2>
                 <p class="text-sm opacity-40 uppercase font-black tracking-widest">{ "Images & Video" }</p>
@@ -100,13 +616,14 @@ pub fn media_player() -> Html {
             <input
                 type="file"
                 accept="image/*,video/*"
+                multiple=true
                 ref={file_input_ref}
                 class="hidden"
                 onchange={on_file_change}
             />
 
             <button
-                onclick={trigger_file_input}
+                onclick={trigger_file_input.clone()}
                 class="btn btn-primary btn-wide btn-lg rounded-2xl transition-all duration-300"
             >
                 <svg xmlns="http://www.w3.org/2000/svg" class="h-6 w-6" fill="none" viewBox="0 0 24 24" stroke="currentColor">
@@ -121,47 +638,235 @@ pub fn media_player() -> Html {
                 </div>
             }
 
+            if let Some(message) = (*notice).as_ref() {
+                <div class="alert alert-success rounded-2xl w-full">
+                    <span class="font-medium">{ message }</span>
+                </div>
+            }
+
             <div class="w-full relative group">
-                if has_media {
-                    if let (Some(url), Some(kind)) = ((*media_url).as_ref(), *media_kind) {
-                        {
-                            match kind {
-                                MediaKind::Image => html! {
-                                    <div class="relative rounded-2xl overflow-hidden bg-base-200 p-2 ring-1 ring-base-content/5">
-                                        <img
-                                            src={url.clone()}
-                                            alt="Loaded media"
-                                            class="w-full rounded-xl object-contain max-h-[70vh] transition-transform duration-500"
+                if let Some(item) = queue.current_item() {
+                    {
+                        match item.kind {
+                            MediaKind::Image => match item.path.clone() {
+                                Some(path) if *editing == Some(item.id) => html! {
+                                    <ImageEditor
+                                        {path}
+                                        url={item.url.clone()}
+                                        alt={item.name.clone()}
+                                        ondragstart={on_image_drag_start}
+                                        onopen={open_saved.clone()}
+                                        onclose={on_edit_close}
+                                    />
+                                },
+                                path => html! {
+                                    <div class="space-y-2">
+                                        <ImageViewer
+                                            src={item.url.clone()}
+                                            alt={item.name.clone()}
+                                            ondragstart={on_image_drag_start}
                                         />
+                                        if path.is_some() {
+                                            <div class="flex justify-end">
+                                                <button onclick={on_edit} class="btn btn-sm btn-ghost rounded-xl">{ "Edit" }</button>
+                                            </div>
+                                        }
                                     </div>
                                 },
-                                MediaKind::Video => html! {
-                                    <div class="relative rounded-2xl overflow-hidden bg-black aspect-video ring-1 ring-white/10">
-                                        <video
-                                            controls=true
-                                            src={url.clone()}
-                                            class="w-full h-full"
-                                        >
-                                            { "Your browser does not support the video tag." }
-                                        </video>
+                            },
+                            MediaKind::Video => html! {
+                                <>
+                                <div class={classes!(
+                                    "relative", "rounded-2xl", "overflow-hidden", "bg-black", "aspect-video", "ring-1",
+                                    if *drop_hover { "ring-primary" } else { "ring-white/10" }
+                                )}>
+                                    <video
+                                        key={item.id}
+                                        ref={video_ref.clone()}
+                                        // Lets frames be drawn to a canvas for capture.
+                                        crossorigin="anonymous"
+                                        controls=true
+                                        autoplay=true
+                                        loop={queue.repeat == Repeat::One}
+                                        onloadedmetadata={on_loaded_metadata}
+                                        ontimeupdate={on_time_update}
+                                        onpause={on_pause}
+                                        onended={on_ended}
+                                        src={item.url.clone()}
+                                        class="subtitled w-full h-full"
+                                    >
+                                        if let (Some(url), Some(source)) = ((*track_url).clone(), subtitles.current()) {
+                                            <track
+                                                key={url.clone()}
+                                                kind="subtitles"
+                                                src={url}
+                                                label={source.label.clone()}
+                                                srclang="und"
+                                                default=true
+                                                onload={on_track_load}
+                                            />
+                                        }
+                                        { "Your browser does not support the video tag." }
+                                    </video>
+                                    <style>{ subtitles.style.css("video.subtitled") }</style>
+                                </div>
+                                <div class="mt-3">
+                                    <SubtitleControls
+                                        subtitles={subtitles.clone()}
+                                        item={item.id}
+                                        path={item.path.clone().map(AttrValue::from)}
+                                    />
+                                </div>
+                                if tauri::is_tauri() {
+                                    <div class="mt-3">
+                                        <FrameCapture
+                                            video={video_ref.clone()}
+                                            name={item.name.clone()}
+                                            onview={open_saved.clone()}
+                                        />
                                     </div>
-                                },
-                            }
+                                }
+                                </>
+                            },
                         }
-                    } else {
-                        <div class="rounded-2xl py-20 px-4 border-2 border-dashed border-base-300"></div>
+                    }
+                    if let Some(path) = item.path.clone() {
+                        <div class="mt-3">
+                            <MediaInfo {path} onopen={open_saved} />
+                        </div>
                     }
                 } else {
-                    <div class="rounded-2xl py-20 px-4 border-2 border-dashed border-base-300 flex flex-col items-center gap-4 hover:border-primary/50 transition-colors duration-300">
+                    <div class={drop_zone_class} onclick={trigger_file_input}>
                         <div class="p-4 rounded-full bg-base-300/50 text-base-content/20">
                             <svg xmlns="http://www.w3.org/2000/svg" class="h-12 w-12" fill="none" viewBox="0 0 24 24" stroke="currentColor">
                                 <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 16l4.586-4.586a2 2 0 012.828 0L16 16m-2-2l1.586-1.586a2 2 0 012.828 0L20 14m-6-6h.01M6 20h12a2 2 0 002-2V6a2 2 0 00-2-2H6a2 2 0 00-2 2v12a2 2 0 002 2z" />
                             </svg>
                         </div>
-                        <p class="text-base-content/40 font-medium">{ "Drop your media file or click to browse" }</p>
+                        <p class="text-base-content/40 font-medium">
+                            { if *drop_hover { "Release to open" } else { "Drop your media files or click to browse" } }
+                        </p>
                     </div>
                 }
             </div>
+
+            if !queue.items.is_empty() {
+                <div class="w-full space-y-2 text-left">
+                    <div class="flex flex-wrap items-center gap-2">
+                        <span class="text-xs font-bold uppercase tracking-widest opacity-60 mr-auto">
+                            { format!("Queue ({})", queue.items.len()) }
+                        </span>
+                        <div class="join">
+                            <button onclick={action(|| MediaAction::Previous)} class="btn btn-sm join-item" title="Previous">
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11 19l-7-7 7-7m8 14l-7-7 7-7" />
+                                </svg>
+                            </button>
+                            <button onclick={action(|| MediaAction::Next)} class="btn btn-sm join-item" title="Next">
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13 5l7 7-7 7M5 5l7 7-7 7" />
+                                </svg>
+                            </button>
+                            <button
+                                onclick={on_shuffle}
+                                class={classes!("btn", "btn-sm", "join-item", queue.shuffled.is_some().then_some("btn-active"))}
+                                title="Shuffle"
+                            >
+                                { "Shuffle" }
+                            </button>
+                            <button
+                                onclick={action(|| MediaAction::CycleRepeat)}
+                                class={classes!("btn", "btn-sm", "join-item", (queue.repeat != Repeat::Off).then_some("btn-active"))}
+                                title="Repeat: off, all, one"
+                            >
+                                {
+                                    match queue.repeat {
+                                        Repeat::Off => "Repeat off",
+                                        Repeat::All => "Repeat all",
+                                        Repeat::One => "Repeat one",
+                                    }
+                                }
+                            </button>
+                        </div>
+                        if tauri::is_tauri() {
+                            <div class="dropdown dropdown-end">
+                                <button tabindex="0" class="btn btn-sm btn-ghost border-base-content/10 rounded-xl">{ "Playlist" }</button>
+                                <ul tabindex="0" class="dropdown-content menu menu-sm bg-base-100 border border-base-content/10 rounded-xl z-10 w-44 p-2 shadow">
+                                    <li><a onclick={on_save_playlist("m3u")}>{ "Save as M3U" }</a></li>
+                                    <li><a onclick={on_save_playlist("json")}>{ "Save as JSON" }</a></li>
+                                    <li><a onclick={on_open_playlist.clone()}>{ "Open playlist..." }</a></li>
+                                </ul>
+                            </div>
+                        }
+                    </div>
+                    <ul class="space-y-1">
+                        { for queue.items.iter().enumerate().map(|(index, item)| html! {
+                            <li
+                                key={item.id}
+                                draggable="true"
+                                ondragstart={on_row_drag_start(index)}
+                                ondragover={on_row_drag_over.clone()}
+                                ondrop={on_row_drop(index)}
+                                ondragend={on_row_drag_end.clone()}
+                                onclick={select(index)}
+                                class={classes!(
+                                    "flex", "items-center", "gap-3", "p-2", "rounded-xl", "cursor-pointer", "border",
+                                    if index == queue.current { "border-primary/40 bg-primary/10" } else { "border-transparent hover:bg-base-content/5" },
+                                    (*dragged == Some(index)).then_some("opacity-40")
+                                )}
+                                title={item.path.clone().unwrap_or_else(|| item.name.clone())}
+                            >
+                                <div class="w-16 h-10 flex-none rounded-lg overflow-hidden bg-base-300">
+                                    {
+                                        match item.kind {
+                                            MediaKind::Image => html! {
+                                                <img src={item.url.clone()} draggable="false" alt="" class="w-full h-full object-cover" />
+                                            },
+                                            // Seeking a little way in makes the browser show a frame.
+                                            MediaKind::Video => html! {
+                                                <video src={format!("{}#t=0.1", item.url)} preload="metadata" muted=true class="w-full h-full object-cover pointer-events-none" />
+                                            },
+                                        }
+                                    }
+                                </div>
+                                <span class="flex-1 truncate text-sm">{ &item.name }</span>
+                                <button onclick={close(index)} class="btn btn-ghost btn-xs btn-square rounded-lg opacity-50 hover:opacity-100" title="Close">
+                                    <svg xmlns="http://www.w3.org/2000/svg" class="h-3 w-3" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
+                                    </svg>
+                                </button>
+                            </li>
+                        }) }
+                    </ul>
+                </div>
+            } else if tauri::is_tauri() {
+                <button onclick={on_open_playlist} class="btn btn-ghost btn-sm rounded-xl">{ "Open playlist..." }</button>
+            }
+
+            if !recent.is_empty() {
+                <div class="w-full space-y-2 text-left">
+                    <div class="flex items-center gap-2">
+                        <span class="text-xs font-bold uppercase tracking-widest opacity-60 mr-auto">{ "Recent files" }</span>
+                        <button onclick={on_clear_recent} class="btn btn-ghost btn-xs rounded-lg">{ "Clear" }</button>
+                    </div>
+                    <ul class="space-y-1">
+                        { for recent.iter().map(|file| html! {
+                            <li
+                                key={file.path.clone()}
+                                onclick={open_recent(file.path.clone())}
+                                class="flex items-center gap-3 p-2 rounded-xl cursor-pointer hover:bg-base-content/5"
+                                title={file.path.clone()}
+                            >
+                                <span class="flex-1 truncate text-sm">{ media::file_name(&file.path) }</span>
+                                if let Some(position) = file.position {
+                                    <span class="badge badge-ghost badge-sm">
+                                        { format!("Resume at {}", media::format_position(position)) }
+                                    </span>
+                                }
+                            </li>
+                        }) }
+                    </ul>
+                </div>
+            }
         </div>
     }
 }
@@ -0,0 +1,200 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::memory::{self, Memory};
+use crate::store::{ChatAction, ChatStore};
+use crate::tauri;
+
+fn saved_on(memory: &Memory) -> String {
+    let date = js_sys::Date::new(&(memory.created_at as f64 * 1000.0).into());
+    date.to_locale_date_string("en-US", &js_sys::Object::new())
+        .as_string()
+        .unwrap_or_default()
+}
+
+#[function_component(MemoryPage)]
+pub fn memory_page() -> Html {
+    let store =
+        use_context::<ChatStore>().expect("MemoryPage must be rendered inside ChatProvider");
+
+    let draft = use_state(String::new);
+    let error = use_state(|| None::<String>);
+
+    // Every change returns the full list, which replaces the one in the store.
+    let apply = {
+        let store = store.clone();
+        let error = error.clone();
+        move |result: Result<Vec<Memory>, String>| match result {
+            Ok(memories) => {
+                error.set(None);
+                store.dispatch(ChatAction::MemoriesLoaded(memories));
+                true
+            }
+            Err(e) => {
+                error.set(Some(e));
+                false
+            }
+        }
+    };
+
+    let on_add = {
+        let draft = draft.clone();
+        let apply = apply.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let text = (*draft).clone();
+            if text.trim().is_empty() {
+                return;
+            }
+            let draft = draft.clone();
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if apply(memory::add(&text).await) {
+                    draft.set(String::new());
+                }
+            });
+        })
+    };
+
+    let on_draft_input = {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            draft.set(input.value());
+        })
+    };
+
+    let on_edit = |id: u64| {
+        let apply = apply.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                apply(memory::update(id, &input.value()).await);
+            });
+        })
+    };
+
+    let on_delete = |id: u64| {
+        let apply = apply.clone();
+        Callback::from(move |_| {
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                apply(memory::delete(id).await);
+            });
+        })
+    };
+
+    let on_proposal = |id: u32, text: &str, save: bool| {
+        let store = store.clone();
+        let apply = apply.clone();
+        let text = text.to_string();
+        Callback::from(move |_| {
+            let store = store.clone();
+            let apply = apply.clone();
+            let text = text.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if !save || apply(memory::add(&text).await) {
+                    store.dispatch(ChatAction::ResolveProposal { id, text });
+                }
+            });
+        })
+    };
+
+    let proposals: Vec<(u32, String, String)> = store
+        .conversations
+        .iter()
+        .flat_map(|c| {
+            c.proposals
+                .iter()
+                .map(|p| (c.id, c.display_title(), p.clone()))
+        })
+        .collect();
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+
+    html! {
+        <div class="w-full max-w-4xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Memory" }</h1>
+                    <p class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Facts added to every new conversation" }</p>
+                </div>
+            </div>
+
+            if !tauri::is_tauri() {
+                <div class="alert alert-warning rounded-2xl">
+                    <span class="font-medium">{ "Memories are stored by the desktop app." }</span>
+                </div>
+            }
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-2xl">
+                    <span class="font-medium">{ message }</span>
+                </div>
+            }
+
+            <form onsubmit={on_add} class="flex gap-2">
+                <input
+                    type="text"
+                    class="input input-bordered flex-1 rounded-xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50"
+                    value={(*draft).clone()}
+                    oninput={on_draft_input}
+                    placeholder="e.g. Our backend is Rust with Axum and Postgres"
+                    disabled={!tauri::is_tauri()}
+                />
+                <button class="btn btn-primary rounded-xl" disabled={!tauri::is_tauri() || draft.trim().is_empty()}>
+                    { "Remember" }
+                </button>
+            </form>
+
+            if !proposals.is_empty() {
+                <div class="space-y-2">
+                    <span class={label_class}>{ "Proposed by models" }</span>
+                    { for proposals.iter().map(|(id, title, text)| html! {
+                        <div class="flex items-center gap-3 bg-primary/5 border border-primary/20 px-4 py-2 rounded-2xl">
+                            <div class="flex-1 min-w-0">
+                                <p class="text-sm">{ text }</p>
+                                <p class="text-xs opacity-50 truncate">{ format!("From \"{}\"", title) }</p>
+                            </div>
+                            <button onclick={on_proposal(*id, text, true)} class="btn btn-primary btn-xs rounded-lg">{ "Save" }</button>
+                            <button onclick={on_proposal(*id, text, false)} class="btn btn-ghost btn-xs rounded-lg">{ "Dismiss" }</button>
+                        </div>
+                    }) }
+                </div>
+            }
+
+            <div class="space-y-2">
+                <span class={label_class}>{ format!("Saved ({})", store.memories.len()) }</span>
+                if store.memories.is_empty() {
+                    <p class="text-sm opacity-50">{ "Nothing saved yet." }</p>
+                }
+                { for store.memories.iter().map(|m| html! {
+                    <div key={m.id} class="flex items-center gap-3 bg-base-100 border border-base-content/10 px-4 py-2 rounded-2xl">
+                        <input
+                            type="text"
+                            class="bg-transparent border-none focus:outline-none text-sm flex-1"
+                            value={m.text.clone()}
+                            onchange={on_edit(m.id)}
+                            title="Edit, then press Enter or leave the field to save"
+                        />
+                        <span class="text-xs opacity-40 whitespace-nowrap">{ saved_on(m) }</span>
+                        <button onclick={on_delete(m.id)} class="btn btn-ghost btn-xs btn-square rounded-lg opacity-50 hover:opacity-100" title="Forget">
+                            <svg xmlns="http://www.w3.org/2000/svg" class="h-3 w-3" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
+                            </svg>
+                        </button>
+                    </div>
+                }) }
+            </div>
+
+            <details class="collapse collapse-arrow bg-base-content/5 rounded-2xl">
+                <summary class="collapse-title min-h-0 py-3 text-xs font-bold uppercase tracking-widest opacity-60">
+                    { "System prompt for new conversations" }
+                </summary>
+                <pre class="collapse-content whitespace-pre-wrap font-mono text-xs opacity-70">
+                    { memory::system_prompt(&store.memories) }
+                </pre>
+            </details>
+        </div>
+    }
+}
@@ -1,9 +1,23 @@
+pub mod batch;
 pub mod chat;
+pub mod embeddings;
+pub mod eval;
 pub mod home;
-pub mod image;
+pub mod library;
 pub mod media;
-pub mod video;
+pub mod memory;
+pub mod modelfile;
+pub mod playground;
+pub mod workspace;
 
+pub use batch::BatchPage;
 pub use chat::ChatPage;
+pub use embeddings::EmbeddingsPage;
+pub use eval::EvalPage;
 pub use home::HomePage;
+pub use library::LibraryPage;
 pub use media::MediaPlayer;
+pub use memory::MemoryPage;
+pub use modelfile::ModelfilePage;
+pub use playground::PlaygroundPage;
+pub use workspace::WorkspacePage;
@@ -0,0 +1,287 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::modelfile::{self, Severity};
+use crate::ollama::ProgressChunk;
+use crate::store::{ChatAction, ChatStore};
+
+const STARTER: &str = "FROM llama3.2\n\nPARAMETER temperature 0.7\nPARAMETER num_ctx 4096\n\nSYSTEM \"\"\"\nYou are a helpful assistant.\n\"\"\"\n";
+
+/// Snippets appended by the toolbar buttons, one per instruction.
+const SNIPPETS: &[(&str, &str)] = &[
+    ("FROM", "FROM llama3.2"),
+    ("PARAMETER", "PARAMETER temperature 0.7"),
+    ("SYSTEM", "SYSTEM \"\"\"\n\n\"\"\""),
+    (
+        "TEMPLATE",
+        "TEMPLATE \"\"\"{{ if .System }}{{ .System }}\n{{ end }}{{ .Prompt }}\"\"\"",
+    ),
+    ("ADAPTER", "ADAPTER sha256:"),
+    ("MESSAGE", "MESSAGE user "),
+];
+
+fn progress_line(chunk: &ProgressChunk) -> String {
+    match (chunk.completed, chunk.total) {
+        (Some(done), Some(total)) if total > 0 => {
+            format!(
+                "{} ({:.0}%)",
+                chunk.status,
+                done as f64 / total as f64 * 100.0
+            )
+        }
+        _ => chunk.status.clone(),
+    }
+}
+
+#[function_component(ModelfilePage)]
+pub fn modelfile_page() -> Html {
+    let store =
+        use_context::<ChatStore>().expect("ModelfilePage must be rendered inside ChatProvider");
+
+    let name = use_state(String::new);
+    let text = use_state(|| STARTER.to_string());
+    let base = use_state(String::new);
+    let log = use_state(Vec::<String>::new);
+    let error = use_state(|| None::<String>);
+    let is_loading = use_state(|| false);
+    let is_creating = use_state(|| false);
+
+    let (parsed, diagnostics) = modelfile::parse(&text);
+    let has_errors = diagnostics.iter().any(|d| d.severity == Severity::Error);
+
+    let selected_base = if base.is_empty() {
+        store.models.first().cloned().unwrap_or_default()
+    } else {
+        (*base).clone()
+    };
+
+    let on_load = {
+        let client = store.client();
+        let selected_base = selected_base.clone();
+        let name = name.clone();
+        let text = text.clone();
+        let error = error.clone();
+        let is_loading = is_loading.clone();
+        Callback::from(move |_| {
+            if selected_base.is_empty() {
+                return;
+            }
+            error.set(None);
+            is_loading.set(true);
+            let client = client.clone();
+            let model = selected_base.clone();
+            let name = name.clone();
+            let text = text.clone();
+            let error = error.clone();
+            let is_loading = is_loading.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match client.show_model(&model).await {
+                    Ok(info) => {
+                        text.set(info.modelfile);
+                        if name.is_empty() {
+                            name.set(format!(
+                                "{}-custom",
+                                model.split(':').next().unwrap_or(&model)
+                            ));
+                        }
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+                is_loading.set(false);
+            });
+        })
+    };
+
+    let on_create = {
+        let store = store.clone();
+        let name = name.clone();
+        let log = log.clone();
+        let error = error.clone();
+        let is_creating = is_creating.clone();
+        Callback::from(move |_| {
+            if name.trim().is_empty() || has_errors || *is_creating {
+                return;
+            }
+            let request = parsed.to_create_request(name.trim());
+            let mut lines = Vec::new();
+            log.set(lines.clone());
+            error.set(None);
+            is_creating.set(true);
+
+            let store = store.clone();
+            let log = log.clone();
+            let error = error.clone();
+            let is_creating = is_creating.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = store
+                    .client()
+                    .stream_create(&request, |chunk| {
+                        let line = progress_line(&chunk);
+                        // Download-style progress repeats the same status; update it in place.
+                        match lines.last_mut() {
+                            Some(last)
+                                if last.starts_with(&chunk.status) && chunk.total.is_some() =>
+                            {
+                                *last = line
+                            }
+                            _ => lines.push(line),
+                        }
+                        log.set(lines.clone());
+                    })
+                    .await;
+
+                match result {
+                    Ok(()) => {
+                        if let Ok(models) = store.client().models().await {
+                            store.dispatch(ChatAction::ModelsLoaded(models));
+                        }
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+                is_creating.set(false);
+            });
+        })
+    };
+
+    let on_name_input = {
+        let name = name.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            name.set(input.value());
+        })
+    };
+
+    let on_text_input = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            text.set(input.value());
+        })
+    };
+
+    let on_base_change = {
+        let base = base.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            base.set(select.value());
+        })
+    };
+
+    let insert = |snippet: &'static str| {
+        let text = text.clone();
+        Callback::from(move |_| {
+            let mut value = (*text).clone();
+            if !value.is_empty() && !value.ends_with('\n') {
+                value.push('\n');
+            }
+            value.push_str(snippet);
+            value.push('\n');
+            text.set(value);
+        })
+    };
+
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+
+    html! {
+        <div class="w-full max-w-6xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Model Builder" }</h1>
+                    <p class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Create custom models from a Modelfile" }</p>
+                </div>
+                <div class="flex flex-col sm:flex-row gap-2 w-full md:w-auto">
+                    <select
+                        class="select select-sm bg-base-100 border-base-content/10 rounded-xl font-bold min-w-[160px]"
+                        disabled={store.models.is_empty()}
+                        onchange={on_base_change}
+                    >
+                        if store.models.is_empty() {
+                            <option disabled=true selected=true>{ "No models found" }</option>
+                        } else {
+                            { for store.models.iter().map(|m| html! {
+                                <option value={m.clone()} selected={*m == selected_base}>{ m }</option>
+                            }) }
+                        }
+                    </select>
+                    <button
+                        onclick={on_load}
+                        disabled={*is_loading || store.models.is_empty()}
+                        class="btn btn-sm btn-ghost border border-base-content/10 rounded-xl"
+                        title="Replace the editor contents with this model's Modelfile"
+                    >
+                        if *is_loading {
+                            <span class="loading loading-spinner loading-xs"></span>
+                        }
+                        { "Load Modelfile" }
+                    </button>
+                </div>
+            </div>
+
+            <div class="grid grid-cols-1 lg:grid-cols-3 gap-6">
+                <div class="lg:col-span-2 space-y-4">
+                    <div class="flex flex-wrap gap-2">
+                        { for SNIPPETS.iter().map(|(label, snippet)| html! {
+                            <button onclick={insert(snippet)} class="btn btn-xs btn-ghost border border-base-content/10 rounded-lg font-mono">
+                                { format!("+ {}", label) }
+                            </button>
+                        }) }
+                    </div>
+                    <textarea
+                        class="textarea w-full h-[28rem] rounded-2xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 font-mono text-sm"
+                        value={(*text).clone()}
+                        oninput={on_text_input}
+                        spellcheck="false"
+                    />
+                    <div class="space-y-1">
+                        if diagnostics.is_empty() {
+                            <p class="text-xs text-success font-bold">{ "Modelfile looks valid" }</p>
+                        }
+                        { for diagnostics.iter().map(|d| html! {
+                            <p class={classes!("text-xs", "font-mono", if d.severity == Severity::Error { "text-error" } else { "text-warning" })}>
+                                { format!("line {}: {}", d.line, d.message) }
+                            </p>
+                        }) }
+                    </div>
+                </div>
+
+                <div class="space-y-4">
+                    <div class="space-y-2">
+                        <span class={label_class}>{ "New model name" }</span>
+                        <input
+                            type="text"
+                            class="input input-bordered w-full rounded-xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50"
+                            value={(*name).clone()}
+                            oninput={on_name_input}
+                            placeholder="my-assistant"
+                        />
+                    </div>
+                    <button
+                        onclick={on_create}
+                        disabled={*is_creating || has_errors || name.trim().is_empty()}
+                        class="btn btn-primary rounded-xl w-full"
+                    >
+                        if *is_creating {
+                            <span class="loading loading-spinner loading-xs"></span>
+                        }
+                        { "Create Model" }
+                    </button>
+
+                    if let Some(message) = (*error).as_ref() {
+                        <div class="alert alert-error rounded-2xl">
+                            <span class="font-medium">{ message }</span>
+                        </div>
+                    }
+
+                    if !log.is_empty() {
+                        <div class="space-y-2">
+                            <span class={label_class}>{ "Status" }</span>
+                            <pre class="bg-base-100 border border-base-content/10 rounded-2xl p-4 max-h-80 overflow-auto whitespace-pre-wrap font-mono text-xs">
+                                { log.join("\n") }
+                            </pre>
+                        </div>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
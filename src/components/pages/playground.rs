@@ -0,0 +1,304 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::ollama::GenerateRequest;
+use crate::store::ChatStore;
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.trim().is_empty()).then(|| value.to_string())
+}
+
+fn textarea_setter(state: &UseStateHandle<String>) -> Callback<InputEvent> {
+    let state = state.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlTextAreaElement = e.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+#[function_component(PlaygroundPage)]
+pub fn playground_page() -> Html {
+    let store =
+        use_context::<ChatStore>().expect("PlaygroundPage must be rendered inside ChatProvider");
+
+    let model = use_state(String::new);
+    let prompt = use_state(String::new);
+    let system = use_state(String::new);
+    let template = use_state(String::new);
+    let suffix = use_state(String::new);
+    let raw = use_state(|| false);
+
+    let output = use_state(String::new);
+    let context = use_state(|| None::<Vec<i64>>);
+    let stats = use_state(|| None::<(u64, u64)>);
+    let error = use_state(|| None::<String>);
+    let is_generating = use_state(|| false);
+
+    let selected_model = if model.is_empty() {
+        store.models.first().cloned().unwrap_or_default()
+    } else {
+        (*model).clone()
+    };
+
+    // `continue_from` carries the context of the previous generation; the new output is
+    // appended to the transcript instead of replacing it.
+    let run = {
+        let client = store.client();
+        let selected_model = selected_model.clone();
+        let prompt = prompt.clone();
+        let system = system.clone();
+        let template = template.clone();
+        let suffix = suffix.clone();
+        let raw = raw.clone();
+        let output = output.clone();
+        let context = context.clone();
+        let stats = stats.clone();
+        let error = error.clone();
+        let is_generating = is_generating.clone();
+
+        Callback::from(move |continue_from: Option<Vec<i64>>| {
+            if selected_model.is_empty() || *is_generating {
+                return;
+            }
+
+            let request = GenerateRequest {
+                model: selected_model.clone(),
+                prompt: (*prompt).clone(),
+                suffix: non_empty(&suffix),
+                system: non_empty(&system).filter(|_| !*raw),
+                template: non_empty(&template).filter(|_| !*raw),
+                context: continue_from.clone(),
+                raw: *raw,
+                stream: true,
+            };
+
+            let mut text = if continue_from.is_some() {
+                (*output).clone()
+            } else {
+                String::new()
+            };
+            output.set(text.clone());
+            // The context to continue from is only set again by a run that finishes.
+            context.set(None);
+            stats.set(None);
+            error.set(None);
+            is_generating.set(true);
+
+            let client = client.clone();
+            let output = output.clone();
+            let context = context.clone();
+            let stats = stats.clone();
+            let error = error.clone();
+            let is_generating = is_generating.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = client
+                    .stream_generate(&request, |chunk| {
+                        text.push_str(&chunk.response);
+                        output.set(text.clone());
+                        if let Some(ctx) = chunk.context {
+                            context.set(Some(ctx));
+                        }
+                        if let (Some(count), Some(duration)) =
+                            (chunk.eval_count, chunk.eval_duration)
+                        {
+                            stats.set(Some((count, duration)));
+                        }
+                    })
+                    .await;
+
+                if let Err(e) = result {
+                    context.set(None);
+                    error.set(Some(e));
+                }
+                is_generating.set(false);
+            });
+        })
+    };
+
+    let on_generate = {
+        let run = run.clone();
+        Callback::from(move |_| run.emit(None))
+    };
+
+    let on_continue = {
+        let run = run.clone();
+        let context = context.clone();
+        Callback::from(move |_| {
+            if let Some(ctx) = (*context).clone() {
+                run.emit(Some(ctx));
+            }
+        })
+    };
+
+    let on_clear = {
+        let output = output.clone();
+        let context = context.clone();
+        let stats = stats.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            output.set(String::new());
+            context.set(None);
+            stats.set(None);
+            error.set(None);
+        })
+    };
+
+    let on_model_change = {
+        let model = model.clone();
+        let context = context.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            model.set(select.value());
+            // A context only means something to the model that produced it.
+            context.set(None);
+        })
+    };
+
+    let on_raw_toggle = {
+        let raw = raw.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            raw.set(input.checked());
+        })
+    };
+
+    let label_class = "text-xs font-bold uppercase tracking-widest opacity-60";
+    let textarea_class = "textarea w-full rounded-2xl bg-base-100 border-base-content/10 focus:outline-none focus:border-primary/50 font-mono text-sm";
+
+    html! {
+        <div class="w-full max-w-6xl mx-auto p-4 md:p-6 lg:p-8 space-y-6">
+            <div class="flex flex-col md:flex-row items-start md:items-center justify-between gap-4 bg-base-100/50 p-4 rounded-3xl border border-base-content/5">
+                <div>
+                    <h1 class="text-3xl font-black tracking-tighter uppercase mb-1">{ "Playground" }</h1>
+                    <p class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Raw completions via /api/generate" }</p>
+                </div>
+                <div class="flex flex-col sm:flex-row gap-2 w-full md:w-auto">
+                    <select
+                        class="select select-sm bg-base-100 border-base-content/10 rounded-xl font-bold min-w-[160px]"
+                        disabled={store.models.is_empty()}
+                        onchange={on_model_change}
+                    >
+                        if store.models.is_empty() {
+                            <option disabled=true selected=true>{ "No models found" }</option>
+                        } else {
+                            { for store.models.iter().map(|m| html! {
+                                <option value={m.clone()} selected={*m == selected_model}>{ m }</option>
+                            }) }
+                        }
+                    </select>
+                    <label class="flex items-center gap-2 bg-base-100 border border-base-content/10 px-3 py-2 rounded-xl text-xs font-bold uppercase tracking-widest cursor-pointer" title="Send the prompt without applying the model's template">
+                        <input type="checkbox" class="toggle toggle-primary toggle-xs" checked={*raw} onchange={on_raw_toggle} />
+                        { "Raw" }
+                    </label>
+                </div>
+            </div>
+
+            <div class="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                <div class="space-y-4">
+                    <div class="space-y-2">
+                        <span class={label_class}>{ "Prompt" }</span>
+                        <textarea
+                            class={classes!(textarea_class, "h-40")}
+                            value={(*prompt).clone()}
+                            oninput={textarea_setter(&prompt)}
+                            placeholder="def fibonacci(n):"
+                        />
+                    </div>
+                    <div class="space-y-2">
+                        <span class={label_class}>{ "Suffix (fill-in-the-middle)" }</span>
+                        <textarea
+                            class={classes!(textarea_class, "h-20")}
+                            value={(*suffix).clone()}
+                            oninput={textarea_setter(&suffix)}
+                            placeholder="    return result"
+                        />
+                    </div>
+                    <details class="collapse collapse-arrow bg-base-100 border border-base-content/10 rounded-2xl">
+                        <summary class={classes!("collapse-title", label_class)}>
+                            { "System & template override" }
+                            if *raw {
+                                <span class="ml-2 normal-case opacity-60">{ "(ignored in raw mode)" }</span>
+                            }
+                        </summary>
+                        <div class="collapse-content space-y-4">
+                            <textarea
+                                class={classes!(textarea_class, "h-20")}
+                                value={(*system).clone()}
+                                oninput={textarea_setter(&system)}
+                                disabled={*raw}
+                                placeholder="System prompt"
+                            />
+                            <textarea
+                                class={classes!(textarea_class, "h-32")}
+                                value={(*template).clone()}
+                                oninput={textarea_setter(&template)}
+                                disabled={*raw}
+                                placeholder="{{ if .System }}{{ .System }}{{ end }}{{ .Prompt }}"
+                            />
+                        </div>
+                    </details>
+                    <div class="flex gap-2">
+                        <button
+                            onclick={on_generate}
+                            disabled={*is_generating || store.models.is_empty()}
+                            class="btn btn-primary rounded-xl flex-1"
+                        >
+                            if *is_generating {
+                                <span class="loading loading-spinner loading-xs"></span>
+                            }
+                            { "Generate" }
+                        </button>
+                        <button
+                            onclick={on_continue}
+                            disabled={*is_generating || context.is_none()}
+                            class="btn btn-ghost border border-base-content/10 rounded-xl"
+                            title="Send the prompt with the context returned by the last generation"
+                        >
+                            { "Continue" }
+                        </button>
+                        <button onclick={on_clear} disabled={*is_generating} class="btn btn-ghost rounded-xl">
+                            { "Clear" }
+                        </button>
+                    </div>
+                </div>
+
+                <div class="space-y-4">
+                    <div class="space-y-2">
+                        <div class="flex items-center justify-between">
+                            <span class={label_class}>{ "Output" }</span>
+                            if let Some((count, duration)) = *stats {
+                                <span class="text-xs opacity-50">
+                                    { format!("{} tokens, {:.1} tok/s", count, count as f64 / (duration.max(1) as f64 / 1e9)) }
+                                </span>
+                            }
+                        </div>
+                        <pre class="bg-base-100 border border-base-content/10 rounded-2xl p-4 min-h-[16rem] max-h-[50vh] overflow-auto whitespace-pre-wrap font-mono text-sm">
+                            { &*output }
+                        </pre>
+                    </div>
+
+                    if let Some(message) = (*error).as_ref() {
+                        <div class="alert alert-error rounded-2xl">
+                            <span class="font-medium">{ message }</span>
+                        </div>
+                    }
+
+                    <details class="collapse collapse-arrow bg-base-100 border border-base-content/10 rounded-2xl">
+                        <summary class={classes!("collapse-title", label_class)}>
+                            { match &*context {
+                                Some(ctx) => format!("Context ({} tokens)", ctx.len()),
+                                None => "Context (none)".to_string(),
+                            } }
+                        </summary>
+                        <div class="collapse-content">
+                            <pre class="font-mono text-xs opacity-70 whitespace-pre-wrap break-all max-h-48 overflow-auto">
+                                { context.as_ref().map(|ctx| format!("{:?}", ctx)).unwrap_or_default() }
+                            </pre>
+                        </div>
+                    </details>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,124 @@
+use web_sys::{Element, HtmlElement};
+use yew::prelude::*;
+
+use crate::components::{ChatPage, MediaPlayer};
+use crate::workspace::Layout;
+
+/// The media player and chat side by side, split by a draggable divider.
+#[function_component(WorkspacePage)]
+pub fn workspace_page() -> Html {
+    let layout = use_state(Layout::load);
+    let dragging = use_state(|| false);
+    let container_ref = use_node_ref();
+
+    {
+        // Saved once a drag ends rather than on every pointer move.
+        let layout = *layout;
+        use_effect_with((layout, *dragging), move |(layout, dragging)| {
+            if !dragging {
+                layout.save();
+            }
+        });
+    }
+
+    let on_pointer_down = {
+        let dragging = dragging.clone();
+        Callback::from(move |e: PointerEvent| {
+            let divider: Element = e.target_unchecked_into();
+            if divider.set_pointer_capture(e.pointer_id()).is_ok() {
+                e.prevent_default();
+                dragging.set(true);
+            }
+        })
+    };
+
+    let on_pointer_move = {
+        let layout = layout.clone();
+        let dragging = dragging.clone();
+        let container_ref = container_ref.clone();
+        Callback::from(move |e: PointerEvent| {
+            if !*dragging {
+                return;
+            }
+            if let Some(container) = container_ref.cast::<HtmlElement>() {
+                let rect = container.get_bounding_client_rect();
+                layout.set(layout.dragged_to(e.client_x() as f64, rect.left(), rect.width()));
+            }
+        })
+    };
+
+    let on_pointer_up = {
+        let dragging = dragging.clone();
+        Callback::from(move |_: PointerEvent| dragging.set(false))
+    };
+
+    let on_reset = {
+        let layout = layout.clone();
+        Callback::from(move |_: MouseEvent| layout.set(layout.with_share(Layout::default().share)))
+    };
+
+    let on_swap = {
+        let layout = layout.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            layout.set(Layout {
+                swapped: !layout.swapped,
+                share: 1.0 - layout.share,
+            });
+        })
+    };
+
+    let (first, second) = if layout.swapped {
+        (
+            html! { <ChatPage compact=true /> },
+            html! { <MediaPlayer /> },
+        )
+    } else {
+        (
+            html! { <MediaPlayer /> },
+            html! { <ChatPage compact=true /> },
+        )
+    };
+
+    html! {
+        <div
+            ref={container_ref}
+            class={classes!("flex", "h-full", "w-full", dragging.then_some("select-none cursor-col-resize"))}
+        >
+            <section
+                class="h-full min-w-0 overflow-y-auto"
+                style={format!("flex: 0 0 {:.2}%", layout.share * 100.0)}
+            >
+                { first }
+            </section>
+
+            <div
+                class={classes!(
+                    "relative", "flex-none", "w-2", "h-full", "cursor-col-resize", "touch-none", "transition-colors",
+                    if *dragging { "bg-primary/40" } else { "bg-base-content/5 hover:bg-primary/20" }
+                )}
+                onpointerdown={on_pointer_down}
+                onpointermove={on_pointer_move}
+                onpointerup={on_pointer_up.clone()}
+                onpointercancel={on_pointer_up}
+                ondblclick={on_reset}
+                title="Drag to resize, double-click to split evenly"
+            >
+                <button
+                    onclick={on_swap}
+                    onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+                    class="absolute top-1/2 left-1/2 -translate-x-1/2 -translate-y-1/2 btn btn-circle btn-xs bg-base-100 border-base-content/10"
+                    title="Swap panes"
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" class="h-3 w-3" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 7h12m0 0l-4-4m4 4l-4 4m0 6H4m0 0l4 4m-4-4l4-4" />
+                    </svg>
+                </button>
+            </div>
+
+            <section class="h-full min-w-0 flex-1 overflow-y-auto">
+                { second }
+            </section>
+        </div>
+    }
+}
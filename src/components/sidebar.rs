@@ -3,6 +3,7 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 
 use crate::routes::Route;
+use crate::store::ChatStore;
 
 #[function_component(Sidebar)]
 pub fn sidebar() -> Html {
@@ -11,6 +12,9 @@ pub fn sidebar() -> Html {
         current_route,
         Some(Route::Media) | Some(Route::Video) | Some(Route::Image)
     );
+    let generating = use_context::<ChatStore>()
+        .map(|store| store.generating_count())
+        .unwrap_or(0);
     let theme = use_state(|| {
         window()
             .and_then(|w| w.local_storage().ok().flatten())
@@ -103,6 +107,124 @@ pub fn sidebar() -> Html {
                                     <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 10h.01M12 10h.01M16 10h.01M9 16H5a2 2 0 01-2-2V6a2 2 0 012-2h14a2 2 0 012 2v8a2 2 0 01-2 2h-5l-5 5v-5z" />
                                 </svg>
                                 { "AI Chat" }
+                                if generating > 0 {
+                                    <span class="badge badge-primary badge-sm gap-1 ml-auto" title="Replies still generating">
+                                        <span class="loading loading-spinner loading-xs"></span>
+                                        { generating }
+                                    </span>
+                                }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Workspace}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Workspace) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 4H5a1 1 0 00-1 1v14a1 1 0 001 1h4m0-16v16m0-16h10a1 1 0 011 1v14a1 1 0 01-1 1H9" />
+                                </svg>
+                                { "Workspace" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Library}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Library) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2H6a2 2 0 01-2-2V6zM14 6a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2h-2a2 2 0 01-2-2V6zM4 16a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2H6a2 2 0 01-2-2v-2zM14 16a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2h-2a2 2 0 01-2-2v-2z" />
+                                </svg>
+                                { "Library" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Playground}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Playground) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4" />
+                                </svg>
+                                { "Playground" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Embeddings}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Embeddings) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11 3.055A9.001 9.001 0 1020.945 13H11V3.055z M20.488 9H15V3.512A9.025 9.025 0 0120.488 9z" />
+                                </svg>
+                                { "Embeddings" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Modelfile}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Modelfile) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 13h6m-3-3v6m5 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z" />
+                                </svg>
+                                { "Model Builder" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Batch}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Batch) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6h16M4 10h16M4 14h16M4 18h16" />
+                                </svg>
+                                { "Batch Jobs" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Eval}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Eval) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 5H7a2 2 0 00-2 2v12a2 2 0 002 2h10a2 2 0 002-2V7a2 2 0 00-2-2h-2M9 5a2 2 0 002 2h2a2 2 0 002-2M9 5a2 2 0 012-2h2a2 2 0 012 2m-6 9l2 2 4-4" />
+                                </svg>
+                                { "Evaluations" }
+                            </Link<Route>>
+                        </li>
+                        <li>
+                            <Link<Route>
+                                to={Route::Memory}
+                                classes={classes!(
+                                    "rounded-xl", "px-4", "py-3", "flex", "items-center", "gap-4", "transition-all", "duration-200",
+                                    if current_route == Some(Route::Memory) { "active bg-primary/10 text-primary font-bold" } else { "hover:bg-base-content/5" }
+                                )}
+                            >
+                                <svg xmlns="http://www.w3.org/2000/svg" class="h-5 w-5" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9.663 17h4.673M12 3v1m6.364 1.636l-.707.707M21 12h-1M4 12H3m3.343-5.657l-.707-.707m2.828 9.9a5 5 0 117.072 0l-.548.547A3.374 3.374 0 0014 18.469V19a2 2 0 11-4 0v-.531c0-.895-.356-1.754-.988-2.386l-.548-.547z" />
+                                </svg>
+                                { "Memory" }
                             </Link<Route>>
                         </li>
                     </ul>
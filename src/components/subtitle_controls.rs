@@ -0,0 +1,220 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::media::file_name;
+use crate::subtitles::{
+    self, SubtitleAction, SubtitleBackground, SubtitleColor, SubtitleSize, SubtitleSource,
+    SubtitleStyle, Subtitles,
+};
+use crate::tauri;
+
+#[derive(Properties, PartialEq)]
+pub struct SubtitleControlsProps {
+    pub subtitles: UseReducerHandle<Subtitles>,
+    /// The queue item of the video playing.
+    pub item: u32,
+    /// Where the video is on disk, for finding subtitles next to it.
+    #[prop_or_default]
+    pub path: Option<AttrValue>,
+}
+
+async fn load(path: &str) -> Result<SubtitleSource, String> {
+    let text = subtitles::read(path).await?;
+    Ok(SubtitleSource::new(file_name(path), &text))
+}
+
+/// Picks, loads, shifts and styles the subtitles of the video playing. Files named
+/// after a video opened by path are loaded as soon as it's opened.
+#[function_component(SubtitleControls)]
+pub fn subtitle_controls(props: &SubtitleControlsProps) -> Html {
+    let file_input_ref = use_node_ref();
+    let error = use_state(|| None::<String>);
+
+    {
+        let dispatcher = props.subtitles.dispatcher();
+        let error = error.clone();
+        use_effect_with((props.item, props.path.clone()), move |(item, path)| {
+            let item = *item;
+            dispatcher.dispatch(SubtitleAction::Reset(Some(item)));
+            error.set(None);
+            if let Some(path) = path.clone().filter(|_| tauri::is_tauri()) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let found = match subtitles::find(&path).await {
+                        Ok(found) => found,
+                        Err(e) => {
+                            log::warn!("Failed to look for subtitles: {}", e);
+                            return;
+                        }
+                    };
+                    let mut sources = Vec::new();
+                    for path in found {
+                        match load(&path).await {
+                            Ok(source) => sources.push(source),
+                            Err(e) => log::warn!("Failed to load subtitles {}: {}", path, e),
+                        }
+                    }
+                    dispatcher.dispatch(SubtitleAction::Found { item, sources });
+                });
+            }
+        });
+    }
+
+    let on_load = {
+        let dispatcher = props.subtitles.dispatcher();
+        let error = error.clone();
+        let file_input_ref = file_input_ref.clone();
+        Callback::from(move |_: MouseEvent| {
+            if !tauri::is_tauri() {
+                if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
+                    input.click();
+                }
+                return;
+            }
+            let dispatcher = dispatcher.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let loaded = match subtitles::open_dialog().await {
+                    Ok(Some(path)) => load(&path).await,
+                    Ok(None) => return,
+                    Err(e) => Err(e),
+                };
+                match loaded {
+                    Ok(source) => {
+                        error.set(None);
+                        dispatcher.dispatch(SubtitleAction::Add(source));
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't load the subtitles: {}", e))),
+                }
+            });
+        })
+    };
+
+    // Files read through the browser, when not running in Tauri.
+    let on_file_change = {
+        let dispatcher = props.subtitles.dispatcher();
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            input.set_value("");
+            let dispatcher = dispatcher.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(text) => {
+                        let text = text.as_string().unwrap_or_default();
+                        error.set(None);
+                        dispatcher
+                            .dispatch(SubtitleAction::Add(SubtitleSource::new(file.name(), &text)));
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't read the subtitles: {:?}", e))),
+                }
+            });
+        })
+    };
+
+    let on_select = {
+        let dispatcher = props.subtitles.dispatcher();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            dispatcher.dispatch(SubtitleAction::Select(select.value().parse().ok()));
+        })
+    };
+
+    let shift = |seconds: f64| {
+        let dispatcher = props.subtitles.dispatcher();
+        Callback::from(move |_: MouseEvent| dispatcher.dispatch(SubtitleAction::Shift(seconds)))
+    };
+
+    let on_reset_offset = {
+        let dispatcher = props.subtitles.dispatcher();
+        Callback::from(move |_: MouseEvent| dispatcher.dispatch(SubtitleAction::ResetOffset))
+    };
+
+    let style = props.subtitles.style;
+    let restyle = |change: fn(&mut SubtitleStyle, usize)| {
+        let dispatcher = props.subtitles.dispatcher();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(index) = select.value().parse() {
+                let mut style = style;
+                change(&mut style, index);
+                dispatcher.dispatch(SubtitleAction::Style(style));
+            }
+        })
+    };
+
+    let subtitles = &props.subtitles;
+    let off = subtitles.selected.is_none();
+    let select_class = "select select-bordered select-xs rounded-lg";
+
+    html! {
+        <div class="rounded-2xl bg-base-content/5 p-3 space-y-2 text-left text-sm">
+            <div class="flex flex-wrap items-center gap-2">
+                <span class="text-xs font-bold uppercase tracking-widest opacity-60">{ "Subtitles" }</span>
+                <select class={classes!(select_class, "max-w-48")} onchange={on_select}>
+                    <option value="" selected={off}>{ "Off" }</option>
+                    {
+                        for subtitles.sources.iter().enumerate().map(|(index, source)| html! {
+                            <option value={index.to_string()} selected={subtitles.selected == Some(index)}>
+                                { &source.label }
+                            </option>
+                        })
+                    }
+                </select>
+                <button onclick={on_load} class="btn btn-xs btn-ghost">{ "Load..." }</button>
+                <input
+                    type="file"
+                    accept=".srt,.vtt"
+                    class="hidden"
+                    ref={file_input_ref}
+                    onchange={on_file_change}
+                />
+
+                <div class="join ml-auto" title="Shift the subtitles earlier or later">
+                    <button onclick={shift(-0.5)} class="btn btn-xs join-item" disabled={off}>{ "−0.5" }</button>
+                    <button onclick={shift(-0.1)} class="btn btn-xs join-item" disabled={off}>{ "−0.1" }</button>
+                    <button onclick={on_reset_offset} class="btn btn-xs join-item font-mono w-20" disabled={off} title="Reset the offset">
+                        { format!("{:+.1} s", subtitles.offset) }
+                    </button>
+                    <button onclick={shift(0.1)} class="btn btn-xs join-item" disabled={off}>{ "+0.1" }</button>
+                    <button onclick={shift(0.5)} class="btn btn-xs join-item" disabled={off}>{ "+0.5" }</button>
+                </div>
+            </div>
+
+            <div class="flex flex-wrap items-center gap-2">
+                <span class="text-xs opacity-60">{ "Size" }</span>
+                <select class={select_class} onchange={restyle(|style, i| style.size = SubtitleSize::ALL[i])}>
+                    {
+                        for SubtitleSize::ALL.iter().enumerate().map(|(i, size)| html! {
+                            <option value={i.to_string()} selected={style.size == *size}>{ size.label() }</option>
+                        })
+                    }
+                </select>
+                <span class="text-xs opacity-60">{ "Colour" }</span>
+                <select class={select_class} onchange={restyle(|style, i| style.color = SubtitleColor::ALL[i])}>
+                    {
+                        for SubtitleColor::ALL.iter().enumerate().map(|(i, color)| html! {
+                            <option value={i.to_string()} selected={style.color == *color}>{ color.label() }</option>
+                        })
+                    }
+                </select>
+                <span class="text-xs opacity-60">{ "Background" }</span>
+                <select class={select_class} onchange={restyle(|style, i| style.background = SubtitleBackground::ALL[i])}>
+                    {
+                        for SubtitleBackground::ALL.iter().enumerate().map(|(i, background)| html! {
+                            <option value={i.to_string()} selected={style.background == *background}>{ background.label() }</option>
+                        })
+                    }
+                </select>
+            </div>
+
+            if let Some(message) = (*error).as_ref() {
+                <div class="alert alert-error rounded-xl py-2 text-sm">{ message }</div>
+            }
+        </div>
+    }
+}
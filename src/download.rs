@@ -0,0 +1,28 @@
+use js_sys::{Array, Uint8Array};
+use wasm_bindgen::JsCast;
+use web_sys::{window, Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+/// Offers `bytes` to the user as a file download named `filename`.
+pub fn download_bytes(filename: &str, mime: &str, bytes: &[u8]) -> Result<(), String> {
+    let parts = Array::of1(&Uint8Array::from(bytes));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|e| format!("Error creating file: {:?}", e))?;
+    let url = Url::create_object_url_with_blob(&blob)
+        .map_err(|e| format!("Error creating download URL: {:?}", e))?;
+
+    let result = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.create_element("a").ok())
+        .and_then(|el| el.dyn_into::<HtmlAnchorElement>().ok())
+        .map(|anchor| {
+            anchor.set_href(&url);
+            anchor.set_download(filename);
+            anchor.click();
+        })
+        .ok_or_else(|| "Error starting download".to_string());
+
+    Url::revoke_object_url(&url).unwrap_or_else(|_| log::warn!("Failed to revoke download URL"));
+    result
+}
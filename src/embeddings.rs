@@ -0,0 +1,282 @@
+/// Cosine similarity of two vectors. Zero-length vectors are similar to nothing.
+/// `None` when the lengths differ, as for vectors from different models.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        Some(0.0)
+    } else {
+        Some(dot / (norm_a * norm_b))
+    }
+}
+
+/// Pairwise similarities of vectors from one model. Pairs of different lengths,
+/// which one model never returns, count as dissimilar.
+pub fn similarity_matrix(vectors: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    vectors
+        .iter()
+        .map(|a| {
+            vectors
+                .iter()
+                .map(|b| cosine_similarity(a, b).unwrap_or(0.0))
+                .collect()
+        })
+        .collect()
+}
+
+/// The `k` vectors most similar to `query`, as `(index, similarity)` pairs, best first.
+/// Fails if `query` has a different length from the vectors, which means it wasn't
+/// embedded by the same model.
+pub fn nearest_neighbours(
+    query: &[f32],
+    vectors: &[Vec<f32>],
+    k: usize,
+) -> Result<Vec<(usize, f32)>, String> {
+    let mut scored = Vec::with_capacity(vectors.len());
+    for (i, v) in vectors.iter().enumerate() {
+        let similarity = cosine_similarity(query, v).ok_or_else(|| {
+            format!(
+                "The query has {} dimensions but the texts have {}",
+                query.len(),
+                v.len()
+            )
+        })?;
+        scored.push((i, similarity));
+    }
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(k);
+    Ok(scored)
+}
+
+/// Projects vectors onto their first two principal components, found by power
+/// iteration on the covariance matrix. Good enough for a scatter plot of a few
+/// hundred points; degenerate inputs collapse onto an axis instead of failing.
+pub fn project_2d(vectors: &[Vec<f32>]) -> Vec<(f32, f32)> {
+    let n = vectors.len();
+    let dims = vectors.first().map_or(0, Vec::len);
+    if n == 0 || dims == 0 {
+        return vec![(0.0, 0.0); n];
+    }
+
+    let mut mean = vec![0.0f64; dims];
+    for v in vectors {
+        for (m, x) in mean.iter_mut().zip(v) {
+            *m += *x as f64 / n as f64;
+        }
+    }
+    let centered: Vec<Vec<f64>> = vectors
+        .iter()
+        .map(|v| v.iter().zip(&mean).map(|(x, m)| *x as f64 - m).collect())
+        .collect();
+
+    let first = principal_component(&centered, &[]);
+    let second = principal_component(&centered, std::slice::from_ref(&first));
+
+    centered
+        .iter()
+        .map(|v| (dot(v, &first) as f32, dot(v, &second) as f32))
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Dominant direction of `rows` orthogonal to every vector in `exclude`.
+fn principal_component(rows: &[Vec<f64>], exclude: &[Vec<f64>]) -> Vec<f64> {
+    let dims = rows[0].len();
+    // A fixed, uneven start vector keeps results deterministic and avoids starting
+    // exactly orthogonal to the answer for symmetric data.
+    let mut v: Vec<f64> = (0..dims).map(|i| 1.0 + i as f64 * 0.01).collect();
+
+    for _ in 0..100 {
+        for e in exclude {
+            let d = dot(&v, e);
+            v.iter_mut().zip(e).for_each(|(x, y)| *x -= d * y);
+        }
+        // Multiply by the covariance matrix without building it: Xᵀ(Xv).
+        let mut next = vec![0.0; dims];
+        for row in rows {
+            let d = dot(row, &v);
+            next.iter_mut().zip(row).for_each(|(x, y)| *x += d * y);
+        }
+        for e in exclude {
+            let d = dot(&next, e);
+            next.iter_mut().zip(e).for_each(|(x, y)| *x -= d * y);
+        }
+        let norm = dot(&next, &next).sqrt();
+        if norm < 1e-12 {
+            return vec![0.0; dims];
+        }
+        next.iter_mut().for_each(|x| *x /= norm);
+        let converged = dot(&next, &v).abs() / dot(&v, &v).sqrt() > 1.0 - 1e-10;
+        v = next;
+        if converged {
+            break;
+        }
+    }
+    v
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// One row per text: the text itself followed by its embedding.
+pub fn to_csv(texts: &[String], vectors: &[Vec<f32>]) -> String {
+    let dims = vectors.first().map_or(0, Vec::len);
+    let mut out = String::from("text");
+    for i in 0..dims {
+        out.push_str(&format!(",d{}", i));
+    }
+    out.push('\n');
+    for (text, vector) in texts.iter().zip(vectors) {
+        out.push_str(&csv_field(text));
+        for x in vector {
+            out.push_str(&format!(",{}", x));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Encodes the embeddings as a 2-D little-endian float32 array in NumPy's `.npy`
+/// format (version 1.0), loadable with `numpy.load`.
+pub fn to_npy(vectors: &[Vec<f32>]) -> Vec<u8> {
+    let dims = vectors.first().map_or(0, Vec::len);
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        vectors.len(),
+        dims
+    );
+    // Magic, version and length take 10 bytes; the header is padded so the data
+    // starts on a 64-byte boundary and ends with a newline.
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut out = Vec::with_capacity(10 + header.len() + vectors.len() * dims * 4);
+    out.extend_from_slice(b"\x93NUMPY");
+    out.extend_from_slice(&[1, 0]);
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    for x in vectors.iter().flatten() {
+        out.extend_from_slice(&x.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn cosine_of_parallel_orthogonal_and_opposite_vectors() {
+        let cosine = |a: &[f32], b: &[f32]| cosine_similarity(a, b).unwrap();
+        assert!(approx(cosine(&[1.0, 2.0], &[2.0, 4.0]), 1.0));
+        assert!(approx(cosine(&[1.0, 0.0], &[0.0, 3.0]), 0.0));
+        assert!(approx(cosine(&[1.0, 1.0], &[-1.0, -1.0]), -1.0));
+    }
+
+    #[test]
+    fn cosine_with_zero_vector_is_zero() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 2.0]), Some(0.0));
+    }
+
+    #[test]
+    fn vectors_of_different_lengths_are_not_compared() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), None);
+        let vectors = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+        let error = nearest_neighbours(&[1.0, 0.0], &vectors, 2).unwrap_err();
+        assert!(error.contains("2 dimensions"), "{}", error);
+        assert!(error.contains("have 3"), "{}", error);
+    }
+
+    #[test]
+    fn similarity_matrix_is_symmetric_with_unit_diagonal() {
+        let vectors = vec![vec![1.0, 0.0], vec![1.0, 1.0], vec![0.0, 1.0]];
+        let matrix = similarity_matrix(&vectors);
+        for (i, row) in matrix.iter().enumerate() {
+            assert!(approx(row[i], 1.0));
+            for (j, value) in row.iter().enumerate() {
+                assert!(approx(*value, matrix[j][i]));
+            }
+        }
+        assert!(approx(matrix[0][1], std::f32::consts::FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn nearest_neighbours_are_ranked_and_truncated() {
+        let vectors = vec![
+            vec![0.0, 1.0],
+            vec![1.0, 0.1],
+            vec![1.0, 0.0],
+            vec![-1.0, 0.0],
+        ];
+        let result = nearest_neighbours(&[1.0, 0.0], &vectors, 2).unwrap();
+        assert_eq!(result.iter().map(|r| r.0).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(approx(result[0].1, 1.0));
+    }
+
+    #[test]
+    fn projection_keeps_the_dominant_axis() {
+        // Points spread along (1, 1, 0) with a little noise on z.
+        let vectors: Vec<Vec<f32>> = (0..5)
+            .map(|i| {
+                let t = i as f32;
+                vec![t, t, if i % 2 == 0 { 0.1 } else { -0.1 }]
+            })
+            .collect();
+        let points = project_2d(&vectors);
+        let xs: Vec<f32> = points.iter().map(|p| p.0).collect();
+        let ascending = xs.windows(2).all(|w| w[0] < w[1]);
+        let descending = xs.windows(2).all(|w| w[0] > w[1]);
+        assert!(ascending || descending, "{:?}", xs);
+        assert!(approx((xs[4] - xs[0]).abs(), 4.0 * 2f32.sqrt()));
+        assert!(points.iter().all(|p| p.1.abs() < 0.2));
+    }
+
+    #[test]
+    fn projection_handles_degenerate_input() {
+        assert!(project_2d(&[]).is_empty());
+        let same = vec![vec![1.0, 2.0]; 3];
+        assert_eq!(project_2d(&same), vec![(0.0, 0.0); 3]);
+    }
+
+    #[test]
+    fn csv_quotes_texts_that_need_it() {
+        let texts = vec!["plain".to_string(), "a, \"quoted\" one".to_string()];
+        let vectors = vec![vec![1.0, 0.5], vec![-2.0, 0.0]];
+        assert_eq!(
+            to_csv(&texts, &vectors),
+            "text,d0,d1\nplain,1,0.5\n\"a, \"\"quoted\"\" one\",-2,0\n"
+        );
+    }
+
+    #[test]
+    fn npy_header_is_aligned_and_describes_the_shape() {
+        let vectors = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let bytes = to_npy(&vectors);
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.contains("'shape': (2, 3)"));
+        assert!(header.ends_with('\n'));
+        let data = &bytes[10 + header_len..];
+        assert_eq!(data.len(), 6 * 4);
+        assert_eq!(&data[4..8], &2.0f32.to_le_bytes());
+    }
+}
@@ -0,0 +1,526 @@
+//! Scored test suites: prompts with assertions on the answer, run against one or more
+//! models to compare pass rates and latency.
+
+pub mod schema;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use ollama_client::{Client, Transport};
+
+use crate::ollama::{ChatRequest, Message};
+use crate::thinking::ThinkSplitter;
+
+pub const EXAMPLE_SUITE: &str = r#"{
+  "name": "Smoke test",
+  "system": "Answer as briefly as possible.",
+  "options": { "temperature": 0 },
+  "cases": [
+    {
+      "name": "capital",
+      "prompt": "What is the capital of France?",
+      "expect": [{ "contains": "Paris" }]
+    },
+    {
+      "name": "arithmetic",
+      "prompt": "What is 17 + 25? Reply with the number only.",
+      "expect": [{ "exact": "42" }]
+    },
+    {
+      "name": "iso date",
+      "prompt": "Write the date of the first Moon landing in ISO 8601 format, nothing else.",
+      "expect": [{ "regex": "^1969-07-20$" }]
+    },
+    {
+      "name": "structured output",
+      "prompt": "Return a JSON object with fields name (string) and year (integer) for the Rust programming language's 1.0 release. JSON only.",
+      "expect": [{
+        "json_schema": {
+          "type": "object",
+          "required": ["name", "year"],
+          "properties": { "name": { "type": "string" }, "year": { "type": "integer", "minimum": 2015, "maximum": 2015 } }
+        }
+      }]
+    }
+  ]
+}
+"#;
+
+/// What an answer must satisfy. `contains` ignores case, `exact` compares the trimmed
+/// answer, `regex` is matched as written and `json_schema` validates the JSON found
+/// in the answer (code fences are allowed).
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Assertion {
+    Contains(String),
+    Regex(String),
+    JsonSchema(Value),
+    Exact(String),
+}
+
+impl Assertion {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Contains(text) => format!("contains \"{}\"", text),
+            Self::Regex(pattern) => format!("matches /{}/", pattern),
+            Self::JsonSchema(_) => "valid against schema".to_string(),
+            Self::Exact(text) => format!("equals \"{}\"", text),
+        }
+    }
+
+    /// `Ok` when `answer` satisfies the assertion, otherwise why not.
+    pub fn check(&self, answer: &str) -> Result<(), String> {
+        match self {
+            Self::Contains(text) => {
+                if answer.to_lowercase().contains(&text.to_lowercase()) {
+                    Ok(())
+                } else {
+                    Err(format!("does not contain \"{}\"", text))
+                }
+            }
+            Self::Regex(pattern) => {
+                let re = Regex::new(pattern).map_err(|e| format!("invalid regex: {}", e))?;
+                if re.is_match(answer.trim()) {
+                    Ok(())
+                } else {
+                    Err(format!("does not match /{}/", pattern))
+                }
+            }
+            Self::Exact(text) => {
+                if answer.trim() == text.trim() {
+                    Ok(())
+                } else {
+                    Err(format!("expected \"{}\"", text))
+                }
+            }
+            Self::JsonSchema(schema) => {
+                let json = extract_json(answer).ok_or("no JSON found in the answer")?;
+                let value: Value =
+                    serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+                let errors = schema::validate(schema, &value);
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors.join("; "))
+                }
+            }
+        }
+    }
+}
+
+/// The JSON document in a model's answer: a fenced block if there is one, otherwise
+/// everything from the first `{` or `[` to the matching last `}` or `]`.
+pub fn extract_json(answer: &str) -> Option<&str> {
+    if let Some(start) = answer.find("```") {
+        let body = &answer[start + 3..];
+        let body = body.strip_prefix("json").unwrap_or(body);
+        if let Some(end) = body.find("```") {
+            return Some(body[..end].trim());
+        }
+    }
+    let start = answer.find(['{', '['])?;
+    let close = if answer[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = answer.rfind(close)?;
+    (end > start).then(|| &answer[start..=end])
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Case {
+    #[serde(default)]
+    pub name: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub system: Option<String>,
+    pub expect: Vec<Assertion>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suite {
+    pub name: String,
+    /// System prompt for every case that does not set its own.
+    #[serde(default)]
+    pub system: Option<String>,
+    /// Ollama options such as `temperature` or `seed`, shared by all cases.
+    #[serde(default)]
+    pub options: Option<Map<String, Value>>,
+    pub cases: Vec<Case>,
+}
+
+impl Suite {
+    /// Parses a suite and checks it up front, so a bad regex is reported before any
+    /// model runs rather than as a failure of every case.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut suite: Suite =
+            serde_json::from_str(text).map_err(|e| format!("Invalid suite: {}", e))?;
+        if suite.cases.is_empty() {
+            return Err("The suite has no cases".to_string());
+        }
+        for (i, case) in suite.cases.iter_mut().enumerate() {
+            if case.name.trim().is_empty() {
+                case.name = format!("case {}", i + 1);
+            }
+            if case.expect.is_empty() {
+                return Err(format!("{} has no assertions", case.name));
+            }
+            for assertion in &case.expect {
+                if let Assertion::Regex(pattern) = assertion {
+                    Regex::new(pattern).map_err(|e| {
+                        format!("{}: invalid regex /{}/: {}", case.name, pattern, e)
+                    })?;
+                }
+            }
+        }
+        Ok(suite)
+    }
+
+    fn request(&self, model: &str, case: &Case) -> ChatRequest {
+        let mut messages = Vec::new();
+        if let Some(system) = case.system.as_ref().or(self.system.as_ref()) {
+            messages.push(Message::system(system.clone()));
+        }
+        messages.push(Message::new("user", case.prompt.clone()));
+        ChatRequest {
+            model: model.to_string(),
+            messages,
+            stream: false,
+            options: self.options.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct CaseResult {
+    pub model: String,
+    pub case: String,
+    pub output: String,
+    pub latency_ms: f64,
+    /// Assertions that failed, with the reason.
+    pub failures: Vec<String>,
+    /// Set when the request itself failed; the case then counts as failed.
+    pub error: Option<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures.is_empty()
+    }
+}
+
+/// Scores one answer. Reasoning in `<think>` tags is not part of the answer.
+pub fn score(case: &Case, output: &str) -> Vec<String> {
+    let mut splitter = ThinkSplitter::default();
+    let (mut answer, _) = splitter.push(output);
+    answer.push_str(&splitter.finish().0);
+    case.expect
+        .iter()
+        .filter_map(|a| {
+            a.check(&answer)
+                .err()
+                .map(|e| format!("{}: {}", a.describe(), e))
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct ModelScore {
+    pub model: String,
+    pub passed: usize,
+    pub total: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl ModelScore {
+    pub fn pass_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.passed as f64 / self.total as f64
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Pass counts and latency per model, in the order `models` lists them. Failed
+/// requests are left out of the latency figures.
+pub fn scoreboard(models: &[String], results: &[CaseResult]) -> Vec<ModelScore> {
+    models
+        .iter()
+        .map(|model| {
+            let mine: Vec<&CaseResult> = results.iter().filter(|r| &r.model == model).collect();
+            let mut latencies: Vec<f64> = mine
+                .iter()
+                .filter(|r| r.error.is_none())
+                .map(|r| r.latency_ms)
+                .collect();
+            latencies.sort_by(f64::total_cmp);
+            let mean_ms = if latencies.is_empty() {
+                0.0
+            } else {
+                latencies.iter().sum::<f64>() / latencies.len() as f64
+            };
+            ModelScore {
+                model: model.clone(),
+                passed: mine.iter().filter(|r| r.passed()).count(),
+                total: mine.len(),
+                mean_ms,
+                p50_ms: percentile(&latencies, 50.0),
+                p95_ms: percentile(&latencies, 95.0),
+                max_ms: latencies.last().copied().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub suite: String,
+    pub created_at: String,
+    pub models: Vec<String>,
+    pub scores: Vec<ModelScore>,
+    pub results: Vec<CaseResult>,
+}
+
+impl Report {
+    pub fn new(
+        suite: &Suite,
+        models: Vec<String>,
+        results: Vec<CaseResult>,
+        created_at: String,
+    ) -> Self {
+        Self {
+            suite: suite.name.clone(),
+            created_at,
+            scores: scoreboard(&models, &results),
+            models,
+            results,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{}\n\n", self.suite, self.created_at);
+        out.push_str("| Model | Passed | Mean | p50 | p95 | Max |\n|---|---|---|---|---|---|\n");
+        for s in &self.scores {
+            out.push_str(&format!(
+                "| {} | {}/{} ({:.0}%) | {:.0} ms | {:.0} ms | {:.0} ms | {:.0} ms |\n",
+                s.model,
+                s.passed,
+                s.total,
+                s.pass_rate() * 100.0,
+                s.mean_ms,
+                s.p50_ms,
+                s.p95_ms,
+                s.max_ms
+            ));
+        }
+        let failed: Vec<&CaseResult> = self.results.iter().filter(|r| !r.passed()).collect();
+        if !failed.is_empty() {
+            out.push_str("\n## Failures\n\n");
+            for r in failed {
+                let reasons = r
+                    .error
+                    .iter()
+                    .chain(&r.failures)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                out.push_str(&format!(
+                    "- **{}** / {}: {}\n",
+                    r.model,
+                    r.case,
+                    reasons.join("; ")
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Wall-clock milliseconds, for timing requests.
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Runs every case against each model in turn, one request at a time so models are
+/// not competing for memory, and reports each result as it lands. Setting `stop`
+/// ends the run after the current request.
+pub async fn run(
+    client: &Client<impl Transport>,
+    suite: &Suite,
+    models: &[String],
+    stop: Rc<Cell<bool>>,
+    mut on_result: impl FnMut(CaseResult),
+) {
+    for model in models {
+        for case in &suite.cases {
+            if stop.get() {
+                return;
+            }
+            let started = now_ms();
+            let response = client.chat(&suite.request(model, case)).await;
+            let latency_ms = now_ms() - started;
+            let result = match response {
+                Ok(response) => CaseResult {
+                    model: model.clone(),
+                    case: case.name.clone(),
+                    failures: score(case, &response.message.content),
+                    output: response.message.content,
+                    latency_ms,
+                    error: None,
+                },
+                Err(e) => CaseResult {
+                    model: model.clone(),
+                    case: case.name.clone(),
+                    output: String::new(),
+                    latency_ms,
+                    failures: Vec::new(),
+                    error: Some(e),
+                },
+            };
+            on_result(result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, latency_ms: f64, passed: bool) -> CaseResult {
+        CaseResult {
+            model: model.to_string(),
+            case: "c".to_string(),
+            output: String::new(),
+            latency_ms,
+            failures: if passed {
+                vec![]
+            } else {
+                vec!["nope".to_string()]
+            },
+            error: None,
+        }
+    }
+
+    #[test]
+    fn the_example_suite_parses() {
+        let suite = Suite::parse(EXAMPLE_SUITE).unwrap();
+        assert_eq!(suite.cases.len(), 4);
+        assert!(matches!(suite.cases[3].expect[0], Assertion::JsonSchema(_)));
+    }
+
+    #[test]
+    fn suites_are_checked_before_running() {
+        assert!(Suite::parse(r#"{"name": "x", "cases": []}"#).is_err());
+        let bad_regex = r#"{"name": "x", "cases": [{"prompt": "p", "expect": [{"regex": "("}]}]}"#;
+        assert!(Suite::parse(bad_regex).err().unwrap().contains("case 1"));
+    }
+
+    #[test]
+    fn assertions_check_the_answer() {
+        assert!(Assertion::Contains("paris".into())
+            .check("It's Paris.")
+            .is_ok());
+        assert!(Assertion::Exact("42".into()).check(" 42\n").is_ok());
+        assert!(Assertion::Exact("42".into()).check("42.").is_err());
+        assert!(Assertion::Regex(r"^\d{4}$".into()).check("1969\n").is_ok());
+        let schema =
+            Assertion::JsonSchema(serde_json::json!({ "type": "object", "required": ["a"] }));
+        assert!(schema
+            .check("Here you go:\n```json\n{\"a\": 1}\n```")
+            .is_ok());
+        assert!(schema.check("{\"b\": 1}").unwrap_err().contains("\"a\""));
+        assert!(schema.check("no json").is_err());
+    }
+
+    #[test]
+    fn reasoning_is_not_scored() {
+        let case = Case {
+            name: "c".into(),
+            prompt: "p".into(),
+            system: None,
+            expect: vec![Assertion::Exact("4".into())],
+        };
+        assert!(score(&case, "<think>2+2 is not 5</think>4").is_empty());
+    }
+
+    #[test]
+    fn scoreboard_counts_passes_and_latency_percentiles() {
+        let models = vec!["a".to_string(), "b".to_string()];
+        let mut results: Vec<CaseResult> = (1..=20)
+            .map(|i| result("a", i as f64 * 10.0, i % 4 != 0))
+            .collect();
+        let mut failed = result("b", 5000.0, true);
+        failed.error = Some("API Error: 404".into());
+        results.push(failed);
+        results.push(result("b", 100.0, true));
+
+        let scores = scoreboard(&models, &results);
+        assert_eq!((scores[0].passed, scores[0].total), (15, 20));
+        assert_eq!(scores[0].p50_ms, 100.0);
+        assert_eq!(scores[0].p95_ms, 190.0);
+        assert_eq!(scores[0].max_ms, 200.0);
+        assert_eq!(scores[0].mean_ms, 105.0);
+        // The failed request counts against the pass rate but not the latency.
+        assert_eq!((scores[1].passed, scores[1].total), (1, 2));
+        assert_eq!(scores[1].max_ms, 100.0);
+    }
+
+    #[tokio::test]
+    async fn suites_run_headless_against_a_server() {
+        let addr = mock_ollama::spawn(mock_ollama::Script {
+            replies: vec!["It's Paris.".to_string(), "41".to_string()],
+            token_delay_ms: 0,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let client: Client<ollama_client::Reqwest> = Client::new(&format!("http://{}", addr));
+        let suite = Suite::parse(
+            r#"{ "name": "s", "cases": [
+                { "name": "capital", "prompt": "Capital of France?", "expect": [{ "contains": "paris" }] },
+                { "name": "sum", "prompt": "17 + 25?", "expect": [{ "exact": "42" }] }
+            ] }"#,
+        )
+        .unwrap();
+        let models = vec!["mock-llm:latest".to_string(), "missing:latest".to_string()];
+
+        let mut results = Vec::new();
+        run(&client, &suite, &models, Rc::default(), |r| results.push(r)).await;
+
+        let passed: Vec<bool> = results.iter().map(CaseResult::passed).collect();
+        assert_eq!(passed, vec![true, false, false, false]);
+        assert_eq!(results[1].output, "41");
+        assert!(results[2]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("missing:latest"));
+        assert!(results.iter().all(|r| r.latency_ms >= 0.0));
+    }
+}
@@ -0,0 +1,278 @@
+//! Validation against the commonly used subset of JSON Schema: `type`, `enum`,
+//! `const`, object `properties`/`required`/`additionalProperties`, array
+//! `items`/`minItems`/`maxItems`, string `minLength`/`maxLength`/`pattern`, numeric
+//! bounds and `anyOf`/`oneOf`/`allOf`. Other keywords, including `$ref`, are ignored.
+
+use regex::Regex;
+use serde_json::Value;
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn at(path: &str) -> &str {
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
+
+/// Checks `value` against `schema`, returning every violation with its JSON pointer.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(schema, value, "", &mut errors);
+    errors
+}
+
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts anything, `false` nothing.
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{}: no value is allowed here", at(path)));
+        }
+        return;
+    };
+
+    match schema.get("type") {
+        Some(Value::String(name)) if !type_matches(name, value) => {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                at(path),
+                name,
+                type_name(value)
+            ));
+            return;
+        }
+        Some(Value::Array(names))
+            if !names
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|n| type_matches(n, value)) =>
+        {
+            errors.push(format!(
+                "{}: {} is not an allowed type",
+                at(path),
+                type_name(value)
+            ));
+            return;
+        }
+        _ => {}
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!(
+                "{}: {} is not one of the allowed values",
+                at(path),
+                value
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{}: expected {}", at(path), expected));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(key) {
+                        errors.push(format!(
+                            "{}: missing required property \"{}\"",
+                            at(path),
+                            key
+                        ));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, item) in object {
+                let item_path = format!("{}/{}", path, key);
+                match properties.and_then(|p| p.get(key)) {
+                    Some(item_schema) => check(item_schema, item, &item_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unexpected property", item_path));
+                        }
+                        Some(extra) => check(extra, item, &item_path, errors),
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{}: expected at least {} items", at(path), min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    errors.push(format!("{}: expected at most {} items", at(path), max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{}/{}", path, i), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let len = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    errors.push(format!("{}: shorter than {} characters", at(path), min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    errors.push(format!("{}: longer than {} characters", at(path), max));
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(re) if !re.is_match(text) => {
+                        errors.push(format!("{}: does not match {}", at(path), pattern));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        errors.push(format!("{}: invalid pattern in schema: {}", at(path), e))
+                    }
+                }
+            }
+        }
+        Value::Number(number) => {
+            let n = number.as_f64().unwrap_or_default();
+            let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+            if bound("minimum").is_some_and(|min| n < min) {
+                errors.push(format!("{}: {} is below the minimum", at(path), n));
+            }
+            if bound("maximum").is_some_and(|max| n > max) {
+                errors.push(format!("{}: {} is above the maximum", at(path), n));
+            }
+            if bound("exclusiveMinimum").is_some_and(|min| n <= min) {
+                errors.push(format!(
+                    "{}: {} must be greater than the minimum",
+                    at(path),
+                    n
+                ));
+            }
+            if bound("exclusiveMaximum").is_some_and(|max| n >= max) {
+                errors.push(format!("{}: {} must be less than the maximum", at(path), n));
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        for sub in all {
+            check(sub, value, path, errors);
+        }
+    }
+    let matching = |key: &str| {
+        schema.get(key).and_then(Value::as_array).map(|subs| {
+            subs.iter()
+                .filter(|sub| validate(sub, value).is_empty())
+                .count()
+        })
+    };
+    if matching("anyOf") == Some(0) {
+        errors.push(format!("{}: matches none of anyOf", at(path)));
+    }
+    if let Some(count) = matching("oneOf") {
+        if count != 1 {
+            errors.push(format!(
+                "{}: matches {} of oneOf, expected exactly 1",
+                at(path),
+                count
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn person() -> Value {
+        json!({
+            "type": "object",
+            "required": ["name", "age"],
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "age": { "type": "integer", "minimum": 0 },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] }, "maxItems": 2 }
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_a_valid_document() {
+        assert!(validate(
+            &person(),
+            &json!({ "name": "Ada", "age": 36, "tags": ["a"] })
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let errors = validate(
+            &person(),
+            &json!({ "name": "", "age": 1.5, "tags": ["c"], "x": 1 }),
+        );
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("/name:")));
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("/age: expected integer")));
+        assert!(errors.iter().any(|e| e.starts_with("/tags/0:")));
+        assert!(errors.iter().any(|e| e.starts_with("/x: unexpected")));
+    }
+
+    #[test]
+    fn reports_missing_required_properties() {
+        let errors = validate(&person(), &json!({ "name": "Ada" }));
+        assert_eq!(
+            errors,
+            vec!["/: missing required property \"age\"".to_string()]
+        );
+    }
+
+    #[test]
+    fn supports_combinators_and_type_lists() {
+        let schema =
+            json!({ "anyOf": [{ "type": "string" }, { "type": "number", "maximum": 10 }] });
+        assert!(validate(&schema, &json!("x")).is_empty());
+        assert!(validate(&schema, &json!(5)).is_empty());
+        assert!(!validate(&schema, &json!(50)).is_empty());
+        let nullable = json!({ "type": ["string", "null"] });
+        assert!(validate(&nullable, &Value::Null).is_empty());
+        assert!(!validate(&nullable, &json!(1)).is_empty());
+    }
+}
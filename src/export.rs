@@ -0,0 +1,262 @@
+use std::rc::Rc;
+
+use pulldown_cmark::{Event, Options, Parser};
+use serde::Serialize;
+
+use crate::ollama::Message;
+use crate::store::Conversation;
+use crate::tauri;
+
+const STYLE: &str = r#"
+body { margin: 0; background: #f5f5f4; color: #1c1917; font: 15px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif; }
+main { max-width: 760px; margin: 0 auto; padding: 40px 20px; }
+header { border-bottom: 1px solid #d6d3d1; margin-bottom: 24px; padding-bottom: 16px; }
+header h1 { font-size: 26px; margin: 0 0 8px; }
+header ul { list-style: none; margin: 0; padding: 0; color: #57534e; font-size: 13px; }
+.message { background: #fff; border: 1px solid #e7e5e4; border-radius: 12px; margin: 0 0 16px; padding: 12px 18px; }
+.message.user { background: #eef2ff; border-color: #c7d2fe; }
+.role { color: #78716c; font-size: 11px; font-weight: 700; letter-spacing: .1em; text-transform: uppercase; }
+.stats { color: #a8a29e; font-size: 12px; }
+pre { background: #1c1917; color: #f5f5f4; border-radius: 8px; overflow-x: auto; padding: 12px; }
+code { font: 13px/1.5 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+:not(pre) > code { background: #f5f5f4; border-radius: 4px; padding: 1px 4px; }
+table { border-collapse: collapse; } th, td { border: 1px solid #d6d3d1; padding: 4px 8px; }
+blockquote { border-left: 3px solid #d6d3d1; color: #57534e; margin-left: 0; padding-left: 12px; }
+"#;
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders Markdown to HTML. Raw HTML in the source is shown as text rather than
+/// passed through, so a shared file can't run anything a model wrote.
+pub fn markdown_to_html(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(text, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        other => other,
+    });
+    let mut out = String::new();
+    pulldown_cmark::html::push_html(&mut out, events);
+    out
+}
+
+/// Error notices are local to the app and are left out of exports.
+fn exported_messages(conv: &Conversation) -> impl Iterator<Item = &Message> {
+    conv.messages
+        .iter()
+        .map(Rc::as_ref)
+        .filter(|m| m.role != "system")
+}
+
+fn stats_line(msg: &Message) -> Option<String> {
+    let stats = msg.stats?;
+    Some(match stats.tokens_per_second() {
+        Some(rate) => format!("{} tokens, {:.1} tokens/s", stats.eval_count, rate),
+        None => format!("{} tokens", stats.eval_count),
+    })
+}
+
+/// The document header: model, export date and generation totals.
+pub fn header_lines(conv: &Conversation, exported_at: &str) -> Vec<String> {
+    let messages: Vec<&Message> = exported_messages(conv).collect();
+    let stats: Vec<_> = messages.iter().filter_map(|m| m.stats).collect();
+    let tokens: u64 = stats.iter().map(|s| s.eval_count).sum();
+    let nanos: u64 = stats.iter().map(|s| s.eval_duration).sum();
+
+    let mut lines = vec![
+        format!("Model: {}", conv.model),
+        format!("Exported: {}", exported_at),
+        format!("Messages: {}", messages.len()),
+    ];
+    if tokens > 0 {
+        let mut line = format!("Generated: {} tokens", tokens);
+        if nanos > 0 {
+            line.push_str(&format!(
+                " at {:.1} tokens/s",
+                tokens as f64 / (nanos as f64 / 1e9)
+            ));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A single HTML file with inline CSS that opens anywhere without the app.
+pub fn conversation_html(conv: &Conversation, exported_at: &str) -> String {
+    let title = escape_html(&conv.display_title());
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<main>\n<header>\n<h1>{}</h1>\n<ul>\n",
+        title, STYLE, title
+    );
+    for line in header_lines(conv, exported_at) {
+        out.push_str(&format!("<li>{}</li>\n", escape_html(&line)));
+    }
+    out.push_str("</ul>\n</header>\n");
+
+    for msg in exported_messages(conv) {
+        out.push_str(&format!(
+            "<section class=\"message {}\">\n<div class=\"role\">{}</div>\n{}",
+            escape_html(&msg.role),
+            escape_html(&msg.role),
+            markdown_to_html(&msg.content)
+        ));
+        if let Some(stats) = stats_line(msg) {
+            out.push_str(&format!(
+                "<div class=\"stats\">{}</div>\n",
+                escape_html(&stats)
+            ));
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</main>\n</body>\n</html>\n");
+    out
+}
+
+/// A file name derived from the conversation title, e.g. `rust-lifetimes.html`.
+pub fn export_filename(conv: &Conversation, extension: &str) -> String {
+    let slug: String = conv
+        .display_title()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!(
+        "{}.{}",
+        if slug.is_empty() {
+            "conversation"
+        } else {
+            &slug
+        },
+        extension
+    )
+}
+
+#[derive(Serialize)]
+struct PdfMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+    stats: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PdfDocument<'a> {
+    title: String,
+    header: Vec<String>,
+    messages: Vec<PdfMessage<'a>>,
+}
+
+#[derive(Serialize)]
+struct PdfArgs<'a> {
+    document: PdfDocument<'a>,
+}
+
+/// Renders the conversation to PDF in the backend and returns the file's bytes.
+pub async fn conversation_pdf(conv: &Conversation, exported_at: &str) -> Result<Vec<u8>, String> {
+    let document = PdfDocument {
+        title: conv.display_title(),
+        header: header_lines(conv, exported_at),
+        messages: exported_messages(conv)
+            .map(|m| PdfMessage {
+                role: &m.role,
+                content: &m.content,
+                stats: stats_line(m),
+            })
+            .collect(),
+    };
+    tauri::invoke("export_pdf", &PdfArgs { document }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ollama::GenerationStats;
+
+    fn conversation() -> Conversation {
+        let mut reply = Message::new(
+            "assistant",
+            "Use `iter().rev()`:\n\n```rust\nfor x in v.iter().rev() {}\n```",
+        );
+        reply.stats = Some(GenerationStats {
+            eval_count: 50,
+            eval_duration: 2_000_000_000,
+        });
+        Conversation {
+            title: "Reverse <a> Vec".to_string(),
+            model: "llama3.2".to_string(),
+            messages: Rc::new(vec![
+                Rc::new(Message::new("user", "How do I reverse a **Vec**?")),
+                Rc::new(reply),
+                Rc::new(Message::system("API Error: 500")),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn markdown_keeps_formatting_but_escapes_raw_html() {
+        let html =
+            markdown_to_html("**bold** <script>alert(1)</script>\n\n```\nlet a = 1 < 2;\n```");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<pre><code>let a = 1 &lt; 2;"));
+    }
+
+    #[test]
+    fn header_totals_generation_stats() {
+        let lines = header_lines(&conversation(), "2024-05-01");
+        assert_eq!(
+            lines,
+            vec![
+                "Model: llama3.2",
+                "Exported: 2024-05-01",
+                "Messages: 2",
+                "Generated: 50 tokens at 25.0 tokens/s",
+            ]
+        );
+    }
+
+    #[test]
+    fn html_document_is_self_contained() {
+        let html = conversation_html(&conversation(), "2024-05-01");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+        assert!(html.contains("<h1>Reverse &lt;a&gt; Vec</h1>"));
+        assert!(html.contains("<code class=\"language-rust\">"));
+        assert!(html.contains("50 tokens, 25.0 tokens/s"));
+        assert!(!html.contains("API Error"));
+    }
+
+    #[test]
+    fn filenames_are_slugged_titles() {
+        assert_eq!(export_filename(&conversation(), "pdf"), "reverse-a-vec.pdf");
+        assert_eq!(
+            export_filename(&Conversation::default(), "html"),
+            "new-chat.html"
+        );
+    }
+}
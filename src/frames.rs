@@ -0,0 +1,104 @@
+use serde::Serialize;
+
+use crate::tauri;
+
+/// The most frames one range capture takes.
+pub const MAX_FRAMES: usize = 500;
+
+/// The moments to capture from `start` to `end` seconds, one every `every` seconds,
+/// including `start` and `end` when it falls on a step. At most `MAX_FRAMES` are
+/// returned; the flag says whether the end of the range was cut off to keep to that.
+pub fn capture_times(start: f64, end: f64, every: f64) -> (Vec<f64>, bool) {
+    if !(start.is_finite() && end.is_finite() && every > 0.0) || end < start {
+        return (Vec::new(), false);
+    }
+    // A little slack so a step landing on `end` isn't lost to rounding.
+    let steps = ((end - start) / every + 1e-9).floor();
+    let cut = steps >= MAX_FRAMES as f64;
+    let times = (0..=(steps as usize).min(MAX_FRAMES - 1))
+        .map(|step| start + step as f64 * every)
+        .collect();
+    (times, cut)
+}
+
+/// Seconds from `83.5`, `1:23.5` or `1:01:23.5`.
+pub fn parse_time(text: &str) -> Option<f64> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let (seconds, whole) = parts.split_last()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    let mut total = 0.0;
+    for part in whole {
+        total = total * 60.0 + part.parse::<u32>().ok()? as f64;
+    }
+    let minutes_given = !whole.is_empty();
+    let valid = seconds.is_finite() && seconds >= 0.0 && (!minutes_given || seconds < 60.0);
+    valid.then_some(total * 60.0 + seconds)
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+#[derive(Serialize)]
+struct SaveArgs<'a> {
+    name: &'a str,
+    time: f64,
+    png: &'a str,
+}
+
+/// The folder frames are saved in, if one was chosen this session.
+pub async fn folder() -> Result<Option<String>, String> {
+    tauri::invoke("frames_folder", &NoArgs {}).await
+}
+
+/// Asks for a folder to save frames in. Returns the folder now in use.
+pub async fn choose_folder() -> Result<Option<String>, String> {
+    tauri::invoke("frames_choose_folder", &NoArgs {}).await
+}
+
+/// Saves a frame of the video called `name`, captured `time` seconds in, from base64
+/// PNG data. Returns where it was saved.
+pub async fn save(name: &str, time: f64, png: &str) -> Result<String, String> {
+    tauri::invoke("frames_save", &SaveArgs { name, time, png }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_are_captured_at_each_step() {
+        let times = |start, end, every| capture_times(start, end, every).0;
+        assert_eq!(times(10.0, 12.0, 0.5), vec![10.0, 10.5, 11.0, 11.5, 12.0]);
+        assert_eq!(times(0.0, 0.3, 0.1).len(), 4);
+        assert_eq!(times(0.0, 2.9, 1.0), vec![0.0, 1.0, 2.0]);
+        assert_eq!(times(5.0, 5.0, 1.0), vec![5.0]);
+        assert!(times(5.0, 4.0, 1.0).is_empty());
+        assert!(times(0.0, 10.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn long_ranges_are_cut_to_the_limit() {
+        let (times, cut) = capture_times(0.0, 1e6, 1.0);
+        assert_eq!(times.len(), MAX_FRAMES);
+        assert!(cut);
+        // Exactly the limit fits.
+        let (times, cut) = capture_times(0.0, (MAX_FRAMES - 1) as f64, 1.0);
+        assert_eq!(times.len(), MAX_FRAMES);
+        assert!(!cut);
+        assert!(!capture_times(0.0, 10.0, 1.0).1);
+    }
+
+    #[test]
+    fn times_are_read_in_seconds_or_clock_form() {
+        assert_eq!(parse_time("83.5"), Some(83.5));
+        assert_eq!(parse_time(" 1:23.5 "), Some(83.5));
+        assert_eq!(parse_time("1:01:23"), Some(3683.0));
+        assert_eq!(parse_time("1:75"), None);
+        assert_eq!(parse_time("-3"), None);
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time("1:2:3:4"), None);
+    }
+}
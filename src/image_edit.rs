@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tauri;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// The rect moved and shrunk as needed to fit in an image of `size`, keeping at
+    /// least one pixel.
+    pub fn clamped(self, size: Dimensions) -> Self {
+        let x = self.x.min(size.width.saturating_sub(1));
+        let y = self.y.min(size.height.saturating_sub(1));
+        Self {
+            x,
+            y,
+            width: self.width.clamp(1, (size.width - x).max(1)),
+            height: self.height.clamp(1, (size.height - y).max(1)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Dimensions {
+    /// The size with the given width and this aspect ratio.
+    pub fn with_width(self, width: u32) -> Self {
+        let height = (width as f64 * self.height as f64 / self.width.max(1) as f64).round() as u32;
+        Self {
+            width,
+            height: height.max(1),
+        }
+    }
+
+    /// The size with the given height and this aspect ratio.
+    pub fn with_height(self, height: u32) -> Self {
+        let width = (height as f64 * self.width as f64 / self.height.max(1) as f64).round() as u32;
+        Self {
+            width: width.max(1),
+            height,
+        }
+    }
+
+    fn swapped(self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+        }
+    }
+}
+
+/// What the backend does to the image, in order: crop, turn, flip, resize.
+#[derive(Clone, Default, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Edits {
+    /// In the original's pixels.
+    pub crop: Option<Rect>,
+    /// Clockwise.
+    pub quarter_turns: u8,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub resize: Option<Dimensions>,
+}
+
+impl Edits {
+    /// The size after cropping and turning, before any resize.
+    pub fn turned_size(&self, original: Dimensions) -> Dimensions {
+        let cropped = self.crop.map_or(original, |crop| Dimensions {
+            width: crop.width,
+            height: crop.height,
+        });
+        if self.quarter_turns % 2 == 1 {
+            cropped.swapped()
+        } else {
+            cropped
+        }
+    }
+
+    pub fn output_size(&self, original: Dimensions) -> Dimensions {
+        self.resize.unwrap_or_else(|| self.turned_size(original))
+    }
+
+    /// Turned a quarter further. A chosen size turns with the image.
+    pub fn rotated(&self, clockwise: bool) -> Self {
+        Self {
+            quarter_turns: (self.quarter_turns + if clockwise { 1 } else { 3 }) % 4,
+            resize: self.resize.map(Dimensions::swapped),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum Export {
+    Png,
+    /// `quality` runs from 1 to 100.
+    Jpeg {
+        quality: u8,
+    },
+    /// Lossless.
+    Webp,
+}
+
+/// The edited image as written for the viewer to show.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Preview {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// Bytes, as the saved file would be.
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+struct EditArgs<'a> {
+    source: &'a str,
+    edits: &'a Edits,
+    export: Export,
+}
+
+/// Renders `edits` to a temporary file; the original is left as it is.
+pub async fn preview(source: &str, edits: &Edits, export: Export) -> Result<Preview, String> {
+    tauri::invoke(
+        "image_edit_preview",
+        &EditArgs {
+            source,
+            edits,
+            export,
+        },
+    )
+    .await
+}
+
+/// Asks where to save the edited image and writes it there. Returns the saved path, or
+/// `None` if the user cancelled.
+pub async fn save(source: &str, edits: &Edits, export: Export) -> Result<Option<String>, String> {
+    tauri::invoke(
+        "image_edit_save",
+        &EditArgs {
+            source,
+            edits,
+            export,
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHOTO: Dimensions = Dimensions {
+        width: 400,
+        height: 300,
+    };
+
+    #[test]
+    fn crops_are_kept_inside_the_image() {
+        let crop = Rect {
+            x: 350,
+            y: 500,
+            width: 100,
+            height: 0,
+        };
+        assert_eq!(
+            crop.clamped(PHOTO),
+            Rect {
+                x: 350,
+                y: 299,
+                width: 50,
+                height: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn sizes_follow_crops_and_turns() {
+        let edits = Edits {
+            crop: Some(Rect {
+                x: 0,
+                y: 0,
+                width: 200,
+                height: 100,
+            }),
+            ..Edits::default()
+        };
+        assert_eq!(
+            edits.output_size(PHOTO),
+            Dimensions {
+                width: 200,
+                height: 100
+            }
+        );
+
+        let resized = Edits {
+            resize: Some(Dimensions {
+                width: 40,
+                height: 20,
+            }),
+            ..edits
+        };
+        let turned = resized.rotated(true);
+        assert_eq!(turned.quarter_turns, 1);
+        assert_eq!(
+            turned.turned_size(PHOTO),
+            Dimensions {
+                width: 100,
+                height: 200
+            }
+        );
+        assert_eq!(
+            turned.output_size(PHOTO),
+            Dimensions {
+                width: 20,
+                height: 40
+            }
+        );
+        assert_eq!(turned.rotated(false).rotated(false).quarter_turns, 3);
+    }
+
+    #[test]
+    fn aspect_ratios_are_kept_when_resizing() {
+        assert_eq!(
+            PHOTO.with_width(200),
+            Dimensions {
+                width: 200,
+                height: 150
+            }
+        );
+        assert_eq!(
+            PHOTO.with_height(100),
+            Dimensions {
+                width: 133,
+                height: 100
+            }
+        );
+        assert_eq!(
+            PHOTO.with_width(1),
+            Dimensions {
+                width: 1,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn exports_serialize_as_the_backend_expects() {
+        let json = serde_json::to_string(&Export::Jpeg { quality: 85 }).unwrap();
+        assert_eq!(json, r#"{"format":"jpeg","quality":85}"#);
+        assert_eq!(
+            serde_json::to_string(&Export::Webp).unwrap(),
+            r#"{"format":"webp"}"#
+        );
+    }
+}
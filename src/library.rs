@@ -0,0 +1,257 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::media::{self, MediaKind};
+use crate::tauri;
+
+/// A file in the media library, as indexed by the backend.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    pub path: String,
+    pub kind: MediaKind,
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub modified: u64,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub duration: Option<f64>,
+    /// Path of a small JPEG of an image.
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+impl LibraryEntry {
+    pub fn name(&self) -> &str {
+        media::file_name(&self.path)
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Library {
+    pub folders: Vec<String>,
+    pub entries: Vec<LibraryEntry>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SortKey {
+    Name,
+    Modified,
+    Size,
+    Duration,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 4] = [Self::Name, Self::Modified, Self::Size, Self::Duration];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Modified => "Date modified",
+            Self::Size => "Size",
+            Self::Duration => "Duration",
+        }
+    }
+}
+
+/// What the library page shows, and in what order.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LibraryView {
+    pub sort: SortKey,
+    pub descending: bool,
+    /// `None` shows images and videos.
+    pub kind: Option<MediaKind>,
+    /// Matched against file names, ignoring case.
+    pub search: String,
+}
+
+impl Default for LibraryView {
+    fn default() -> Self {
+        Self {
+            sort: SortKey::Modified,
+            descending: true,
+            kind: None,
+            search: String::new(),
+        }
+    }
+}
+
+impl LibraryView {
+    /// The entries this view shows, in order. Entries with no duration sort after
+    /// those with one, whichever way the list runs.
+    pub fn apply<'a>(&self, entries: &'a [LibraryEntry]) -> Vec<&'a LibraryEntry> {
+        let search = self.search.trim().to_lowercase();
+        let mut shown: Vec<&LibraryEntry> = entries
+            .iter()
+            .filter(|e| self.kind.is_none_or(|kind| e.kind == kind))
+            .filter(|e| search.is_empty() || e.name().to_lowercase().contains(&search))
+            .collect();
+        shown.sort_by(|a, b| {
+            let order = match self.sort {
+                SortKey::Name => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
+                SortKey::Modified => a.modified.cmp(&b.modified),
+                SortKey::Size => a.size.cmp(&b.size),
+                SortKey::Duration => match (a.duration, b.duration) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (None, None) => Ordering::Equal,
+                    (a, b) => return b.is_some().cmp(&a.is_some()),
+                },
+            };
+            let order = if self.descending {
+                order.reverse()
+            } else {
+                order
+            };
+            order.then_with(|| a.path.cmp(&b.path))
+        });
+        shown
+    }
+}
+
+/// A byte count in the largest unit that keeps it at least 1, e.g. `"2.4 MB"`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64;
+    let mut unit = "";
+    for next in UNITS {
+        if size < 1000.0 {
+            break;
+        }
+        size /= 1000.0;
+        unit = next;
+    }
+    format!("{:.1} {}", size, unit)
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+#[derive(Serialize)]
+struct FolderArgs<'a> {
+    folder: &'a str,
+}
+
+/// The library as last indexed.
+pub async fn get() -> Result<Library, String> {
+    tauri::invoke("library_get", &NoArgs {}).await
+}
+
+/// Asks for a folder to add and indexes it.
+pub async fn add_folder() -> Result<Library, String> {
+    tauri::invoke("library_add_folder", &NoArgs {}).await
+}
+
+pub async fn remove_folder(folder: &str) -> Result<Library, String> {
+    tauri::invoke("library_remove_folder", &FolderArgs { folder }).await
+}
+
+/// Indexes every folder again, picking up files added, changed or deleted.
+pub async fn refresh() -> Result<Library, String> {
+    tauri::invoke("library_refresh", &NoArgs {}).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        path: &str,
+        kind: MediaKind,
+        size: u64,
+        modified: u64,
+        duration: Option<f64>,
+    ) -> LibraryEntry {
+        LibraryEntry {
+            path: path.to_string(),
+            kind,
+            size,
+            modified,
+            width: None,
+            height: None,
+            duration,
+            thumbnail: None,
+        }
+    }
+
+    fn entries() -> Vec<LibraryEntry> {
+        vec![
+            entry("/shots/b.png", MediaKind::Image, 300, 20, None),
+            entry("/rec/Demo.webm", MediaKind::Video, 9000, 10, Some(61.0)),
+            entry("/rec/a.mp4", MediaKind::Video, 5000, 30, Some(5.5)),
+            entry("/rec/c.mkv", MediaKind::Video, 100, 40, None),
+        ]
+    }
+
+    fn names(shown: Vec<&LibraryEntry>) -> Vec<&str> {
+        shown.into_iter().map(|e| e.name()).collect()
+    }
+
+    #[test]
+    fn views_sort_either_way() {
+        let entries = entries();
+        let mut view = LibraryView::default();
+        assert_eq!(
+            names(view.apply(&entries)),
+            vec!["c.mkv", "a.mp4", "b.png", "Demo.webm"]
+        );
+
+        view.sort = SortKey::Name;
+        view.descending = false;
+        assert_eq!(
+            names(view.apply(&entries)),
+            vec!["a.mp4", "b.png", "c.mkv", "Demo.webm"]
+        );
+
+        view.sort = SortKey::Size;
+        assert_eq!(
+            names(view.apply(&entries)),
+            vec!["c.mkv", "b.png", "a.mp4", "Demo.webm"]
+        );
+
+        view.sort = SortKey::Duration;
+        assert_eq!(
+            names(view.apply(&entries)),
+            vec!["a.mp4", "Demo.webm", "c.mkv", "b.png"]
+        );
+        view.descending = true;
+        assert_eq!(
+            names(view.apply(&entries)),
+            vec!["Demo.webm", "a.mp4", "c.mkv", "b.png"]
+        );
+    }
+
+    #[test]
+    fn views_filter_by_kind_and_name() {
+        let entries = entries();
+        let view = LibraryView {
+            sort: SortKey::Name,
+            descending: false,
+            kind: Some(MediaKind::Video),
+            search: " DEM ".to_string(),
+        };
+        assert_eq!(names(view.apply(&entries)), vec!["Demo.webm"]);
+
+        let view = LibraryView {
+            kind: Some(MediaKind::Image),
+            search: String::new(),
+            ..view
+        };
+        assert_eq!(names(view.apply(&entries)), vec!["b.png"]);
+    }
+
+    #[test]
+    fn sizes_use_the_largest_fitting_unit() {
+        assert_eq!(format_size(999), "999 B");
+        assert_eq!(format_size(1000), "1.0 KB");
+        assert_eq!(format_size(2_400_000), "2.4 MB");
+        assert_eq!(format_size(3_000_000_000_000_000), "3000.0 TB");
+    }
+}
@@ -1,6 +1,28 @@
 mod app;
+mod attachments;
+mod batch;
 mod components;
+mod download;
+mod embeddings;
+mod eval;
+mod export;
+mod frames;
+mod image_edit;
+mod library;
+mod media;
+mod memory;
+mod metadata;
+mod modelfile;
+mod ollama;
 mod routes;
+mod store;
+mod subtitles;
+mod tauri;
+mod thinking;
+mod titles;
+mod viewer;
+mod virtual_list;
+mod workspace;
 
 use app::App;
 
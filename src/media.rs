@@ -0,0 +1,502 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::tauri;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg", "avif"];
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "webm", "mov", "mkv", "ogv"];
+
+thread_local! {
+    /// Files another page asked the player to open, picked up when it next mounts.
+    static PENDING: RefCell<Vec<PlaylistEntry>> = RefCell::default();
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+impl MediaKind {
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        if mime.starts_with("image/") {
+            Some(Self::Image)
+        } else if mime.starts_with("video/") {
+            Some(Self::Video)
+        } else {
+            None
+        }
+    }
+
+    /// Guesses the kind of a file on disk from its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let name = file_name(path);
+        let (_, extension) = name.rsplit_once('.')?;
+        let extension = extension.to_ascii_lowercase();
+        if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            Some(Self::Image)
+        } else if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+            Some(Self::Video)
+        } else {
+            None
+        }
+    }
+}
+
+/// The last component of a Windows or Unix path.
+pub fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// One file opened in the media player.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MediaItem {
+    /// Assigned by the queue; stays the same while the item is moved around.
+    pub id: u32,
+    pub name: String,
+    pub url: String,
+    pub kind: MediaKind,
+    /// Where the file is on disk. `None` for files read through the browser, whose
+    /// object URL must be revoked once the item is closed.
+    pub path: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Repeat {
+    #[default]
+    Off,
+    All,
+    One,
+}
+
+impl Repeat {
+    fn next(self) -> Self {
+        match self {
+            Self::Off => Self::All,
+            Self::All => Self::One,
+            Self::One => Self::Off,
+        }
+    }
+}
+
+/// The files open in the media player and which one is shown.
+#[derive(Clone, PartialEq, Default)]
+pub struct MediaQueue {
+    pub items: Vec<MediaItem>,
+    pub current: usize,
+    pub repeat: Repeat,
+    /// Play order while shuffling, as item ids. The queue itself keeps its order.
+    pub shuffled: Option<Vec<u32>>,
+    next_id: u32,
+}
+
+impl MediaQueue {
+    pub fn current_item(&self) -> Option<&MediaItem> {
+        self.items.get(self.current)
+    }
+
+    fn index_of(&self, id: u32) -> Option<usize> {
+        self.items.iter().position(|item| item.id == id)
+    }
+
+    /// The item `forward` or back from the current one in play order, wrapping around
+    /// the ends when `wrap` is set.
+    fn step(&self, forward: bool, wrap: bool) -> Option<usize> {
+        let current = self.current_item()?.id;
+        let order: Vec<u32> = match &self.shuffled {
+            Some(order) => order.clone(),
+            None => self.items.iter().map(|item| item.id).collect(),
+        };
+        let position = order.iter().position(|&id| id == current)?;
+        let len = order.len();
+        let next = match (forward, wrap) {
+            (true, _) if position + 1 < len => position + 1,
+            (false, _) if position > 0 => position - 1,
+            (true, true) => 0,
+            (false, true) => len - 1,
+            _ => return None,
+        };
+        self.index_of(order[next])
+    }
+}
+
+/// A shuffled copy of `ids`, seeded so the reducer stays deterministic.
+fn shuffle(ids: &mut [u32], seed: u64) {
+    // xorshift64; any non-zero state works.
+    let mut state = seed | 1;
+    for i in (1..ids.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        ids.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+pub enum MediaAction {
+    /// Adds files to the queue and shows the first of them.
+    Open(Vec<MediaItem>),
+    Select(usize),
+    Close(usize),
+    /// Moves an item to another place in the queue, as when dragged.
+    Move {
+        from: usize,
+        to: usize,
+    },
+    Next,
+    Previous,
+    /// The current video finished playing. With repeat one the player restarts it
+    /// itself, so this only advances.
+    Ended,
+    /// Turns shuffle on, with a play order drawn from `seed`, or off.
+    ToggleShuffle(u64),
+    CycleRepeat,
+}
+
+impl Reducible for MediaQueue {
+    type Action = MediaAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut queue = (*self).clone();
+        match action {
+            MediaAction::Open(items) => {
+                if !items.is_empty() {
+                    queue.current = queue.items.len();
+                    for mut item in items {
+                        item.id = queue.next_id;
+                        queue.next_id += 1;
+                        if let Some(order) = queue.shuffled.as_mut() {
+                            order.push(item.id);
+                        }
+                        queue.items.push(item);
+                    }
+                }
+            }
+            MediaAction::Select(index) => {
+                if index < queue.items.len() {
+                    queue.current = index;
+                }
+            }
+            MediaAction::Close(index) => {
+                if index < queue.items.len() {
+                    let closed = queue.items.remove(index);
+                    if let Some(order) = queue.shuffled.as_mut() {
+                        order.retain(|&id| id != closed.id);
+                    }
+                    if index < queue.current || queue.current == queue.items.len() {
+                        queue.current = queue.current.saturating_sub(1);
+                    }
+                }
+            }
+            MediaAction::Move { from, to } => {
+                if from < queue.items.len() && to < queue.items.len() {
+                    let current = queue.current_item().map(|item| item.id);
+                    let item = queue.items.remove(from);
+                    queue.items.insert(to, item);
+                    if let Some(index) = current.and_then(|id| queue.index_of(id)) {
+                        queue.current = index;
+                    }
+                }
+            }
+            MediaAction::Next | MediaAction::Previous => {
+                let forward = matches!(action, MediaAction::Next);
+                if let Some(index) = queue.step(forward, queue.repeat != Repeat::Off) {
+                    queue.current = index;
+                }
+            }
+            MediaAction::Ended => {
+                if queue.repeat != Repeat::One {
+                    if let Some(index) = queue.step(true, queue.repeat == Repeat::All) {
+                        queue.current = index;
+                    }
+                }
+            }
+            MediaAction::ToggleShuffle(seed) => {
+                queue.shuffled = match queue.shuffled {
+                    Some(_) => None,
+                    None => {
+                        // The current item leads, so shuffling doesn't skip away from it.
+                        let current = queue.current_item().map(|item| item.id);
+                        let mut rest: Vec<u32> = queue
+                            .items
+                            .iter()
+                            .map(|item| item.id)
+                            .filter(|&id| Some(id) != current)
+                            .collect();
+                        shuffle(&mut rest, seed);
+                        Some(current.into_iter().chain(rest).collect())
+                    }
+                };
+            }
+            MediaAction::CycleRepeat => queue.repeat = queue.repeat.next(),
+        }
+        Rc::new(queue)
+    }
+}
+
+/// A file in a playlist saved or opened through the backend.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    pub path: String,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// Has the player open `entries` when it next mounts, e.g. after navigating to it.
+pub fn open_in_player(entries: Vec<PlaylistEntry>) {
+    PENDING.with(|pending| *pending.borrow_mut() = entries);
+}
+
+/// The files waiting to be opened by [`open_in_player`], which are then forgotten.
+pub fn take_pending() -> Vec<PlaylistEntry> {
+    PENDING.with(|pending| pending.take())
+}
+
+#[derive(Serialize)]
+struct SaveArgs<'a> {
+    entries: &'a [PlaylistEntry],
+    format: &'a str,
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+/// Saves `entries` as an `"m3u"` or `"json"` playlist wherever the user picks. Returns
+/// the saved path, or `None` if they cancelled.
+pub async fn save_playlist(
+    entries: &[PlaylistEntry],
+    format: &str,
+) -> Result<Option<String>, String> {
+    tauri::invoke("playlist_save", &SaveArgs { entries, format }).await
+}
+
+/// Asks for a playlist and returns the entries that can still be opened.
+pub async fn open_playlist() -> Result<Vec<PlaylistEntry>, String> {
+    tauri::invoke("playlist_open", &NoArgs {}).await
+}
+
+/// A file opened before, newest first.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFile {
+    pub path: String,
+    /// Where playback of a video stopped, in seconds.
+    #[serde(default)]
+    pub position: Option<f64>,
+    pub opened_at: u64,
+}
+
+#[derive(Serialize)]
+struct PathsArgs<'a> {
+    paths: &'a [String],
+}
+
+#[derive(Serialize)]
+struct PositionArgs<'a> {
+    path: &'a str,
+    position: Option<f64>,
+}
+
+/// Asks for images and videos through the native dialog. Returns their paths, or
+/// nothing if the user cancelled.
+pub async fn open_dialog() -> Result<Vec<String>, String> {
+    tauri::invoke("media_open_dialog", &NoArgs {}).await
+}
+
+/// The recent files that still exist.
+pub async fn recent_files() -> Result<Vec<RecentFile>, String> {
+    tauri::invoke("media_recent_list", &NoArgs {}).await
+}
+
+/// Moves `paths` to the top of the recent list, returning the updated list.
+pub async fn add_recent(paths: &[String]) -> Result<Vec<RecentFile>, String> {
+    tauri::invoke("media_recent_add", &PathsArgs { paths }).await
+}
+
+/// Remembers where playback of `path` stopped; `None` once it played to the end.
+pub async fn remember_position(
+    path: &str,
+    position: Option<f64>,
+) -> Result<Vec<RecentFile>, String> {
+    tauri::invoke("media_recent_position", &PositionArgs { path, position }).await
+}
+
+pub async fn clear_recent() -> Result<Vec<RecentFile>, String> {
+    tauri::invoke("media_recent_clear", &NoArgs {}).await
+}
+
+/// `m:ss`, or `h:mm:ss` past an hour, for showing playback positions.
+pub fn format_position(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str) -> MediaItem {
+        MediaItem {
+            id: 0,
+            name: name.to_string(),
+            url: format!("blob:{}", name),
+            kind: MediaKind::Image,
+            path: None,
+        }
+    }
+
+    fn queue_of(names: &[&str]) -> Rc<MediaQueue> {
+        Rc::new(MediaQueue::default())
+            .reduce(MediaAction::Open(names.iter().map(|n| item(n)).collect()))
+    }
+
+    fn names(queue: &MediaQueue) -> Vec<&str> {
+        queue.items.iter().map(|i| i.name.as_str()).collect()
+    }
+
+    fn current(queue: &MediaQueue) -> &str {
+        &queue.current_item().unwrap().name
+    }
+
+    #[test]
+    fn kinds_come_from_mime_types_and_extensions() {
+        assert_eq!(MediaKind::from_mime("image/png"), Some(MediaKind::Image));
+        assert_eq!(MediaKind::from_mime("video/webm"), Some(MediaKind::Video));
+        assert_eq!(MediaKind::from_mime("audio/mpeg"), None);
+
+        assert_eq!(
+            MediaKind::from_path("/home/me/Shot.PNG"),
+            Some(MediaKind::Image)
+        );
+        assert_eq!(
+            MediaKind::from_path(r"C:\Videos\demo.mkv"),
+            Some(MediaKind::Video)
+        );
+        assert_eq!(MediaKind::from_path("/home/me/notes.txt"), None);
+        assert_eq!(MediaKind::from_path("/home/me.d/README"), None);
+    }
+
+    #[test]
+    fn file_names_handle_both_separators() {
+        assert_eq!(file_name("/home/me/a.png"), "a.png");
+        assert_eq!(file_name(r"C:\Users\me\b.mp4"), "b.mp4");
+        assert_eq!(file_name("c.webm"), "c.webm");
+    }
+
+    #[test]
+    fn opening_shows_the_first_new_file_and_closing_keeps_the_view() {
+        let queue = queue_of(&["a", "b"]).reduce(MediaAction::Open(vec![item("c"), item("d")]));
+        assert_eq!(names(&queue), vec!["a", "b", "c", "d"]);
+        assert_eq!(current(&queue), "c");
+
+        let queue = queue.reduce(MediaAction::Close(0));
+        assert_eq!(current(&queue), "c");
+        let queue = queue
+            .reduce(MediaAction::Select(2))
+            .reduce(MediaAction::Close(2));
+        assert_eq!(current(&queue), "c");
+        let queue = queue
+            .reduce(MediaAction::Close(1))
+            .reduce(MediaAction::Close(0));
+        assert!(queue.current_item().is_none());
+        assert_eq!(queue.reduce(MediaAction::Open(Vec::new())).current, 0);
+    }
+
+    #[test]
+    fn next_and_previous_wrap_only_when_repeating() {
+        let queue = queue_of(&["a", "b", "c"]);
+        assert_eq!(current(&queue.clone().reduce(MediaAction::Previous)), "a");
+        let at_end = queue.reduce(MediaAction::Select(2));
+        assert_eq!(current(&at_end.clone().reduce(MediaAction::Next)), "c");
+        assert_eq!(current(&at_end.clone().reduce(MediaAction::Ended)), "c");
+
+        let repeat_all = at_end.reduce(MediaAction::CycleRepeat);
+        assert_eq!(repeat_all.repeat, Repeat::All);
+        assert_eq!(current(&repeat_all.clone().reduce(MediaAction::Next)), "a");
+        assert_eq!(current(&repeat_all.clone().reduce(MediaAction::Ended)), "a");
+
+        let repeat_one = repeat_all
+            .reduce(MediaAction::CycleRepeat)
+            .reduce(MediaAction::Select(1));
+        assert_eq!(current(&repeat_one.clone().reduce(MediaAction::Ended)), "b");
+        assert_eq!(current(&repeat_one.clone().reduce(MediaAction::Next)), "c");
+        assert_eq!(
+            repeat_one.reduce(MediaAction::CycleRepeat).repeat,
+            Repeat::Off
+        );
+    }
+
+    #[test]
+    fn shuffle_plays_every_item_once_starting_from_the_current_one() {
+        let queue = queue_of(&["a", "b", "c", "d", "e"])
+            .reduce(MediaAction::Select(2))
+            .reduce(MediaAction::ToggleShuffle(42));
+        let order = queue.shuffled.clone().unwrap();
+        assert_eq!(order[0], queue.items[2].id);
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+
+        let mut played = vec![current(&queue).to_string()];
+        let mut queue = queue;
+        for _ in 0..4 {
+            queue = queue.reduce(MediaAction::Next);
+            played.push(current(&queue).to_string());
+        }
+        assert_eq!(
+            queue.clone().reduce(MediaAction::Ended).current,
+            queue.current
+        );
+        played.sort();
+        assert_eq!(played, vec!["a", "b", "c", "d", "e"]);
+
+        let back = queue.clone().reduce(MediaAction::Previous);
+        assert_eq!(back.current_item().unwrap().id, order[3]);
+        assert!(queue
+            .reduce(MediaAction::ToggleShuffle(0))
+            .shuffled
+            .is_none());
+    }
+
+    #[test]
+    fn moving_and_closing_keep_the_current_item() {
+        let queue = queue_of(&["a", "b", "c", "d"]).reduce(MediaAction::Select(1));
+        let moved = queue.reduce(MediaAction::Move { from: 3, to: 0 });
+        assert_eq!(names(&moved), vec!["d", "a", "b", "c"]);
+        assert_eq!(current(&moved), "b");
+        let moved = moved.reduce(MediaAction::Move { from: 2, to: 3 });
+        assert_eq!(names(&moved), vec!["d", "a", "c", "b"]);
+        assert_eq!(current(&moved), "b");
+        assert_eq!(
+            names(&moved.clone().reduce(MediaAction::Move { from: 9, to: 0 })),
+            names(&moved)
+        );
+
+        let shuffled = moved
+            .reduce(MediaAction::ToggleShuffle(7))
+            .reduce(MediaAction::Close(0));
+        assert_eq!(shuffled.shuffled.as_ref().unwrap().len(), 3);
+        assert_eq!(current(&shuffled), "b");
+        let opened = shuffled.reduce(MediaAction::Open(vec![item("e")]));
+        assert_eq!(
+            opened.shuffled.as_ref().unwrap().last(),
+            Some(&opened.items[3].id)
+        );
+    }
+
+    #[test]
+    fn positions_read_as_clock_times() {
+        assert_eq!(format_position(0.0), "0:00");
+        assert_eq!(format_position(75.9), "1:15");
+        assert_eq!(format_position(3725.0), "1:02:05");
+        assert_eq!(format_position(-3.0), "0:00");
+    }
+}
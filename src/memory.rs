@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tauri;
+
+const OPEN_TAG: &str = "<remember>";
+const CLOSE_TAG: &str = "</remember>";
+
+const PROPOSAL_INSTRUCTIONS: &str = "If the user mentions a lasting fact about themselves, their \
+work or their preferences that would help in future conversations, you may propose remembering \
+it by ending your reply with a line of the form <remember>the fact</remember>. Only propose \
+facts the user actually stated and that are not already listed; the user decides what is saved.";
+
+/// A fact the user asked the app to remember. Stored by the backend.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Memory {
+    pub id: u64,
+    pub text: String,
+    pub created_at: u64,
+}
+
+/// The system prompt for a new conversation: saved memories, then how to propose more.
+pub fn system_prompt(memories: &[Memory]) -> String {
+    let mut prompt = String::new();
+    if !memories.is_empty() {
+        prompt.push_str("Facts the user asked you to remember:\n");
+        for memory in memories {
+            prompt.push_str(&format!("- {}\n", memory.text));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(PROPOSAL_INSTRUCTIONS);
+    prompt
+}
+
+/// Splits `<remember>` proposals out of a finished reply, returning the text to show
+/// and the proposed facts. A tag that is never closed is dropped and the text after it
+/// kept, since it can't be told apart from the rest of the reply.
+pub fn extract_proposals(content: &str) -> (String, Vec<String>) {
+    split_proposals(content, true)
+}
+
+fn split_proposals(content: &str, keep_unclosed: bool) -> (String, Vec<String>) {
+    let mut visible = String::new();
+    let mut proposals: Vec<String> = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find(OPEN_TAG) {
+        visible.push_str(&rest[..start]);
+        let after = &rest[start + OPEN_TAG.len()..];
+        let Some(end) = after.find(CLOSE_TAG) else {
+            rest = if keep_unclosed { after } else { "" };
+            break;
+        };
+        let fact = after[..end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !fact.is_empty() && !proposals.iter().any(|p| p.eq_ignore_ascii_case(&fact)) {
+            proposals.push(fact);
+        }
+        rest = &after[end + CLOSE_TAG.len()..];
+    }
+    visible.push_str(rest);
+    (visible.trim_end().to_string(), proposals)
+}
+
+/// The part of a reply still streaming in that should be shown: proposals are hidden,
+/// including one still arriving or whose opening tag has only partly arrived.
+pub fn streaming_text(content: &str) -> String {
+    let partial = (1..OPEN_TAG.len())
+        .rev()
+        .find(|&n| content.ends_with(&OPEN_TAG[..n]))
+        .unwrap_or(0);
+    split_proposals(&content[..content.len() - partial], false).0
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+#[derive(Serialize)]
+struct AddArgs<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct UpdateArgs<'a> {
+    id: u64,
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct DeleteArgs {
+    id: u64,
+}
+
+pub async fn list() -> Result<Vec<Memory>, String> {
+    tauri::invoke("memory_list", &NoArgs {}).await
+}
+
+pub async fn add(text: &str) -> Result<Vec<Memory>, String> {
+    tauri::invoke("memory_add", &AddArgs { text }).await
+}
+
+pub async fn update(id: u64, text: &str) -> Result<Vec<Memory>, String> {
+    tauri::invoke("memory_update", &UpdateArgs { id, text }).await
+}
+
+pub async fn delete(id: u64) -> Result<Vec<Memory>, String> {
+    tauri::invoke("memory_delete", &DeleteArgs { id }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_prompt_lists_memories() {
+        let memories = vec![Memory {
+            id: 1,
+            text: "Our stack is Rust and Postgres".to_string(),
+            created_at: 0,
+        }];
+        let prompt = system_prompt(&memories);
+        assert!(prompt.starts_with(
+            "Facts the user asked you to remember:\n- Our stack is Rust and Postgres\n\n"
+        ));
+        assert!(prompt.ends_with(PROPOSAL_INSTRUCTIONS));
+        assert_eq!(system_prompt(&[]), PROPOSAL_INSTRUCTIONS);
+    }
+
+    #[test]
+    fn proposals_are_removed_from_the_reply() {
+        let (text, proposals) = extract_proposals(
+            "Sure, I'll use tabs.\n\n<remember>Prefers tabs\n over spaces</remember>\n<remember>prefers tabs over spaces</remember>\n<remember> </remember>",
+        );
+        assert_eq!(text, "Sure, I'll use tabs.");
+        assert_eq!(proposals, vec!["Prefers tabs over spaces"]);
+    }
+
+    #[test]
+    fn partial_tags_are_hidden_while_streaming() {
+        assert_eq!(streaming_text("Noted.\n<remem"), "Noted.");
+        assert_eq!(streaming_text("Noted.\n<remember>Uses Vi"), "Noted.");
+        assert_eq!(streaming_text("a < b"), "a < b");
+        assert_eq!(extract_proposals("x <").0, "x <");
+    }
+
+    #[test]
+    fn text_after_an_unclosed_tag_is_kept() {
+        let (text, proposals) =
+            extract_proposals("Noted.\n<remember>Uses Vim</remember>\nAlso <remember> this part");
+        assert_eq!(text, "Noted.\n\nAlso  this part");
+        assert_eq!(proposals, vec!["Uses Vim"]);
+    }
+}
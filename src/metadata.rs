@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tauri;
+
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct Gps {
+    /// Degrees, north positive.
+    pub latitude: f64,
+    /// Degrees, east positive.
+    pub longitude: f64,
+    /// Metres above sea level.
+    pub altitude: Option<f64>,
+}
+
+impl Gps {
+    /// E.g. `33.86000° S, 151.20000° E`.
+    pub fn coordinates(&self) -> String {
+        let part = |degrees: f64, positive: char, negative: char| {
+            let side = if degrees < 0.0 { negative } else { positive };
+            format!("{:.5}° {}", degrees.abs(), side)
+        };
+        format!(
+            "{}, {}",
+            part(self.latitude, 'N', 'S'),
+            part(self.longitude, 'E', 'W')
+        )
+    }
+}
+
+/// A photo's EXIF, as far as the file has it.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Camera {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    pub taken: Option<String>,
+    /// Seconds.
+    pub exposure_time: Option<f64>,
+    pub f_number: Option<f64>,
+    pub iso: Option<u32>,
+    /// Millimetres.
+    pub focal_length: Option<f64>,
+    pub gps: Option<Gps>,
+    /// The EXIF orientation, 1 to 8.
+    pub orientation: u16,
+}
+
+impl Camera {
+    /// The make and model, leaving out the make when the model already names it.
+    pub fn name(&self) -> Option<String> {
+        match (&self.make, &self.model) {
+            (Some(make), Some(model)) if !model.starts_with(make.as_str()) => {
+                Some(format!("{} {}", make, model))
+            }
+            (make, model) => model.clone().or_else(|| make.clone()),
+        }
+    }
+
+    /// E.g. `1/250 s · f/2.8 · ISO 400 · 23 mm`.
+    pub fn exposure(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            self.exposure_time.map(format_exposure_time),
+            self.f_number.map(|f| format!("f/{}", round(f, 1))),
+            self.iso.map(|iso| format!("ISO {}", iso)),
+            self.focal_length.map(|mm| format!("{} mm", round(mm, 1))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+/// `value` to at most `places` decimals, without trailing zeros.
+fn round(value: f64, places: usize) -> String {
+    let text = format!("{:.*}", places, value);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+/// Shutter speeds as photographers write them: fractions under half a second.
+pub fn format_exposure_time(seconds: f64) -> String {
+    if seconds > 0.0 && seconds < 0.5 {
+        format!("1/{} s", (1.0 / seconds).round())
+    } else {
+        format!("{} s", round(seconds, 1))
+    }
+}
+
+/// What an EXIF orientation other than upright does to the stored pixels.
+pub fn orientation_label(orientation: u16) -> Option<&'static str> {
+    Some(match orientation {
+        2 => "Mirrored",
+        3 => "Rotated 180°",
+        4 => "Flipped upside down",
+        5 => "Mirrored and rotated 90° counter-clockwise",
+        6 => "Rotated 90° clockwise",
+        7 => "Mirrored and rotated 90° clockwise",
+        8 => "Rotated 90° counter-clockwise",
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackKind {
+    Video,
+    Audio,
+    Subtitle,
+    Other,
+}
+
+impl TrackKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Video => "Video",
+            Self::Audio => "Audio",
+            Self::Subtitle => "Subtitles",
+            Self::Other => "Other",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct Track {
+    pub kind: TrackKind,
+    pub codec: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Metadata {
+    #[serde(rename_all = "camelCase")]
+    Image {
+        format: Option<String>,
+        /// Upright, as displayed.
+        width: Option<u32>,
+        height: Option<u32>,
+        camera: Option<Camera>,
+        /// Whether a copy without metadata can be saved.
+        strippable: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Video {
+        container: Option<String>,
+        duration: Option<f64>,
+        width: Option<u32>,
+        height: Option<u32>,
+        frame_rate: Option<f64>,
+        tracks: Vec<Track>,
+    },
+}
+
+/// E.g. `29.97 fps`.
+pub fn format_frame_rate(rate: f64) -> String {
+    format!("{} fps", round(rate, 2))
+}
+
+#[derive(Serialize)]
+struct PathArgs<'a> {
+    path: &'a str,
+}
+
+#[derive(Serialize)]
+struct SourceArgs<'a> {
+    source: &'a str,
+}
+
+pub async fn metadata(path: &str) -> Result<Metadata, String> {
+    tauri::invoke("media_metadata", &PathArgs { path }).await
+}
+
+/// Asks where to save a copy of the image without its metadata. Returns the saved
+/// path, or `None` if the user cancelled.
+pub async fn strip_metadata(source: &str) -> Result<Option<String>, String> {
+    tauri::invoke("media_strip_metadata", &SourceArgs { source }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposures_read_as_photographers_write_them() {
+        assert_eq!(format_exposure_time(0.004), "1/250 s");
+        assert_eq!(format_exposure_time(1.0 / 3.0), "1/3 s");
+        assert_eq!(format_exposure_time(2.5), "2.5 s");
+        assert_eq!(format_exposure_time(30.0), "30 s");
+
+        let camera: Camera = serde_json::from_str(
+            r#"{"make": "FUJIFILM", "model": "FUJIFILM X100V", "lens": null, "taken": null,
+                "exposureTime": 0.004, "fNumber": 2.8, "iso": 400, "focalLength": 23.0,
+                "gps": null, "orientation": 6}"#,
+        )
+        .unwrap();
+        assert_eq!(camera.name().as_deref(), Some("FUJIFILM X100V"));
+        assert_eq!(
+            camera.exposure().as_deref(),
+            Some("1/250 s · f/2.8 · ISO 400 · 23 mm")
+        );
+        assert_eq!(
+            orientation_label(camera.orientation),
+            Some("Rotated 90° clockwise")
+        );
+        assert_eq!(orientation_label(1), None);
+    }
+
+    #[test]
+    fn coordinates_name_their_hemisphere() {
+        let gps = Gps {
+            latitude: -33.86,
+            longitude: 151.2,
+            altitude: None,
+        };
+        assert_eq!(gps.coordinates(), "33.86000° S, 151.20000° E");
+        assert_eq!(format_frame_rate(30000.0 / 1001.0), "29.97 fps");
+        assert_eq!(format_frame_rate(25.0), "25 fps");
+    }
+}
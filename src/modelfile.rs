@@ -0,0 +1,482 @@
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::ollama::{CreateRequest, Message};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    /// 1-based line the problem starts on.
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ParamKind {
+    Int,
+    Float,
+    Text,
+}
+
+/// Parameters Ollama accepts in a Modelfile, and the type of their value. Others are
+/// passed through with a warning, since Ollama gains options faster than this list.
+const PARAMETERS: &[(&str, ParamKind)] = &[
+    ("frequency_penalty", ParamKind::Float),
+    ("mirostat", ParamKind::Int),
+    ("mirostat_eta", ParamKind::Float),
+    ("mirostat_tau", ParamKind::Float),
+    ("num_batch", ParamKind::Int),
+    ("num_ctx", ParamKind::Int),
+    ("num_gpu", ParamKind::Int),
+    ("num_keep", ParamKind::Int),
+    ("num_thread", ParamKind::Int),
+    ("num_predict", ParamKind::Int),
+    ("presence_penalty", ParamKind::Float),
+    ("repeat_last_n", ParamKind::Int),
+    ("repeat_penalty", ParamKind::Float),
+    ("temperature", ParamKind::Float),
+    ("seed", ParamKind::Int),
+    ("stop", ParamKind::Text),
+    ("top_k", ParamKind::Int),
+    ("top_p", ParamKind::Float),
+    ("min_p", ParamKind::Float),
+    ("typical_p", ParamKind::Float),
+];
+
+pub const INSTRUCTIONS: &[&str] = &[
+    "FROM",
+    "PARAMETER",
+    "SYSTEM",
+    "TEMPLATE",
+    "ADAPTER",
+    "LICENSE",
+    "MESSAGE",
+];
+
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Modelfile {
+    pub from: Option<String>,
+    pub parameters: Map<String, Value>,
+    pub system: Option<String>,
+    pub template: Option<String>,
+    pub adapters: Vec<String>,
+    pub license: Vec<String>,
+    pub messages: Vec<(String, String)>,
+}
+
+/// The blob digest `value` refers to, either written as `sha256:<hex>` or as a path into
+/// Ollama's blob store, which is how `/api/show` prints the FROM of an installed model.
+fn blob_digest(value: &str) -> Option<String> {
+    if value.starts_with("sha256:") {
+        return Some(value.to_string());
+    }
+    let file = value.rsplit(['/', '\\']).next()?;
+    file.strip_prefix("sha256-")
+        .map(|hex| format!("sha256:{}", hex))
+}
+
+/// Files referenced by path must be uploaded as blobs before `/api/create` can use them;
+/// only model names and blob digests can be sent directly.
+fn is_local_path(value: &str) -> bool {
+    blob_digest(value).is_none()
+        && (value.starts_with('.')
+            || value.starts_with('/')
+            || value.starts_with('~')
+            || value.contains('\\')
+            || value.ends_with(".gguf")
+            || value.ends_with(".safetensors")
+            || value.ends_with(".bin"))
+}
+
+/// Reads an argument starting at `rest` on line `line`. Triple-quoted values may span
+/// lines, so the remaining lines are passed in and consumed as needed.
+fn read_value<'a>(
+    rest: &'a str,
+    line: usize,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> String {
+    let rest = rest.trim();
+    if let Some(body) = rest.strip_prefix("\"\"\"") {
+        if let Some(end) = body.find("\"\"\"") {
+            return body[..end].to_string();
+        }
+        let mut value = body.to_string();
+        for (_, next) in lines.by_ref() {
+            if let Some(end) = next.find("\"\"\"") {
+                value.push('\n');
+                value.push_str(&next[..end]);
+                return value;
+            }
+            value.push('\n');
+            value.push_str(next);
+        }
+        diagnostics.push(Diagnostic {
+            line,
+            severity: Severity::Error,
+            message: "Unterminated \"\"\" block".to_string(),
+        });
+        return value;
+    }
+    if rest.len() >= 2 && rest.starts_with('"') && rest.ends_with('"') {
+        return rest[1..rest.len() - 1].to_string();
+    }
+    rest.to_string()
+}
+
+fn parameter_kind(name: &str) -> Option<ParamKind> {
+    PARAMETERS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, kind)| *kind)
+}
+
+/// The value of a parameter of unknown type: a number or boolean if it reads as one,
+/// text otherwise.
+fn untyped_value(raw: &str) -> Value {
+    if let Ok(int) = raw.parse::<i64>() {
+        return Value::from(int);
+    }
+    if let Ok(float) = raw.parse::<f64>() {
+        return Value::from(float);
+    }
+    match raw {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::from(raw),
+    }
+}
+
+fn parameter_value(name: &str, raw: &str) -> Result<Value, String> {
+    let Some(kind) = parameter_kind(name) else {
+        return Ok(untyped_value(raw));
+    };
+    match kind {
+        ParamKind::Int => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("{} expects an integer, got \"{}\"", name, raw)),
+        ParamKind::Float => raw
+            .parse::<f64>()
+            .map(Value::from)
+            .map_err(|_| format!("{} expects a number, got \"{}\"", name, raw)),
+        ParamKind::Text => Ok(Value::from(raw)),
+    }
+}
+
+/// Parses a Modelfile, collecting every problem instead of stopping at the first.
+pub fn parse(text: &str) -> (Modelfile, Vec<Diagnostic>) {
+    let mut file = Modelfile::default();
+    let mut diagnostics = Vec::new();
+    let mut lines = text.lines().enumerate().map(|(i, l)| (i + 1, l));
+
+    while let Some((line, raw)) = lines.next() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (instruction, rest) = trimmed
+            .split_once(char::is_whitespace)
+            .unwrap_or((trimmed, ""));
+        let mut error = |severity, message: String| {
+            diagnostics.push(Diagnostic {
+                line,
+                severity,
+                message,
+            })
+        };
+
+        match instruction.to_ascii_uppercase().as_str() {
+            "FROM" => {
+                let value = rest.trim().to_string();
+                if value.is_empty() {
+                    error(
+                        Severity::Error,
+                        "FROM needs a model name or path".to_string(),
+                    );
+                } else if file.from.is_some() {
+                    error(Severity::Error, "Only one FROM is allowed".to_string());
+                } else {
+                    // The server can't read this machine's files, so creating would fail.
+                    if is_local_path(&value) {
+                        error(
+                            Severity::Error,
+                            "Local files must be uploaded with `ollama create`; use a model name or sha256 digest here".to_string(),
+                        );
+                    }
+                    file.from = Some(value);
+                }
+            }
+            "PARAMETER" => {
+                let (name, raw_value) = rest
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((rest.trim(), ""));
+                let raw_value = raw_value.trim().trim_matches('"');
+                if name.is_empty() || raw_value.is_empty() {
+                    error(
+                        Severity::Error,
+                        "PARAMETER needs a name and a value".to_string(),
+                    );
+                    continue;
+                }
+                if parameter_kind(name).is_none() {
+                    error(
+                        Severity::Warning,
+                        format!(
+                            "Unknown parameter \"{}\"; it is sent to Ollama unchecked",
+                            name
+                        ),
+                    );
+                }
+                match parameter_value(name, raw_value) {
+                    // `stop` may be given several times and collects into a list.
+                    Ok(value) if name == "stop" => {
+                        let entry = file
+                            .parameters
+                            .entry(name.to_string())
+                            .or_insert_with(|| Value::Array(Vec::new()));
+                        if let Value::Array(items) = entry {
+                            items.push(value);
+                        }
+                    }
+                    Ok(value) => {
+                        if file.parameters.insert(name.to_string(), value).is_some() {
+                            error(
+                                Severity::Warning,
+                                format!("{} is set more than once; the last value wins", name),
+                            );
+                        }
+                    }
+                    Err(message) => error(Severity::Error, message),
+                }
+            }
+            "SYSTEM" | "TEMPLATE" => {
+                let is_system = instruction.eq_ignore_ascii_case("SYSTEM");
+                let value = read_value(rest, line, &mut lines, &mut diagnostics);
+                let slot = if is_system {
+                    &mut file.system
+                } else {
+                    &mut file.template
+                };
+                if slot.replace(value).is_some() {
+                    diagnostics.push(Diagnostic {
+                        line,
+                        severity: Severity::Warning,
+                        message: format!(
+                            "{} is set more than once; the last value wins",
+                            instruction.to_ascii_uppercase()
+                        ),
+                    });
+                }
+            }
+            "ADAPTER" => {
+                let value = rest.trim().to_string();
+                if value.is_empty() {
+                    error(
+                        Severity::Error,
+                        "ADAPTER needs a path or digest".to_string(),
+                    );
+                } else {
+                    if is_local_path(&value) {
+                        error(
+                            Severity::Error,
+                            "Local adapters must be uploaded with `ollama create`; use a sha256 digest here".to_string(),
+                        );
+                    }
+                    file.adapters.push(value);
+                }
+            }
+            "LICENSE" => {
+                let value = read_value(rest, line, &mut lines, &mut diagnostics);
+                file.license.push(value);
+            }
+            "MESSAGE" => {
+                let (role, content) = rest
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((rest.trim(), ""));
+                let role = role.to_ascii_lowercase();
+                if !matches!(role.as_str(), "system" | "user" | "assistant") {
+                    diagnostics.push(Diagnostic {
+                        line,
+                        severity: Severity::Error,
+                        message: format!(
+                            "MESSAGE role must be system, user or assistant, got \"{}\"",
+                            role
+                        ),
+                    });
+                    continue;
+                }
+                let content = read_value(content, line, &mut lines, &mut diagnostics);
+                file.messages.push((role, content));
+            }
+            _ => error(
+                Severity::Error,
+                format!(
+                    "Unknown instruction \"{}\"; expected one of {}",
+                    instruction,
+                    INSTRUCTIONS.join(", ")
+                ),
+            ),
+        }
+    }
+
+    if file.from.is_none() {
+        diagnostics.push(Diagnostic {
+            line: 1,
+            severity: Severity::Error,
+            message: "A Modelfile needs a FROM instruction".to_string(),
+        });
+    }
+
+    (file, diagnostics)
+}
+
+impl Modelfile {
+    /// Builds the structured `/api/create` request for a model called `name`.
+    pub fn to_create_request(&self, name: &str) -> CreateRequest {
+        let mut request = CreateRequest {
+            model: name.to_string(),
+            system: self.system.clone(),
+            template: self.template.clone(),
+            license: self.license.clone(),
+            parameters: self.parameters.clone(),
+            messages: self
+                .messages
+                .iter()
+                .map(|(role, content)| Message::new(role, content.clone()))
+                .collect(),
+            stream: true,
+            ..Default::default()
+        };
+
+        match self.from.as_deref().map(|from| (from, blob_digest(from))) {
+            Some((_, Some(digest))) => {
+                request.files = BTreeMap::from([("model.gguf".to_string(), digest)]);
+            }
+            from => request.from = from.map(|(from, _)| from.to_string()),
+        }
+        request.adapters = self
+            .adapters
+            .iter()
+            .enumerate()
+            .map(|(i, adapter)| {
+                let digest = blob_digest(adapter).unwrap_or_else(|| adapter.clone());
+                (format!("adapter{}.gguf", i), digest)
+            })
+            .collect();
+
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(diagnostics: &[Diagnostic]) -> Vec<&Diagnostic> {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .collect()
+    }
+
+    #[test]
+    fn parses_a_typical_modelfile() {
+        let text = "# reviewer\nFROM llama3.2\nPARAMETER temperature 0.2\nPARAMETER stop \"<|end|>\"\nPARAMETER stop \"User:\"\nSYSTEM \"\"\"\nYou review code.\nBe terse.\n\"\"\"\nMESSAGE user Hi\n";
+        let (file, diagnostics) = parse(text);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(file.from.as_deref(), Some("llama3.2"));
+        assert_eq!(file.parameters["temperature"], Value::from(0.2));
+        assert_eq!(
+            file.parameters["stop"],
+            serde_json::json!(["<|end|>", "User:"])
+        );
+        assert_eq!(
+            file.system.as_deref(),
+            Some("\nYou review code.\nBe terse.\n")
+        );
+        assert_eq!(file.messages, vec![("user".to_string(), "Hi".to_string())]);
+    }
+
+    #[test]
+    fn reports_bad_parameters_with_their_line() {
+        let (_, diagnostics) = parse("FROM x\nPARAMETER num_ctx lots\nPARAMETER top_p high\n");
+        let errors = errors(&diagnostics);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, 2);
+        assert!(errors[0].message.contains("integer"));
+        assert_eq!(errors[1].line, 3);
+        assert!(errors[1].message.contains("number"));
+    }
+
+    #[test]
+    fn unknown_parameters_are_passed_through_with_a_warning() {
+        let (file, diagnostics) = parse(
+            "FROM x\nPARAMETER num_keep 24\nPARAMETER colour blue\nPARAMETER new_ratio 0.5\nPARAMETER use_mmap false\n",
+        );
+        assert!(errors(&diagnostics).is_empty(), "{:?}", diagnostics);
+        let lines: Vec<usize> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(diagnostics[0].message.contains("Unknown parameter"));
+        assert_eq!(file.parameters["num_keep"], Value::from(24));
+        assert_eq!(file.parameters["colour"], Value::from("blue"));
+        assert_eq!(file.parameters["new_ratio"], Value::from(0.5));
+        assert_eq!(file.parameters["use_mmap"], Value::Bool(false));
+    }
+
+    #[test]
+    fn requires_exactly_one_from() {
+        assert!(!errors(&parse("SYSTEM hi\n").1).is_empty());
+        assert_eq!(errors(&parse("FROM a\nFROM b\n").1)[0].line, 2);
+    }
+
+    #[test]
+    fn flags_unterminated_blocks_and_unknown_instructions() {
+        let (_, diagnostics) = parse("FROM a\nTEMPLATE \"\"\"{{ .Prompt }}\nRUN rm -rf\n");
+        assert_eq!(errors(&diagnostics)[0].line, 2);
+        let (_, diagnostics) = parse("FROM a\nRUN rm -rf\n");
+        assert!(errors(&diagnostics)[0]
+            .message
+            .contains("Unknown instruction"));
+    }
+
+    #[test]
+    fn local_paths_are_errors() {
+        let (file, diagnostics) = parse("FROM ./model.gguf\nADAPTER ./lora.gguf\n");
+        let found = errors(&diagnostics);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].line, found[1].line), (1, 2));
+        assert_eq!(file.adapters, vec!["./lora.gguf".to_string()]);
+        assert_eq!(errors(&parse("FROM C:\\models\\llama.gguf\n").1).len(), 1);
+    }
+
+    #[test]
+    fn builds_a_create_request() {
+        let (file, _) = parse("FROM sha256:abc\nADAPTER sha256:def\nSYSTEM be brief\n");
+        let request = file.to_create_request("brief");
+        assert_eq!(request.model, "brief");
+        assert_eq!(request.from, None);
+        assert_eq!(request.files["model.gguf"], "sha256:abc");
+        assert_eq!(request.adapters["adapter0.gguf"], "sha256:def");
+        assert_eq!(request.system.as_deref(), Some("be brief"));
+    }
+
+    #[test]
+    fn blob_store_paths_from_show_become_digests() {
+        let (file, diagnostics) = parse("FROM /home/me/.ollama/models/blobs/sha256-abc123\n");
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(
+            file.to_create_request("copy").files["model.gguf"],
+            "sha256:abc123"
+        );
+    }
+}
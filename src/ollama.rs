@@ -0,0 +1,6 @@
+//! The Ollama client, shared with the backend through the `ollama-client` crate.
+
+pub use ollama_client::*;
+
+/// A client that sends its requests through the webview's `fetch`.
+pub type Client = ollama_client::Client<Fetch>;
@@ -12,4 +12,20 @@ pub enum Route {
     Image,
     #[at("/chat")]
     Chat,
+    #[at("/library")]
+    Library,
+    #[at("/workspace")]
+    Workspace,
+    #[at("/playground")]
+    Playground,
+    #[at("/embeddings")]
+    Embeddings,
+    #[at("/models/new")]
+    Modelfile,
+    #[at("/batch")]
+    Batch,
+    #[at("/eval")]
+    Eval,
+    #[at("/memory")]
+    Memory,
 }
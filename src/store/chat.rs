@@ -0,0 +1,353 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use web_sys::{window, AbortController};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use super::state::{ChatAction, ChatState, Conversation};
+use crate::memory;
+use crate::ollama::{ChatRequest, Client, Message};
+use crate::routes::Route;
+use crate::tauri;
+use crate::thinking::ReplyBuilder;
+use crate::titles;
+
+const STORAGE_KEY: &str = "conversations";
+
+static NEXT_REPLY_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// Aborts the request behind each reply in flight, keyed by reply id.
+    static ABORTS: RefCell<HashMap<u64, AbortController>> = RefCell::default();
+    /// The reply each conversation is waiting on, keyed by conversation id. Set before
+    /// the request starts, since a store handle only sees `Submit` once it re-renders and
+    /// a quick double submit would otherwise send twice.
+    static IN_FLIGHT: RefCell<HashMap<u32, u64>> = RefCell::default();
+}
+
+/// The part of [`ChatState`] kept in local storage between sessions.
+#[derive(Serialize, Deserialize)]
+struct SavedChats {
+    conversations: Vec<Conversation>,
+    active: u32,
+    #[serde(default)]
+    title_model: String,
+}
+
+impl ChatState {
+    /// Restores saved conversations, or starts fresh if there are none.
+    fn load() -> Self {
+        let saved = window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str::<SavedChats>(&json).ok())
+            .filter(|saved| !saved.conversations.is_empty());
+        let Some(saved) = saved else {
+            return Self::default();
+        };
+        let next_id = saved.conversations.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+        let active = if saved.conversations.iter().any(|c| c.id == saved.active) {
+            saved.active
+        } else {
+            saved.conversations[0].id
+        };
+        Self {
+            conversations: saved.conversations,
+            active,
+            title_model: saved.title_model,
+            next_id,
+            ..Self::default()
+        }
+    }
+
+    fn save(&self) {
+        let saved = SavedChats {
+            conversations: self.conversations.clone(),
+            active: self.active,
+            title_model: self.title_model.clone(),
+        };
+        let Ok(json) = serde_json::to_string(&saved) else {
+            return;
+        };
+        if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+            if let Err(e) = storage.set_item(STORAGE_KEY, &json) {
+                log::warn!("Failed to save conversations: {:?}", e);
+            }
+        }
+    }
+}
+
+pub type ChatStore = UseReducerHandle<ChatState>;
+
+/// Sends `text` and any attached `images` in the active conversation, returning whether
+/// it was sent. The reply streams into the store, so it keeps arriving while the user
+/// is on another page.
+pub fn send_message(store: &ChatStore, text: String, images: Vec<String>) -> bool {
+    let Some(conv) = store.active_conversation() else {
+        return false;
+    };
+    let id = conv.id;
+    let waiting = IN_FLIGHT.with(|in_flight| in_flight.borrow().contains_key(&id));
+    if waiting || conv.generating() || conv.model.is_empty() {
+        return false;
+    }
+    if conv.messages.is_empty() && !store.memories_loaded {
+        return false;
+    }
+    let reply_id = NEXT_REPLY_ID.fetch_add(1, Ordering::Relaxed);
+    IN_FLIGHT.with(|in_flight| in_flight.borrow_mut().insert(id, reply_id));
+
+    let needs_title = conv.messages.is_empty() && !conv.title_edited;
+    let title_model = Some(store.title_model.clone())
+        .filter(|m| store.models.contains(m))
+        .unwrap_or_else(|| conv.model.clone());
+    let prompt = Message {
+        images,
+        ..Message::new("user", text)
+    };
+    // Memories are read once, when the conversation starts.
+    let system = if conv.messages.is_empty() {
+        tauri::is_tauri().then(|| memory::system_prompt(&store.memories))
+    } else {
+        conv.system.clone()
+    };
+    // Error notices are local only, and earlier reasoning just costs context.
+    let history = system
+        .iter()
+        .map(|s| Message::system(s.clone()))
+        .chain(
+            conv.messages
+                .iter()
+                .map(Rc::as_ref)
+                .chain(std::iter::once(&prompt))
+                .filter(|m| m.role != "system")
+                .map(|m| Message {
+                    images: m.images.clone(),
+                    ..Message::new(&m.role, m.content.clone())
+                }),
+        )
+        .collect();
+    let request = ChatRequest {
+        model: conv.model.clone(),
+        messages: history,
+        stream: true,
+        think: Some(conv.think),
+        ..Default::default()
+    };
+    let title_client = store.client();
+    let client = match AbortController::new() {
+        Ok(abort) => {
+            let client = store.client().with_abort_signal(abort.signal());
+            ABORTS.with(|aborts| aborts.borrow_mut().insert(reply_id, abort));
+            client
+        }
+        Err(_) => store.client(),
+    };
+    let dispatcher = store.dispatcher();
+
+    let prompt_text = prompt.content.clone();
+    store.dispatch(ChatAction::Submit {
+        id,
+        reply_id,
+        prompt,
+        system,
+    });
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut reply = ReplyBuilder::default();
+        let mut stats = None;
+        let result = client
+            .stream_chat(&request, |chunk| {
+                stats = chunk.stats().or(stats);
+                if let Some(delta) = &chunk.message {
+                    reply.push(delta, js_sys::Date::now());
+                    let mut shown = reply.message().clone();
+                    shown.content = memory::streaming_text(&shown.content);
+                    dispatcher.dispatch(ChatAction::StreamChunk {
+                        id,
+                        reply_id,
+                        reply: shown,
+                    });
+                }
+            })
+            .await;
+        ABORTS.with(|aborts| aborts.borrow_mut().remove(&reply_id));
+        IN_FLIGHT.with(|in_flight| {
+            let mut in_flight = in_flight.borrow_mut();
+            if in_flight.get(&id) == Some(&reply_id) {
+                in_flight.remove(&id);
+            }
+        });
+
+        let mut reply = reply.finish(js_sys::Date::now());
+        reply.stats = stats;
+        let (content, proposals) = memory::extract_proposals(&reply.content);
+        reply.content = content;
+        let snippet: String = reply.content.chars().take(120).collect();
+        let reply_text = reply.content.clone();
+        let succeeded = result.is_ok();
+        match result {
+            Ok(()) => dispatcher.dispatch(ChatAction::Done {
+                id,
+                reply_id,
+                reply,
+                proposals,
+            }),
+            Err(error) => dispatcher.dispatch(ChatAction::Error {
+                id,
+                reply_id,
+                reply,
+                error,
+            }),
+        }
+
+        if succeeded {
+            notify_reply_ready(request.model, snippet, id).await;
+        }
+        if needs_title {
+            let title =
+                generate_title(&title_client, &title_model, &prompt_text, &reply_text).await;
+            dispatcher.dispatch(ChatAction::SetTitle { id, title });
+        }
+    });
+    true
+}
+
+/// Stops the reply in flight in conversation `id`, keeping what has arrived so far.
+pub fn cancel_reply(store: &ChatStore, id: u32) {
+    let reply_id = store.conversation(id).and_then(|c| c.streaming);
+    if let Some(abort) = reply_id.and_then(|r| ABORTS.with(|aborts| aborts.borrow_mut().remove(&r)))
+    {
+        abort.abort();
+    }
+    IN_FLIGHT.with(|in_flight| in_flight.borrow_mut().remove(&id));
+    store.dispatch(ChatAction::Cancel(id));
+}
+
+/// Asks the title model to name a conversation after its first exchange, falling back
+/// to the start of the prompt when the model is unavailable or answers with nothing
+/// usable.
+async fn generate_title(client: &Client, model: &str, prompt: &str, reply: &str) -> String {
+    let fallback = titles::fallback_title(prompt);
+    if reply.is_empty() {
+        return fallback;
+    }
+    let request = titles::title_request(model, prompt, reply);
+    match client.chat(&request).await {
+        Ok(response) => titles::sanitize_title(&response.message.content).unwrap_or(fallback),
+        Err(e) => {
+            log::warn!("Failed to generate a title: {}", e);
+            fallback
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotifyArgs {
+    model: String,
+    snippet: String,
+    conversation_id: u32,
+}
+
+/// Asks the backend to post a desktop notification; it only does so when the window is
+/// not focused.
+async fn notify_reply_ready(model: String, snippet: String, conversation_id: u32) {
+    if !tauri::is_tauri() {
+        return;
+    }
+    let args = NotifyArgs {
+        model,
+        snippet,
+        conversation_id,
+    };
+    if let Err(e) = tauri::invoke::<_, bool>("notify_reply_ready", &args).await {
+        log::warn!("Failed to post notification: {}", e);
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ChatProviderProps {
+    pub children: Html,
+}
+
+/// Owns the chat state for the whole app so conversations outlive the chat page.
+#[function_component(ChatProvider)]
+pub fn chat_provider(props: &ChatProviderProps) -> Html {
+    let store = use_reducer(ChatState::load);
+
+    {
+        // Only settled state is saved; a streaming reply is written once it finishes.
+        let state = (*store).clone();
+        use_effect_with(
+            (
+                state.conversations.clone(),
+                state.active,
+                state.title_model.clone(),
+            ),
+            move |_| {
+                if state.generating_count() == 0 {
+                    state.save();
+                }
+            },
+        );
+    }
+
+    {
+        let dispatcher = store.dispatcher();
+        use_effect_with(store.api_url.clone(), move |url| {
+            let client = Client::new(url);
+
+            wasm_bindgen_futures::spawn_local(async move {
+                dispatcher.dispatch(ChatAction::Connecting);
+                match client.models().await {
+                    Ok(names) => dispatcher.dispatch(ChatAction::ModelsLoaded(names)),
+                    Err(e) => dispatcher.dispatch(ChatAction::ConnectionFailed(e)),
+                }
+            });
+        });
+    }
+
+    {
+        let dispatcher = store.dispatcher();
+        use_effect_with((), move |_| {
+            if !tauri::is_tauri() {
+                dispatcher.dispatch(ChatAction::MemoriesLoaded(Vec::new()));
+                return;
+            }
+            wasm_bindgen_futures::spawn_local(async move {
+                // Conversations wait for this, so a failure still lets them start.
+                let memories = memory::list().await.unwrap_or_else(|e| {
+                    log::warn!("Failed to load memories: {}", e);
+                    Vec::new()
+                });
+                dispatcher.dispatch(ChatAction::MemoriesLoaded(memories));
+            });
+        });
+    }
+
+    {
+        // Clicking a "Response ready" notification opens the conversation it was about.
+        let dispatcher = store.dispatcher();
+        let navigator = use_navigator();
+        use_effect_with((), move |_| {
+            let listener = tauri::listen("open-conversation", move |id: u32| {
+                dispatcher.dispatch(ChatAction::SelectConversation(id));
+                if let Some(navigator) = &navigator {
+                    navigator.push(&Route::Chat);
+                }
+            });
+            move || drop(listener)
+        });
+    }
+
+    html! {
+        <ContextProvider<ChatStore> context={store}>
+            { props.children.clone() }
+        </ContextProvider<ChatStore>>
+    }
+}
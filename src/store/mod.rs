@@ -0,0 +1,5 @@
+pub mod chat;
+pub mod state;
+
+pub use chat::{ChatProvider, ChatStore};
+pub use state::{ChatAction, Conversation};
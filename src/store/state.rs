@@ -0,0 +1,613 @@
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::memory::Memory;
+use crate::ollama::{Client, Message, DEFAULT_API_URL};
+use crate::titles;
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: u32,
+    pub title: String,
+    /// Set once the user renames the conversation, so generated titles stop replacing it.
+    #[serde(default)]
+    pub title_edited: bool,
+    /// Shared so that cloning the state on every streamed token doesn't copy the
+    /// history. Adding a message copies the list but not the messages, so finished
+    /// ones keep their identity and the views can compare them by pointer.
+    pub messages: Rc<Vec<Rc<Message>>>,
+    /// The assistant reply currently being streamed, if any.
+    #[serde(skip)]
+    pub pending: Option<Message>,
+    /// Identifies the reply in flight. Stream updates carrying any other id are stale
+    /// (from a cancelled request) and are dropped.
+    #[serde(skip)]
+    pub streaming: Option<u64>,
+    pub model: String,
+    pub think: bool,
+    /// System prompt fixed when the conversation starts, carrying the memories saved
+    /// at that time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    /// Facts the model proposed remembering that the user hasn't accepted or dismissed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposals: Vec<String>,
+}
+
+impl Conversation {
+    fn new(id: u32, model: String) -> Self {
+        Self {
+            id,
+            title: String::new(),
+            title_edited: false,
+            messages: Rc::default(),
+            pending: None,
+            streaming: None,
+            model,
+            think: false,
+            system: None,
+            proposals: Vec::new(),
+        }
+    }
+
+    pub fn generating(&self) -> bool {
+        self.streaming.is_some()
+    }
+
+    /// Ends the reply in flight, keeping whatever arrived of it.
+    fn finish_reply(&mut self, reply: Option<Message>) {
+        self.streaming = None;
+        self.pending = None;
+        if let Some(reply) = reply.filter(|r| !r.content.is_empty() || r.thinking.is_some()) {
+            Rc::make_mut(&mut self.messages).push(Rc::new(reply));
+        }
+    }
+
+    /// Title to show in lists: the stored title, or the start of the first prompt.
+    pub fn display_title(&self) -> String {
+        if !self.title.is_empty() {
+            return self.title.clone();
+        }
+        self.messages
+            .iter()
+            .find(|m| m.role == "user")
+            .map(|m| titles::fallback_title(&m.content))
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "New chat".to_string())
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ChatState {
+    pub api_url: String,
+    pub models: Vec<String>,
+    pub connecting: bool,
+    pub connection_error: String,
+    pub conversations: Vec<Conversation>,
+    pub active: u32,
+    /// Model used to name conversations; empty means the conversation's own model.
+    pub title_model: String,
+    /// Saved memories; always empty outside the desktop app.
+    pub memories: Vec<Memory>,
+    /// Whether `memories` has been read yet. A new conversation waits for it, since its
+    /// system prompt is fixed when it starts.
+    pub memories_loaded: bool,
+    pub(super) next_id: u32,
+}
+
+impl Default for ChatState {
+    fn default() -> Self {
+        Self {
+            api_url: DEFAULT_API_URL.to_string(),
+            models: Vec::new(),
+            connecting: false,
+            connection_error: String::new(),
+            conversations: vec![Conversation::new(1, String::new())],
+            active: 1,
+            title_model: String::new(),
+            memories: Vec::new(),
+            memories_loaded: false,
+            next_id: 2,
+        }
+    }
+}
+
+impl ChatState {
+    pub fn active_conversation(&self) -> Option<&Conversation> {
+        self.conversation(self.active)
+    }
+
+    pub fn conversation(&self, id: u32) -> Option<&Conversation> {
+        self.conversations.iter().find(|c| c.id == id)
+    }
+
+    pub fn client(&self) -> Client {
+        Client::new(&self.api_url)
+    }
+
+    pub fn generating_count(&self) -> usize {
+        self.conversations.iter().filter(|c| c.generating()).count()
+    }
+
+    fn conversation_mut(&mut self, id: u32) -> Option<&mut Conversation> {
+        self.conversations.iter_mut().find(|c| c.id == id)
+    }
+
+    /// The conversation whose reply in flight is `reply_id`, if that reply is still
+    /// wanted.
+    fn streaming_mut(&mut self, id: u32, reply_id: u64) -> Option<&mut Conversation> {
+        self.conversation_mut(id)
+            .filter(|c| c.streaming == Some(reply_id))
+    }
+
+    fn default_model(&self) -> String {
+        self.active_conversation()
+            .map(|c| c.model.clone())
+            .filter(|m| !m.is_empty())
+            .or_else(|| self.models.first().cloned())
+            .unwrap_or_default()
+    }
+}
+
+pub enum ChatAction {
+    SetApiUrl(String),
+    Connecting,
+    ModelsLoaded(Vec<String>),
+    ConnectionFailed(String),
+    /// Changes the active conversation's model; a reply in flight finishes on the old one.
+    SwitchModel(String),
+    ToggleThinking,
+    NewConversation,
+    SelectConversation(u32),
+    DeleteConversation(u32),
+    /// A generated title; ignored once the user has renamed the conversation.
+    SetTitle {
+        id: u32,
+        title: String,
+    },
+    /// A title chosen by the user. An empty title falls back to the start of the prompt.
+    RenameConversation {
+        id: u32,
+        title: String,
+    },
+    SetTitleModel(String),
+    MemoriesLoaded(Vec<Memory>),
+    /// Removes a proposed memory once the user has saved or dismissed it.
+    ResolveProposal {
+        id: u32,
+        text: String,
+    },
+    /// Sends `prompt`. Ignored while the conversation is still waiting on a reply, so
+    /// a double submit can't interleave two answers.
+    Submit {
+        id: u32,
+        reply_id: u64,
+        prompt: Message,
+        system: Option<String>,
+    },
+    /// The reply so far. Replaces the previous chunk rather than appending to it.
+    StreamChunk {
+        id: u32,
+        reply_id: u64,
+        reply: Message,
+    },
+    Done {
+        id: u32,
+        reply_id: u64,
+        reply: Message,
+        proposals: Vec<String>,
+    },
+    /// The request failed; whatever arrived is kept, followed by an error notice.
+    Error {
+        id: u32,
+        reply_id: u64,
+        reply: Message,
+        error: String,
+    },
+    /// Stops waiting for the reply in flight. Anything still arriving for it is dropped.
+    Cancel(u32),
+}
+
+impl Reducible for ChatState {
+    type Action = ChatAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut state = (*self).clone();
+
+        match action {
+            ChatAction::SetApiUrl(url) => state.api_url = url,
+            ChatAction::Connecting => {
+                state.connecting = true;
+                state.connection_error.clear();
+            }
+            ChatAction::ModelsLoaded(names) => {
+                state.connecting = false;
+                for conv in state.conversations.iter_mut() {
+                    if !names.contains(&conv.model) {
+                        conv.model = names.first().cloned().unwrap_or_default();
+                    }
+                }
+                state.models = names;
+            }
+            ChatAction::ConnectionFailed(error) => {
+                state.connecting = false;
+                state.connection_error = error;
+                state.models.clear();
+            }
+            ChatAction::SwitchModel(model) => {
+                let active = state.active;
+                if let Some(conv) = state.conversation_mut(active) {
+                    conv.model = model;
+                }
+            }
+            ChatAction::ToggleThinking => {
+                let active = state.active;
+                if let Some(conv) = state.conversation_mut(active) {
+                    conv.think = !conv.think;
+                }
+            }
+            ChatAction::NewConversation => {
+                let id = state.next_id;
+                state.next_id += 1;
+                let model = state.default_model();
+                state.conversations.insert(0, Conversation::new(id, model));
+                state.active = id;
+            }
+            ChatAction::SelectConversation(id) => {
+                if state.conversation(id).is_some() {
+                    state.active = id;
+                }
+            }
+            ChatAction::DeleteConversation(id) => {
+                state.conversations.retain(|c| c.id != id || c.generating());
+                if state.conversations.is_empty() {
+                    let id = state.next_id;
+                    state.next_id += 1;
+                    let model = state.models.first().cloned().unwrap_or_default();
+                    state.conversations.push(Conversation::new(id, model));
+                }
+                if state.active_conversation().is_none() {
+                    state.active = state.conversations[0].id;
+                }
+            }
+            ChatAction::SetTitle { id, title } => {
+                if let Some(conv) = state.conversation_mut(id).filter(|c| !c.title_edited) {
+                    conv.title = title;
+                }
+            }
+            ChatAction::RenameConversation { id, title } => {
+                if let Some(conv) = state.conversation_mut(id) {
+                    conv.title = title.trim().chars().take(titles::MAX_TITLE_CHARS).collect();
+                    conv.title_edited = !conv.title.is_empty();
+                }
+            }
+            ChatAction::SetTitleModel(model) => state.title_model = model,
+            ChatAction::MemoriesLoaded(memories) => {
+                state.memories = memories;
+                state.memories_loaded = true;
+            }
+            ChatAction::ResolveProposal { id, text } => {
+                if let Some(conv) = state.conversation_mut(id) {
+                    conv.proposals.retain(|p| *p != text);
+                }
+            }
+            ChatAction::Submit {
+                id,
+                reply_id,
+                prompt,
+                system,
+            } => {
+                if let Some(conv) = state.conversation_mut(id).filter(|c| !c.generating()) {
+                    conv.system = system;
+                    Rc::make_mut(&mut conv.messages).push(Rc::new(prompt));
+                    conv.pending = None;
+                    conv.streaming = Some(reply_id);
+                }
+            }
+            ChatAction::StreamChunk {
+                id,
+                reply_id,
+                reply,
+            } => {
+                if let Some(conv) = state.streaming_mut(id, reply_id) {
+                    conv.pending = Some(reply);
+                }
+            }
+            ChatAction::Done {
+                id,
+                reply_id,
+                reply,
+                proposals,
+            } => {
+                let saved = state.memories.clone();
+                if let Some(conv) = state.streaming_mut(id, reply_id) {
+                    let known = |p: &String| {
+                        saved.iter().any(|m| m.text.eq_ignore_ascii_case(p))
+                            || conv.proposals.iter().any(|q| q.eq_ignore_ascii_case(p))
+                    };
+                    let fresh: Vec<String> = proposals.into_iter().filter(|p| !known(p)).collect();
+                    conv.proposals.extend(fresh);
+                    conv.finish_reply(Some(reply));
+                }
+            }
+            ChatAction::Error {
+                id,
+                reply_id,
+                reply,
+                error,
+            } => {
+                if let Some(conv) = state.streaming_mut(id, reply_id) {
+                    conv.finish_reply(Some(reply));
+                    Rc::make_mut(&mut conv.messages).push(Rc::new(Message::system(error)));
+                }
+            }
+            ChatAction::Cancel(id) => {
+                if let Some(conv) = state.conversation_mut(id).filter(|c| c.generating()) {
+                    let partial = conv.pending.take();
+                    conv.finish_reply(partial);
+                }
+            }
+        }
+
+        Rc::new(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reduce(state: ChatState, action: ChatAction) -> ChatState {
+        (*Rc::new(state).reduce(action)).clone()
+    }
+
+    fn run(actions: Vec<ChatAction>) -> ChatState {
+        let mut state = ChatState::default();
+        state.conversations[0].model = "llama3".to_string();
+        actions.into_iter().fold(state, reduce)
+    }
+
+    fn submit(id: u32, reply_id: u64, text: &str) -> ChatAction {
+        ChatAction::Submit {
+            id,
+            reply_id,
+            prompt: Message::new("user", text),
+            system: None,
+        }
+    }
+
+    fn chunk(id: u32, reply_id: u64, text: &str) -> ChatAction {
+        ChatAction::StreamChunk {
+            id,
+            reply_id,
+            reply: Message::new("assistant", text),
+        }
+    }
+
+    fn done(id: u32, reply_id: u64, text: &str) -> ChatAction {
+        ChatAction::Done {
+            id,
+            reply_id,
+            reply: Message::new("assistant", text),
+            proposals: Vec::new(),
+        }
+    }
+
+    fn contents(conv: &Conversation) -> Vec<&str> {
+        conv.messages.iter().map(|m| m.content.as_str()).collect()
+    }
+
+    #[test]
+    fn a_reply_streams_then_finishes() {
+        let state = run(vec![
+            submit(1, 1, "Hi"),
+            chunk(1, 1, "Hel"),
+            chunk(1, 1, "Hello"),
+        ]);
+        let conv = &state.conversations[0];
+        assert!(conv.generating());
+        assert_eq!(conv.pending.as_ref().unwrap().content, "Hello");
+        assert_eq!(state.generating_count(), 1);
+
+        let state = reduce(state, done(1, 1, "Hello!"));
+        let conv = &state.conversations[0];
+        assert!(!conv.generating());
+        assert!(conv.pending.is_none());
+        assert_eq!(contents(conv), vec!["Hi", "Hello!"]);
+    }
+
+    #[test]
+    fn a_second_submit_waits_for_the_first_reply() {
+        let state = run(vec![
+            submit(1, 1, "One"),
+            submit(1, 2, "Two"),
+            chunk(1, 2, "stray"),
+        ]);
+        let conv = &state.conversations[0];
+        assert_eq!(conv.streaming, Some(1));
+        assert_eq!(contents(conv), vec!["One"]);
+        assert!(conv.pending.is_none());
+    }
+
+    #[test]
+    fn cancel_keeps_the_partial_reply_and_drops_late_updates() {
+        let state = run(vec![
+            submit(1, 1, "Tell me a story"),
+            chunk(1, 1, "Once upon"),
+            ChatAction::Cancel(1),
+            chunk(1, 1, "Once upon a time"),
+            done(1, 1, "Once upon a time, the end."),
+        ]);
+        let conv = &state.conversations[0];
+        assert!(!conv.generating());
+        assert_eq!(contents(conv), vec!["Tell me a story", "Once upon"]);
+
+        let state = run(vec![submit(1, 1, "Hi"), ChatAction::Cancel(1)]);
+        assert_eq!(contents(&state.conversations[0]), vec!["Hi"]);
+    }
+
+    #[test]
+    fn a_stale_reply_does_not_finish_the_next_one() {
+        let state = run(vec![
+            submit(1, 1, "One"),
+            ChatAction::Cancel(1),
+            submit(1, 2, "Two"),
+            chunk(1, 1, "late"),
+            done(1, 1, "late"),
+        ]);
+        let conv = &state.conversations[0];
+        assert_eq!(conv.streaming, Some(2));
+        assert!(conv.pending.is_none());
+        assert_eq!(contents(conv), vec!["One", "Two"]);
+    }
+
+    #[test]
+    fn replies_in_two_conversations_interleave() {
+        let state = run(vec![
+            submit(1, 1, "First"),
+            ChatAction::NewConversation,
+            submit(2, 2, "Second"),
+            chunk(1, 1, "a"),
+            chunk(2, 2, "b"),
+            done(2, 2, "B"),
+            done(1, 1, "A"),
+        ]);
+        assert_eq!(state.active, 2);
+        assert_eq!(contents(state.conversation(1).unwrap()), vec!["First", "A"]);
+        assert_eq!(
+            contents(state.conversation(2).unwrap()),
+            vec!["Second", "B"]
+        );
+        assert_eq!(state.generating_count(), 0);
+    }
+
+    #[test]
+    fn an_error_keeps_the_partial_reply_and_adds_a_notice() {
+        let state = run(vec![
+            submit(1, 1, "Hi"),
+            ChatAction::Error {
+                id: 1,
+                reply_id: 1,
+                reply: Message::new("assistant", "Hel"),
+                error: "connection reset".to_string(),
+            },
+        ]);
+        let conv = &state.conversations[0];
+        assert!(!conv.generating());
+        assert_eq!(contents(conv), vec!["Hi", "Hel", "connection reset"]);
+        assert_eq!(conv.messages[2].role, "system");
+
+        let state = run(vec![
+            submit(1, 1, "Hi"),
+            ChatAction::Error {
+                id: 1,
+                reply_id: 1,
+                reply: Message::new("assistant", ""),
+                error: "model not found".to_string(),
+            },
+        ]);
+        assert_eq!(
+            contents(&state.conversations[0]),
+            vec!["Hi", "model not found"]
+        );
+    }
+
+    #[test]
+    fn switching_model_mid_stream_leaves_the_reply_alone() {
+        let state = run(vec![
+            submit(1, 1, "Hi"),
+            chunk(1, 1, "Hel"),
+            ChatAction::SwitchModel("mistral".to_string()),
+            done(1, 1, "Hello"),
+        ]);
+        let conv = &state.conversations[0];
+        assert_eq!(conv.model, "mistral");
+        assert_eq!(contents(conv), vec!["Hi", "Hello"]);
+    }
+
+    #[test]
+    fn a_streaming_conversation_cannot_be_deleted() {
+        let state = run(vec![submit(1, 1, "Hi"), ChatAction::DeleteConversation(1)]);
+        assert_eq!(state.conversations.len(), 1);
+        assert_eq!(state.conversations[0].id, 1);
+
+        let state = reduce(state, done(1, 1, "Hello"));
+        let state = reduce(state, ChatAction::DeleteConversation(1));
+        assert_eq!(state.conversations.len(), 1);
+        assert_ne!(state.conversations[0].id, 1);
+        assert_eq!(state.active, state.conversations[0].id);
+    }
+
+    #[test]
+    fn generated_titles_do_not_replace_a_rename() {
+        let state = run(vec![
+            ChatAction::RenameConversation {
+                id: 1,
+                title: "  Trip plans ".to_string(),
+            },
+            ChatAction::SetTitle {
+                id: 1,
+                title: "Generated".to_string(),
+            },
+        ]);
+        assert_eq!(state.conversations[0].title, "Trip plans");
+
+        let state = reduce(
+            state,
+            ChatAction::RenameConversation {
+                id: 1,
+                title: String::new(),
+            },
+        );
+        let state = reduce(
+            state,
+            ChatAction::SetTitle {
+                id: 1,
+                title: "Generated".to_string(),
+            },
+        );
+        assert_eq!(state.conversations[0].title, "Generated");
+    }
+
+    #[test]
+    fn proposals_skip_facts_already_known() {
+        let mut state = run(vec![submit(1, 1, "I use Vim")]);
+        state.memories = vec![Memory {
+            id: 1,
+            text: "Prefers tabs".to_string(),
+            created_at: 0,
+        }];
+        state.conversations[0].proposals = vec!["Lives in Oslo".to_string()];
+        let state = reduce(
+            state,
+            ChatAction::Done {
+                id: 1,
+                reply_id: 1,
+                reply: Message::new("assistant", "Noted."),
+                proposals: vec![
+                    "prefers tabs".to_string(),
+                    "lives in oslo".to_string(),
+                    "Uses Vim".to_string(),
+                ],
+            },
+        );
+        assert_eq!(
+            state.conversations[0].proposals,
+            vec!["Lives in Oslo", "Uses Vim"]
+        );
+    }
+
+    #[test]
+    fn streamed_chunks_share_the_history() {
+        let state = run(vec![submit(1, 1, "Hi"), chunk(1, 1, "Hel")]);
+        let before = state.conversations[0].messages.clone();
+        let state = reduce(state, chunk(1, 1, "Hello"));
+        assert!(Rc::ptr_eq(&before, &state.conversations[0].messages));
+
+        let state = reduce(state, done(1, 1, "Hello!"));
+        assert!(!Rc::ptr_eq(&before, &state.conversations[0].messages));
+        assert_eq!(before.len(), 1);
+    }
+}
@@ -0,0 +1,480 @@
+//! Subtitles for the video player: SubRip and WebVTT files parsed into cues, shifted
+//! by a timing offset and written back out as the WebVTT a `<track>` plays, plus
+//! the options for how they look.
+
+use std::rc::Rc;
+
+use serde::Serialize;
+use yew::Reducible;
+
+use crate::tauri;
+
+/// The furthest subtitles can be shifted either way, in seconds.
+pub const MAX_OFFSET: f64 = 600.0;
+
+/// Settings a WebVTT cue may carry after its timing. SubRip's `X1:` and friends
+/// aren't among them and are dropped.
+const CUE_SETTINGS: &[&str] = &["vertical", "line", "position", "size", "align", "region"];
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cue {
+    /// Seconds.
+    pub start: f64,
+    pub end: f64,
+    /// WebVTT cue settings, such as `align:start line:0`.
+    pub settings: String,
+    pub text: String,
+}
+
+/// Seconds from `01:02:03,456` (SubRip) or `01:02:03.456` / `02:03.456` (WebVTT).
+fn parse_timestamp(text: &str) -> Option<f64> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    let (seconds, whole) = parts.split_last()?;
+    if whole.is_empty() || whole.len() > 2 {
+        return None;
+    }
+    let seconds: f64 = seconds.replace(',', ".").parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    let mut total = 0.0;
+    for part in whole {
+        total = total * 60.0 + part.parse::<u32>().ok()? as f64;
+    }
+    Some(total * 60.0 + seconds)
+}
+
+/// `hh:mm:ss.mmm`, as WebVTT writes times.
+fn format_timestamp(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// A line of cue text without the markup WebVTT doesn't know: SubRip's `<font>`
+/// tags and the `{\an8}` style overrides carried over from ASS.
+fn clean_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(at) = rest.find(['<', '{']) {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let tag = if rest.starts_with('{') && rest[1..].starts_with('\\') {
+            rest.find('}')
+        } else {
+            let name = rest[1..].trim_start_matches('/');
+            let is_font = name.len() >= 4 && name[..4].eq_ignore_ascii_case("font");
+            is_font.then(|| rest.find('>')).flatten()
+        };
+        match tag {
+            Some(end) => rest = &rest[end + 1..],
+            None => {
+                out.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The cues of a SubRip or WebVTT file. Headers, notes, style blocks and cues whose
+/// timing can't be read are skipped.
+pub fn parse(text: &str) -> Vec<Cue> {
+    let text = text
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+    let mut cues = Vec::new();
+    for block in text.split("\n\n") {
+        let lines: Vec<&str> = block
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        // The timing line follows an optional cue number or identifier.
+        let Some(timing_at) = lines.iter().take(2).position(|line| line.contains("-->")) else {
+            continue;
+        };
+        let (start, rest) = lines[timing_at].split_once("-->").unwrap();
+        let mut rest = rest.split_whitespace();
+        let (Some(start), Some(end)) = (
+            parse_timestamp(start),
+            rest.next().and_then(parse_timestamp),
+        ) else {
+            continue;
+        };
+        let settings: Vec<&str> = rest
+            .filter(|setting| {
+                setting
+                    .split_once(':')
+                    .is_some_and(|(name, _)| CUE_SETTINGS.contains(&name))
+            })
+            .collect();
+        let text: Vec<String> = lines[timing_at + 1..]
+            .iter()
+            .map(|line| clean_line(line))
+            .collect();
+        cues.push(Cue {
+            start,
+            end: end.max(start),
+            settings: settings.join(" "),
+            text: text.join("\n"),
+        });
+    }
+    cues
+}
+
+/// `cues` as a WebVTT file, each moved by `offset` seconds. Cues shifted to before
+/// the start are dropped, or cut short if they're still showing at zero.
+pub fn to_vtt(cues: &[Cue], offset: f64) -> String {
+    let mut out = String::from("WEBVTT\n");
+    for cue in cues {
+        let (start, end) = ((cue.start + offset).max(0.0), cue.end + offset);
+        if end <= 0.0 {
+            continue;
+        }
+        out.push('\n');
+        out.push_str(&format_timestamp(start));
+        out.push_str(" --> ");
+        out.push_str(&format_timestamp(end));
+        if !cue.settings.is_empty() {
+            out.push(' ');
+            out.push_str(&cue.settings);
+        }
+        out.push('\n');
+        // "-->" would end the cue text early.
+        out.push_str(&cue.text.replace("-->", "--&gt;"));
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum SubtitleSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Huge,
+}
+
+impl SubtitleSize {
+    pub const ALL: [Self; 4] = [Self::Small, Self::Medium, Self::Large, Self::Huge];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Small => "Small",
+            Self::Medium => "Medium",
+            Self::Large => "Large",
+            Self::Huge => "Huge",
+        }
+    }
+
+    fn percent(self) -> u32 {
+        match self {
+            Self::Small => 80,
+            Self::Medium => 100,
+            Self::Large => 130,
+            Self::Huge => 170,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum SubtitleColor {
+    #[default]
+    White,
+    Yellow,
+}
+
+impl SubtitleColor {
+    pub const ALL: [Self; 2] = [Self::White, Self::Yellow];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::White => "White",
+            Self::Yellow => "Yellow",
+        }
+    }
+
+    fn css(self) -> &'static str {
+        match self {
+            Self::White => "#ffffff",
+            Self::Yellow => "#ffe14d",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum SubtitleBackground {
+    None,
+    #[default]
+    Shaded,
+    Solid,
+}
+
+impl SubtitleBackground {
+    pub const ALL: [Self; 3] = [Self::None, Self::Shaded, Self::Solid];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Shaded => "Shaded",
+            Self::Solid => "Solid",
+        }
+    }
+
+    fn css(self) -> &'static str {
+        match self {
+            Self::None => "transparent",
+            Self::Shaded => "rgba(0, 0, 0, 0.6)",
+            Self::Solid => "#000000",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct SubtitleStyle {
+    pub size: SubtitleSize,
+    pub color: SubtitleColor,
+    pub background: SubtitleBackground,
+}
+
+impl SubtitleStyle {
+    /// A stylesheet for the cues of the videos matched by `selector`.
+    pub fn css(&self, selector: &str) -> String {
+        let outline = match self.background {
+            // Without a box behind them the letters need an edge to stay readable.
+            SubtitleBackground::None => " text-shadow: 0 0 3px #000, 0 0 3px #000;",
+            _ => "",
+        };
+        format!(
+            "{}::cue {{ font-size: {}%; color: {}; background-color: {};{} }}",
+            selector,
+            self.size.percent(),
+            self.color.css(),
+            self.background.css(),
+            outline
+        )
+    }
+}
+
+/// A subtitle file that was loaded for the current video.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SubtitleSource {
+    pub label: String,
+    pub cues: Rc<Vec<Cue>>,
+}
+
+impl SubtitleSource {
+    pub fn new(label: impl Into<String>, text: &str) -> Self {
+        Self {
+            label: label.into(),
+            cues: Rc::new(parse(text)),
+        }
+    }
+}
+
+/// The subtitles of the video playing: the tracks loaded for it, which is shown and
+/// how, and how far it's shifted.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Subtitles {
+    /// The queue item the tracks belong to.
+    pub item: Option<u32>,
+    pub sources: Vec<SubtitleSource>,
+    /// `None` when subtitles are off.
+    pub selected: Option<usize>,
+    /// Seconds; positive shows them later.
+    pub offset: f64,
+    pub style: SubtitleStyle,
+}
+
+pub enum SubtitleAction {
+    /// Another item started playing. Its tracks start empty; the style stays.
+    Reset(Option<u32>),
+    /// Files found next to `item`, the first of them shown unless another is.
+    Found {
+        item: u32,
+        sources: Vec<SubtitleSource>,
+    },
+    /// A file the user loaded, shown straight away.
+    Add(SubtitleSource),
+    Select(Option<usize>),
+    Shift(f64),
+    ResetOffset,
+    Style(SubtitleStyle),
+}
+
+impl Subtitles {
+    pub fn current(&self) -> Option<&SubtitleSource> {
+        self.sources.get(self.selected?)
+    }
+}
+
+impl Reducible for Subtitles {
+    type Action = SubtitleAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut subtitles = (*self).clone();
+        match action {
+            SubtitleAction::Reset(item) => {
+                subtitles = Self {
+                    item,
+                    style: self.style,
+                    ..Self::default()
+                };
+            }
+            SubtitleAction::Found { item, sources } => {
+                // Files found for a video that's no longer playing are dropped.
+                if subtitles.item != Some(item) || sources.is_empty() {
+                    return self;
+                }
+                if subtitles.selected.is_none() {
+                    subtitles.selected = Some(subtitles.sources.len());
+                }
+                subtitles.sources.extend(sources);
+            }
+            SubtitleAction::Add(source) => {
+                subtitles.selected = Some(subtitles.sources.len());
+                subtitles.sources.push(source);
+            }
+            SubtitleAction::Select(selected) => {
+                subtitles.selected = selected.filter(|index| *index < subtitles.sources.len());
+            }
+            SubtitleAction::Shift(seconds) => {
+                // Kept to whole milliseconds so repeated nudges don't drift.
+                let offset = ((subtitles.offset + seconds) * 1000.0).round() / 1000.0;
+                subtitles.offset = offset.clamp(-MAX_OFFSET, MAX_OFFSET);
+            }
+            SubtitleAction::ResetOffset => subtitles.offset = 0.0,
+            SubtitleAction::Style(style) => subtitles.style = style,
+        }
+        Rc::new(subtitles)
+    }
+}
+
+#[derive(Serialize)]
+struct VideoArgs<'a> {
+    video: &'a str,
+}
+
+#[derive(Serialize)]
+struct PathArgs<'a> {
+    path: &'a str,
+}
+
+#[derive(Serialize)]
+struct NoArgs {}
+
+/// Subtitle files next to the video at `video` that share its name.
+pub async fn find(video: &str) -> Result<Vec<String>, String> {
+    tauri::invoke("subtitles_find", &VideoArgs { video }).await
+}
+
+/// Asks for a subtitle file. Returns its path, or `None` if the user cancelled.
+pub async fn open_dialog() -> Result<Option<String>, String> {
+    tauri::invoke("subtitles_open_dialog", &NoArgs {}).await
+}
+
+pub async fn read(path: &str) -> Result<String, String> {
+    tauri::invoke("subtitles_read", &PathArgs { path }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "\u{feff}1\r\n00:00:01,000 --> 00:00:03,500 X1:100 X2:600\r\n<font color=\"#ff0000\">Hello</font>, <i>world</i>\r\n\r\n2\r\n00:01:02,250 --> 00:01:04,000\r\n{\\an8}Two lines\r\nof text --> here\r\n\r\n3\r\nnot a timing line\r\nskipped\r\n";
+
+    #[test]
+    fn srt_converts_to_vtt() {
+        assert_eq!(
+            to_vtt(&parse(SRT), 0.0),
+            "WEBVTT\n\
+             \n00:00:01.000 --> 00:00:03.500\nHello, <i>world</i>\n\
+             \n00:01:02.250 --> 00:01:04.000\nTwo lines\nof text --&gt; here\n"
+        );
+    }
+
+    #[test]
+    fn vtt_is_read_with_its_settings_and_short_times() {
+        let vtt = "WEBVTT - Example\n\nNOTE a comment\n\nSTYLE\n::cue { color: red }\n\nintro\n00:05.000 --> 00:07.5 align:start line:0\n<b>Hi</b> {there}\n";
+        assert_eq!(
+            parse(vtt),
+            vec![Cue {
+                start: 5.0,
+                end: 7.5,
+                settings: "align:start line:0".to_string(),
+                text: "<b>Hi</b> {there}".to_string(),
+            }]
+        );
+        assert_eq!(parse_timestamp("1:02:03,5"), Some(3723.5));
+        assert_eq!(parse_timestamp("00:61.000"), None);
+        assert_eq!(parse_timestamp("12"), None);
+    }
+
+    #[test]
+    fn offsets_shift_and_trim_cues() {
+        let cues = parse(SRT);
+        let earlier = to_vtt(&cues, -2.0);
+        assert!(earlier.contains("\n00:00:00.000 --> 00:00:01.500\n"));
+        assert!(earlier.contains("\n00:01:00.250 --> 00:01:02.000\n"));
+        let much_earlier = to_vtt(&cues, -10.0);
+        assert!(!much_earlier.contains("Hello"));
+        assert!(to_vtt(&cues, 0.75).contains("\n00:00:01.750 --> 00:00:04.250\n"));
+    }
+
+    #[test]
+    fn tracks_follow_the_playing_video() {
+        let source = |label: &str| SubtitleSource::new(label, SRT);
+        let subtitles = Rc::new(Subtitles::default())
+            .reduce(SubtitleAction::Style(SubtitleStyle {
+                size: SubtitleSize::Large,
+                ..SubtitleStyle::default()
+            }))
+            .reduce(SubtitleAction::Reset(Some(1)))
+            .reduce(SubtitleAction::Found {
+                item: 2,
+                sources: vec![source("stale.srt")],
+            })
+            .reduce(SubtitleAction::Found {
+                item: 1,
+                sources: vec![source("film.srt"), source("film.de.srt")],
+            });
+        assert_eq!(subtitles.current().unwrap().label, "film.srt");
+        assert_eq!(subtitles.style.size, SubtitleSize::Large);
+
+        let subtitles = subtitles
+            .reduce(SubtitleAction::Add(source("mine.vtt")))
+            .reduce(SubtitleAction::Shift(0.1))
+            .reduce(SubtitleAction::Shift(0.2));
+        assert_eq!(subtitles.current().unwrap().label, "mine.vtt");
+        assert_eq!(subtitles.offset, 0.3);
+        let vtt = to_vtt(&subtitles.current().unwrap().cues, subtitles.offset);
+        assert!(vtt.contains("00:00:01.300 --> 00:00:03.800"));
+
+        let off = subtitles.reduce(SubtitleAction::Select(None));
+        assert_eq!(off.current(), None);
+        let reset = off.reduce(SubtitleAction::Reset(Some(3)));
+        assert!(reset.sources.is_empty());
+        assert_eq!((reset.offset, reset.style.size), (0.0, SubtitleSize::Large));
+    }
+
+    #[test]
+    fn styles_become_cue_css() {
+        let style = SubtitleStyle {
+            size: SubtitleSize::Huge,
+            color: SubtitleColor::Yellow,
+            background: SubtitleBackground::None,
+        };
+        assert_eq!(
+            style.css("video.player"),
+            "video.player::cue { font-size: 170%; color: #ffe14d; background-color: transparent; text-shadow: 0 0 3px #000, 0 0 3px #000; }"
+        );
+    }
+}
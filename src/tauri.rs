@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::{Function, Reflect};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn invoke_raw(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = convertFileSrc)]
+    fn convert_file_src(path: &str, protocol: &str) -> String;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen, catch)]
+    async fn listen_raw(
+        event: &str,
+        handler: &Closure<dyn FnMut(JsValue)>,
+    ) -> Result<JsValue, JsValue>;
+}
+
+/// Whether the frontend runs inside the desktop app rather than a plain browser
+/// (`trunk serve`), where backend commands are unavailable.
+pub fn is_tauri() -> bool {
+    web_sys::window()
+        .map(|w| Reflect::has(&w, &"__TAURI__".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+/// A `media://` URL the webview can load a local file from. Only files the backend has
+/// let through, such as ones dropped on the window, can be read this way.
+pub fn file_url(path: &str) -> String {
+    convert_file_src(path, "media")
+}
+
+/// Calls a backend command. Argument structs should use camelCase field names, which
+/// is how Tauri maps them onto the command's snake_case parameters.
+pub async fn invoke<A: Serialize, R: DeserializeOwned>(cmd: &str, args: &A) -> Result<R, String> {
+    if !is_tauri() {
+        return Err(format!("{} is only available in the desktop app", cmd));
+    }
+    let args = serde_wasm_bindgen::to_value(args).map_err(|e| e.to_string())?;
+    let result = invoke_raw(cmd, args)
+        .await
+        .map_err(|e| e.as_string().unwrap_or_else(|| format!("{:?}", e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| e.to_string())
+}
+
+#[derive(Default)]
+struct ListenerState {
+    unlisten: Option<Function>,
+    /// Kept alive for as long as the backend may call it.
+    closure: Option<Closure<dyn FnMut(JsValue)>>,
+    dropped: bool,
+}
+
+/// An event subscription; dropping it unsubscribes.
+#[derive(Default)]
+pub struct Listener(Rc<RefCell<ListenerState>>);
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let mut state = self.0.borrow_mut();
+        state.dropped = true;
+        if let Some(unlisten) = state.unlisten.take() {
+            let _ = unlisten.call0(&JsValue::NULL);
+        }
+    }
+}
+
+/// Subscribes to a backend event until the returned [`Listener`] is dropped.
+pub fn listen<T: DeserializeOwned + 'static>(
+    event: &'static str,
+    handler: impl Fn(T) + 'static,
+) -> Listener {
+    let listener = Listener::default();
+    if !is_tauri() {
+        return listener;
+    }
+    let closure = Closure::<dyn FnMut(JsValue)>::new(move |raw: JsValue| {
+        let payload = Reflect::get(&raw, &"payload".into()).unwrap_or(JsValue::NULL);
+        match serde_wasm_bindgen::from_value(payload) {
+            Ok(value) => handler(value),
+            Err(e) => log::warn!("Ignoring malformed {} event: {}", event, e),
+        }
+    });
+
+    let state = listener.0.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        match listen_raw(event, &closure).await {
+            Ok(unlisten) => {
+                let unlisten: Function = unlisten.unchecked_into();
+                let mut state = state.borrow_mut();
+                // Registration finishes asynchronously, possibly after the listener
+                // was already dropped.
+                if state.dropped {
+                    let _ = unlisten.call0(&JsValue::NULL);
+                    return;
+                }
+                state.unlisten = Some(unlisten);
+                state.closure = Some(closure);
+            }
+            Err(e) => log::warn!("Failed to listen for {}: {:?}", event, e),
+        }
+    });
+    listener
+}
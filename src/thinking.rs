@@ -0,0 +1,233 @@
+use crate::ollama::Message;
+
+const OPEN_TAG: &str = "<think>";
+const CLOSE_TAG: &str = "</think>";
+
+/// Splits streamed content into answer text and `<think>...</think>` reasoning.
+/// Tags may be cut anywhere between chunks, so a trailing partial tag is held back
+/// until the next chunk decides what it is.
+#[derive(Default)]
+pub struct ThinkSplitter {
+    in_think: bool,
+    pending: String,
+}
+
+impl ThinkSplitter {
+    /// Returns the `(content, thinking)` text that became final with this chunk.
+    pub fn push(&mut self, text: &str) -> (String, String) {
+        let mut buf = std::mem::take(&mut self.pending);
+        buf.push_str(text);
+
+        let mut content = String::new();
+        let mut thinking = String::new();
+        let mut rest = buf.as_str();
+
+        loop {
+            let tag = if self.in_think { CLOSE_TAG } else { OPEN_TAG };
+            let sink = if self.in_think {
+                &mut thinking
+            } else {
+                &mut content
+            };
+
+            if let Some(pos) = rest.find(tag) {
+                sink.push_str(&rest[..pos]);
+                rest = &rest[pos + tag.len()..];
+                self.in_think = !self.in_think;
+                continue;
+            }
+
+            let keep = partial_tag_len(rest, tag);
+            sink.push_str(&rest[..rest.len() - keep]);
+            self.pending = rest[rest.len() - keep..].to_string();
+            break;
+        }
+
+        (content, thinking)
+    }
+
+    /// Flushes whatever is still held back once the stream has ended.
+    pub fn finish(&mut self) -> (String, String) {
+        let pending = std::mem::take(&mut self.pending);
+        if self.in_think {
+            (String::new(), pending)
+        } else {
+            (pending, String::new())
+        }
+    }
+}
+
+/// Length of the longest suffix of `text` that is a proper prefix of `tag`.
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&n| text.ends_with(&tag[..n]))
+        .unwrap_or(0)
+}
+
+/// Assembles an assistant reply from streamed deltas, collecting reasoning from both
+/// the `thinking` field and inline `<think>` tags and timing how long it lasted.
+pub struct ReplyBuilder {
+    splitter: ThinkSplitter,
+    message: Message,
+    thinking_started: Option<f64>,
+}
+
+impl Default for ReplyBuilder {
+    fn default() -> Self {
+        Self {
+            splitter: ThinkSplitter::default(),
+            message: Message::new("assistant", ""),
+            thinking_started: None,
+        }
+    }
+}
+
+impl ReplyBuilder {
+    pub fn push(&mut self, delta: &Message, now: f64) {
+        let (content, mut thinking) = self.splitter.push(&delta.content);
+        if let Some(extra) = &delta.thinking {
+            thinking.push_str(extra);
+        }
+        self.append(&content, &thinking, now);
+    }
+
+    pub fn finish(mut self, now: f64) -> Message {
+        let (content, thinking) = self.splitter.finish();
+        self.append(&content, &thinking, now);
+        self.stop_thinking(now);
+        self.message
+    }
+
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    fn append(&mut self, content: &str, thinking: &str, now: f64) {
+        if !thinking.is_empty() {
+            self.thinking_started.get_or_insert(now);
+            self.message
+                .thinking
+                .get_or_insert_with(String::new)
+                .push_str(thinking);
+        }
+        if !content.trim().is_empty() {
+            self.stop_thinking(now);
+        }
+        if self.message.content.is_empty() {
+            self.message.content.push_str(content.trim_start());
+        } else {
+            self.message.content.push_str(content);
+        }
+    }
+
+    fn stop_thinking(&mut self, now: f64) {
+        if let (Some(started), None) = (self.thinking_started, self.message.thinking_ms) {
+            self.message.thinking_ms = Some(now - started);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `chunks` through a splitter and joins everything it gives back.
+    fn split(chunks: &[&str]) -> (String, String) {
+        let mut splitter = ThinkSplitter::default();
+        let (mut content, mut thinking) = (String::new(), String::new());
+        for chunk in chunks {
+            let (c, t) = splitter.push(chunk);
+            content.push_str(&c);
+            thinking.push_str(&t);
+        }
+        let (c, t) = splitter.finish();
+        content.push_str(&c);
+        thinking.push_str(&t);
+        (content, thinking)
+    }
+
+    fn delta(content: &str, thinking: Option<&str>) -> Message {
+        Message {
+            thinking: thinking.map(str::to_string),
+            ..Message::new("assistant", content)
+        }
+    }
+
+    #[test]
+    fn tags_split_across_chunks_are_recognised() {
+        assert_eq!(
+            split(&["<thi", "nk>Let me see", "</th", "ink>The answer"]),
+            ("The answer".to_string(), "Let me see".to_string())
+        );
+        assert_eq!(
+            split(&["<", "t", "h", "i", "n", "k", ">", "a", "<", "/", "think", ">", "b"]),
+            ("b".to_string(), "a".to_string())
+        );
+    }
+
+    #[test]
+    fn partial_tags_are_held_back_until_decided() {
+        let mut splitter = ThinkSplitter::default();
+        assert_eq!(
+            splitter.push("Hello <thi"),
+            ("Hello ".to_string(), String::new())
+        );
+        // Not a tag after all.
+        assert_eq!(splitter.push("s>"), ("<this>".to_string(), String::new()));
+        // A lone `<` at the end of the stream is just text.
+        assert_eq!(splitter.push(" 1 <"), (" 1 ".to_string(), String::new()));
+        assert_eq!(splitter.finish(), ("<".to_string(), String::new()));
+    }
+
+    #[test]
+    fn unclosed_thinking_is_kept_as_thinking() {
+        assert_eq!(
+            split(&["<think>Still going", " and going</thi"]),
+            (String::new(), "Still going and going</thi".to_string())
+        );
+    }
+
+    #[test]
+    fn the_thinking_field_is_collected_with_inline_tags() {
+        let mut reply = ReplyBuilder::default();
+        reply.push(&delta("", Some("Field reasoning. ")), 0.0);
+        reply.push(&delta("<think>Tag reasoning.</think>", None), 10.0);
+        reply.push(&delta("\n\nAnswer", None), 20.0);
+        let message = reply.finish(30.0);
+        assert_eq!(
+            message.thinking.as_deref(),
+            Some("Field reasoning. Tag reasoning.")
+        );
+        // Leading whitespace left behind by the tags is dropped.
+        assert_eq!(message.content, "Answer");
+    }
+
+    #[test]
+    fn thinking_is_timed_until_the_answer_starts() {
+        let mut reply = ReplyBuilder::default();
+        reply.push(&delta("", Some("Hmm")), 1000.0);
+        reply.push(&delta("", Some(" hmm")), 1500.0);
+        // Whitespace doesn't end the thinking.
+        reply.push(&delta(" ", None), 1800.0);
+        assert_eq!(reply.message().thinking_ms, None);
+        reply.push(&delta("Yes", None), 2250.0);
+        assert_eq!(reply.message().thinking_ms, Some(1250.0));
+        let message = reply.finish(5000.0);
+        assert_eq!(message.thinking_ms, Some(1250.0));
+    }
+
+    #[test]
+    fn thinking_to_the_end_is_timed_by_finish() {
+        let mut reply = ReplyBuilder::default();
+        reply.push(&delta("<think>Never ", None), 100.0);
+        reply.push(&delta("done", None), 400.0);
+        let message = reply.finish(700.0);
+        assert_eq!(message.thinking.as_deref(), Some("Never done"));
+        assert_eq!(message.thinking_ms, Some(600.0));
+        assert_eq!(message.content, "");
+
+        let plain = ReplyBuilder::default();
+        assert_eq!(plain.finish(50.0).thinking_ms, None);
+    }
+}
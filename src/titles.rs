@@ -0,0 +1,139 @@
+use crate::ollama::{ChatRequest, Message};
+use crate::thinking::ThinkSplitter;
+
+/// Titles longer than this are cut at a word boundary.
+pub const MAX_TITLE_CHARS: usize = 48;
+const FALLBACK_WORDS: usize = 6;
+/// How much of the first exchange the title model gets to see.
+const EXCERPT_CHARS: usize = 1500;
+
+const TITLE_PROMPT: &str = "Write a short title (3 to 6 words) for the conversation below. \
+Reply with the title only: no quotes, no punctuation at the end, no explanation.";
+
+fn truncate_words(text: &str, max_chars: usize) -> String {
+    let mut out = String::new();
+    for word in text.split_whitespace() {
+        let extra = if out.is_empty() { 0 } else { 1 } + word.chars().count();
+        if out.chars().count() + extra > max_chars {
+            if out.is_empty() {
+                out = word.chars().take(max_chars).collect();
+            }
+            break;
+        }
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// The first few words of `prompt`, used until (or instead of) a generated title.
+pub fn fallback_title(prompt: &str) -> String {
+    let words: Vec<&str> = prompt.split_whitespace().take(FALLBACK_WORDS).collect();
+    truncate_words(&words.join(" "), MAX_TITLE_CHARS)
+}
+
+/// Cleans up a model's answer into a single-line title. Models like to wrap titles in
+/// quotes or markdown, prefix them with "Title:" or reason first; all of that goes.
+pub fn sanitize_title(raw: &str) -> Option<String> {
+    let mut splitter = ThinkSplitter::default();
+    let (mut content, _) = splitter.push(raw);
+    content.push_str(&splitter.finish().0);
+
+    let line = content.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.trim_start_matches(['#', '*', '-', '>', ' ']);
+    let line = match line.split_once(':') {
+        Some((label, rest)) if label.trim().eq_ignore_ascii_case("title") => rest,
+        _ => line,
+    };
+    let cleaned: String = line
+        .chars()
+        .filter(|c| !matches!(c, '"' | '“' | '”' | '`' | '*' | '_') && !c.is_control())
+        .collect();
+    let cleaned = cleaned
+        .trim()
+        .trim_matches('\'')
+        .trim_end_matches(['.', '!', ',', ';', ':'])
+        .trim();
+
+    let title = truncate_words(cleaned, MAX_TITLE_CHARS);
+    (!title.is_empty()).then_some(title)
+}
+
+fn excerpt(text: &str) -> String {
+    text.chars().take(EXCERPT_CHARS).collect()
+}
+
+/// A one-shot request asking `model` to name a conversation from its first exchange.
+pub fn title_request(model: &str, prompt: &str, reply: &str) -> ChatRequest {
+    ChatRequest {
+        model: model.to_string(),
+        messages: vec![
+            Message::system(TITLE_PROMPT),
+            Message::new(
+                "user",
+                format!("User: {}\n\nAssistant: {}", excerpt(prompt), excerpt(reply)),
+            ),
+        ],
+        stream: false,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_uses_the_first_words() {
+        assert_eq!(
+            fallback_title("  How do I   reverse a linked list in Rust without unsafe?"),
+            "How do I reverse a linked"
+        );
+        assert_eq!(fallback_title(""), "");
+    }
+
+    #[test]
+    fn sanitize_strips_decoration() {
+        assert_eq!(
+            sanitize_title("\"Reversing Linked Lists.\"").as_deref(),
+            Some("Reversing Linked Lists")
+        );
+        assert_eq!(
+            sanitize_title("**Title:** Rust *borrow* checker\nbecause...").as_deref(),
+            Some("Rust borrow checker")
+        );
+        assert_eq!(
+            sanitize_title("\n\n## Sourdough Starter Tips\n").as_deref(),
+            Some("Sourdough Starter Tips")
+        );
+    }
+
+    #[test]
+    fn sanitize_drops_reasoning() {
+        assert_eq!(
+            sanitize_title("<think>The user asks about tea.</think>\nBrewing Green Tea").as_deref(),
+            Some("Brewing Green Tea")
+        );
+    }
+
+    #[test]
+    fn sanitize_limits_length_at_word_boundaries() {
+        let title =
+            sanitize_title("A very long title that goes on and on well past any reasonable length")
+                .unwrap();
+        assert!(title.chars().count() <= MAX_TITLE_CHARS);
+        assert!(
+            "A very long title that goes on and on well past any reasonable length"
+                .starts_with(&title)
+        );
+        assert!(!title.ends_with(' '));
+    }
+
+    #[test]
+    fn sanitize_rejects_empty_answers() {
+        assert_eq!(sanitize_title(""), None);
+        assert_eq!(sanitize_title("<think>hmm</think>  \n \"\""), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! Geometry of the image viewer: how the image is zoomed, panned, rotated and
+//! flipped inside its viewport, and which image pixel lies under a point.
+//!
+//! Points are in CSS pixels relative to the centre of the viewport, where the
+//! image's centre sits before it is panned.
+
+pub const MIN_ZOOM: f64 = 0.05;
+pub const MAX_ZOOM: f64 = 64.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Fit {
+    /// The whole image, as large as fits.
+    Screen,
+    /// The image's width fills the viewport, showing its top first.
+    Width,
+    /// One image pixel per CSS pixel.
+    Actual,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ImageView {
+    pub zoom: f64,
+    pub pan_x: f64,
+    pub pan_y: f64,
+    /// Clockwise, in 90° steps.
+    pub quarter_turns: u8,
+    /// Mirrored left to right on screen, whichever way the image is turned.
+    pub flip_x: bool,
+    /// Mirrored top to bottom on screen.
+    pub flip_y: bool,
+    /// The fit last chosen, applied again when the viewport or rotation changes.
+    /// `None` once the image is zoomed or panned by hand.
+    pub fit: Option<Fit>,
+}
+
+impl Default for ImageView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+            quarter_turns: 0,
+            flip_x: false,
+            flip_y: false,
+            fit: Some(Fit::Screen),
+        }
+    }
+}
+
+/// Turns `(x, y)` clockwise by `turns` quarter turns, with y pointing down.
+fn turn(x: f64, y: f64, turns: u8) -> (f64, f64) {
+    (0..turns % 4).fold((x, y), |(x, y), _| (-y, x))
+}
+
+impl ImageView {
+    /// The image's size on screen at a zoom of 1, once turned.
+    fn turned(&self, image: Size) -> Size {
+        if self.quarter_turns % 2 == 1 {
+            Size {
+                width: image.height,
+                height: image.width,
+            }
+        } else {
+            image
+        }
+    }
+
+    pub fn fitted(self, fit: Fit, image: Size, viewport: Size) -> Self {
+        let turned = self.turned(image);
+        if turned.width <= 0.0 || turned.height <= 0.0 {
+            return self;
+        }
+        let zoom = match fit {
+            Fit::Screen => (viewport.width / turned.width).min(viewport.height / turned.height),
+            Fit::Width => viewport.width / turned.width,
+            Fit::Actual => 1.0,
+        }
+        .clamp(MIN_ZOOM, MAX_ZOOM);
+        let pan_y = match fit {
+            Fit::Width => ((turned.height * zoom - viewport.height) / 2.0).max(0.0),
+            _ => 0.0,
+        };
+        Self {
+            zoom,
+            pan_x: 0.0,
+            pan_y,
+            fit: Some(fit),
+            ..self
+        }
+    }
+
+    /// Applies the chosen fit again, e.g. after the viewport was resized.
+    pub fn refitted(self, image: Size, viewport: Size) -> Self {
+        match self.fit {
+            Some(fit) => self.fitted(fit, image, viewport),
+            None => self,
+        }
+    }
+
+    /// Zooms by `factor`, keeping whatever is under `(x, y)` where it is.
+    pub fn zoomed_at(self, factor: f64, x: f64, y: f64) -> Self {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let scale = zoom / self.zoom;
+        Self {
+            zoom,
+            pan_x: x - (x - self.pan_x) * scale,
+            pan_y: y - (y - self.pan_y) * scale,
+            fit: None,
+            ..self
+        }
+    }
+
+    pub fn panned(self, dx: f64, dy: f64) -> Self {
+        Self {
+            pan_x: self.pan_x + dx,
+            pan_y: self.pan_y + dy,
+            fit: None,
+            ..self
+        }
+    }
+
+    pub fn rotated(self, clockwise: bool) -> Self {
+        let step = if clockwise { 1 } else { 3 };
+        Self {
+            quarter_turns: (self.quarter_turns + step) % 4,
+            ..self
+        }
+    }
+
+    pub fn flipped(self, horizontally: bool) -> Self {
+        if horizontally {
+            Self {
+                flip_x: !self.flip_x,
+                ..self
+            }
+        } else {
+            Self {
+                flip_y: !self.flip_y,
+                ..self
+            }
+        }
+    }
+
+    /// The CSS transform for an image whose centre starts at the viewport's centre.
+    /// Scaling comes before the rotation in the list, so flips happen on screen axes.
+    pub fn transform(&self) -> String {
+        let sign = |flip: bool| if flip { -1.0 } else { 1.0 };
+        format!(
+            "translate(-50%, -50%) translate({}px, {}px) scale({}, {}) rotate({}deg)",
+            self.pan_x,
+            self.pan_y,
+            self.zoom * sign(self.flip_x),
+            self.zoom * sign(self.flip_y),
+            self.quarter_turns as u32 * 90
+        )
+    }
+
+    /// The image pixel under `(x, y)`, if the point is over the image.
+    pub fn pixel_at(&self, x: f64, y: f64, image: Size) -> Option<(u32, u32)> {
+        let mut dx = (x - self.pan_x) / self.zoom;
+        let mut dy = (y - self.pan_y) / self.zoom;
+        if self.flip_x {
+            dx = -dx;
+        }
+        if self.flip_y {
+            dy = -dy;
+        }
+        let (ix, iy) = turn(dx, dy, 4 - self.quarter_turns % 4);
+        let (px, py) = (
+            (ix + image.width / 2.0).floor(),
+            (iy + image.height / 2.0).floor(),
+        );
+        let inside = px >= 0.0 && py >= 0.0 && px < image.width && py < image.height;
+        inside.then_some((px as u32, py as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE: Size = Size {
+        width: 400.0,
+        height: 200.0,
+    };
+    const VIEWPORT: Size = Size {
+        width: 800.0,
+        height: 300.0,
+    };
+
+    #[test]
+    fn fits_size_the_image_to_the_viewport() {
+        let view = ImageView::default();
+        assert_eq!(view.fitted(Fit::Screen, IMAGE, VIEWPORT).zoom, 1.5);
+        let width = view.fitted(Fit::Width, IMAGE, VIEWPORT);
+        assert_eq!((width.zoom, width.pan_y), (2.0, 50.0));
+        assert_eq!(view.fitted(Fit::Actual, IMAGE, VIEWPORT).zoom, 1.0);
+
+        // Turned on its side the image is 200 wide and 400 tall.
+        let turned = view.rotated(true).refitted(IMAGE, VIEWPORT);
+        assert_eq!(turned.zoom, 0.75);
+        assert_eq!(turned.rotated(false).rotated(false).quarter_turns, 3);
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_cursor() {
+        let view = ImageView::default().fitted(Fit::Actual, IMAGE, VIEWPORT);
+        let before = view.pixel_at(50.0, -20.0, IMAGE);
+        let zoomed = view.zoomed_at(3.0, 50.0, -20.0);
+        assert_eq!(zoomed.fit, None);
+        assert_eq!(zoomed.zoom, 3.0);
+        assert_eq!(zoomed.pixel_at(50.0, -20.0, IMAGE), before);
+        assert_eq!(zoomed.zoomed_at(1000.0, 0.0, 0.0).zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn pixels_are_found_through_turns_and_flips() {
+        let view = ImageView::default().fitted(Fit::Actual, IMAGE, VIEWPORT);
+        assert_eq!(view.pixel_at(0.0, 0.0, IMAGE), Some((200, 100)));
+        assert_eq!(view.pixel_at(-199.5, -99.5, IMAGE), Some((0, 0)));
+        assert_eq!(view.pixel_at(-201.0, 0.0, IMAGE), None);
+
+        // A quarter turn clockwise brings the top left corner to the top right.
+        let turned = view.rotated(true);
+        assert_eq!(turned.pixel_at(99.5, -199.5, IMAGE), Some((0, 0)));
+        assert_eq!(turned.pixel_at(-99.5, 199.5, IMAGE), Some((399, 199)));
+
+        let mirrored = view.flipped(true);
+        assert_eq!(mirrored.pixel_at(199.5, -99.5, IMAGE), Some((0, 0)));
+        let both = turned.flipped(false);
+        assert_eq!(both.pixel_at(99.5, 199.5, IMAGE), Some((0, 0)));
+
+        let panned = view.panned(10.0, 5.0);
+        assert_eq!(panned.pixel_at(10.0, 5.0, IMAGE), Some((200, 100)));
+        assert_eq!(panned.fit, None);
+    }
+}
@@ -0,0 +1,199 @@
+/// Item heights for a windowed list: measured once an item has been rendered, estimated
+/// until then.
+#[derive(Default)]
+pub struct Heights {
+    heights: Vec<f64>,
+    measured: Vec<bool>,
+}
+
+/// The items to render and the space standing in for the rest.
+#[derive(Debug, PartialEq)]
+pub struct Window {
+    pub start: usize,
+    pub end: usize,
+    /// Height of the items before `start`.
+    pub top: f64,
+    /// Height of the items from `end` on.
+    pub bottom: f64,
+}
+
+/// A rough pixel height for a chat bubble holding `text`, used until it is measured.
+pub fn estimate_height(text: &str) -> f64 {
+    const LINE_HEIGHT: f64 = 26.0;
+    const CHARS_PER_LINE: usize = 80;
+    const CHROME: f64 = 48.0;
+    let lines: usize = text
+        .lines()
+        .map(|line| line.chars().count().div_ceil(CHARS_PER_LINE).max(1))
+        .sum();
+    CHROME + lines.max(1) as f64 * LINE_HEIGHT
+}
+
+impl Heights {
+    /// Matches the list to `len` items. Heights already known are kept, so `estimate`
+    /// is only asked about new items and the last one, which may have just changed.
+    pub fn sync(&mut self, len: usize, estimate: impl Fn(usize) -> f64) {
+        self.heights.truncate(len);
+        self.measured.truncate(len);
+        for index in self.heights.len().min(len.saturating_sub(1))..len {
+            if index >= self.heights.len() {
+                self.heights.push(estimate(index));
+                self.measured.push(false);
+            } else if !self.measured[index] {
+                self.heights[index] = estimate(index);
+            }
+        }
+    }
+
+    /// Records the rendered height of item `index`, returning how much it grew.
+    pub fn measure(&mut self, index: usize, height: f64) -> f64 {
+        let Some(current) = self.heights.get_mut(index) else {
+            return 0.0;
+        };
+        self.measured[index] = true;
+        let delta = height - *current;
+        if delta.abs() < 0.5 {
+            return 0.0;
+        }
+        *current = height;
+        delta
+    }
+
+    /// The item under `offset` pixels from the top of the list.
+    pub fn index_at(&self, offset: f64) -> usize {
+        let mut bottom = 0.0;
+        for (index, height) in self.heights.iter().enumerate() {
+            bottom += height;
+            if bottom > offset {
+                return index;
+            }
+        }
+        self.heights.len()
+    }
+
+    /// Items overlapping the viewport, plus `overscan` pixels either side.
+    pub fn window(&self, scroll_top: f64, viewport: f64, overscan: f64) -> Window {
+        let from = scroll_top - overscan;
+        let to = scroll_top + viewport + overscan;
+        let mut window = Window {
+            start: self.heights.len(),
+            end: self.heights.len(),
+            top: 0.0,
+            bottom: 0.0,
+        };
+
+        let mut offset = 0.0;
+        for (index, height) in self.heights.iter().enumerate() {
+            let bottom = offset + height;
+            if bottom <= from {
+                window.top = bottom;
+            } else if offset >= to {
+                if window.end == self.heights.len() {
+                    window.end = index;
+                }
+                window.bottom += height;
+            } else if window.start == self.heights.len() {
+                window.start = index;
+            }
+            offset = bottom;
+        }
+        window.start = window.start.min(window.end);
+        window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heights(values: &[f64]) -> Heights {
+        let mut heights = Heights::default();
+        heights.sync(values.len(), |index| values[index]);
+        heights
+    }
+
+    #[test]
+    fn window_covers_the_viewport_and_overscan() {
+        let list = heights(&[100.0; 10]);
+        assert_eq!(
+            list.window(0.0, 250.0, 0.0),
+            Window {
+                start: 0,
+                end: 3,
+                top: 0.0,
+                bottom: 700.0
+            }
+        );
+        assert_eq!(
+            list.window(450.0, 200.0, 100.0),
+            Window {
+                start: 3,
+                end: 8,
+                top: 300.0,
+                bottom: 200.0
+            }
+        );
+        assert_eq!(
+            list.window(800.0, 200.0, 0.0),
+            Window {
+                start: 8,
+                end: 10,
+                top: 800.0,
+                bottom: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn empty_and_scrolled_past_lists() {
+        assert_eq!(
+            Heights::default().window(0.0, 500.0, 100.0),
+            Window {
+                start: 0,
+                end: 0,
+                top: 0.0,
+                bottom: 0.0
+            }
+        );
+        let list = heights(&[100.0, 100.0]);
+        assert_eq!(list.window(5000.0, 100.0, 0.0).start, 2);
+        assert_eq!(list.index_at(150.0), 1);
+        assert_eq!(list.index_at(5000.0), 2);
+    }
+
+    #[test]
+    fn measurements_survive_sync_and_report_growth() {
+        let mut list = heights(&[100.0, 100.0]);
+        assert_eq!(list.measure(0, 160.0), 60.0);
+        assert_eq!(list.measure(0, 160.2), 0.0);
+        assert_eq!(list.measure(7, 10.0), 0.0);
+
+        list.sync(3, |index| [50.0, 50.0, 80.0][index]);
+        assert_eq!(list.heights, vec![160.0, 100.0, 80.0]);
+        list.sync(1, |_| 50.0);
+        assert_eq!(list.heights, vec![160.0]);
+    }
+
+    #[test]
+    fn only_new_and_last_items_are_estimated() {
+        let mut list = heights(&[100.0, 100.0, 100.0]);
+        let asked = std::cell::RefCell::new(Vec::new());
+        let estimate = |index| {
+            asked.borrow_mut().push(index);
+            40.0
+        };
+        list.sync(3, estimate);
+        assert_eq!(*asked.borrow(), vec![2]);
+        list.sync(5, estimate);
+        assert_eq!(*asked.borrow(), vec![2, 3, 4]);
+        assert_eq!(list.heights, vec![100.0, 100.0, 40.0, 40.0, 40.0]);
+    }
+
+    #[test]
+    fn estimates_grow_with_wrapped_lines() {
+        let one = estimate_height("Hello");
+        assert_eq!(estimate_height(""), one);
+        assert_eq!(estimate_height(&"x".repeat(200)), one + 2.0 * 26.0);
+        assert_eq!(estimate_height("a\nb\nc"), one + 2.0 * 26.0);
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use web_sys::window;
+
+const STORAGE_KEY: &str = "workspace";
+
+/// The smallest share of the width either pane can be dragged down to.
+pub const MIN_SHARE: f64 = 0.2;
+
+/// How the split workspace is arranged. Kept in local storage between sessions.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    /// Share of the width taken by the left pane.
+    pub share: f64,
+    /// Puts the chat on the left and the media viewer on the right.
+    pub swapped: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            share: 0.5,
+            swapped: false,
+        }
+    }
+}
+
+impl Layout {
+    pub fn load() -> Self {
+        window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str::<Layout>(&json).ok())
+            .map(|layout| layout.with_share(layout.share))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Ok(json) = serde_json::to_string(self) else {
+            return;
+        };
+        if let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) {
+            if let Err(e) = storage.set_item(STORAGE_KEY, &json) {
+                log::warn!("Failed to save workspace layout: {:?}", e);
+            }
+        }
+    }
+
+    /// The layout with the divider moved to `share`, kept clear of either edge.
+    pub fn with_share(self, share: f64) -> Self {
+        let share = if share.is_finite() {
+            share.clamp(MIN_SHARE, 1.0 - MIN_SHARE)
+        } else {
+            Self::default().share
+        };
+        Self { share, ..self }
+    }
+
+    /// The layout with the divider under a pointer at `x`, for a workspace spanning
+    /// `width` pixels from `left`.
+    pub fn dragged_to(self, x: f64, left: f64, width: f64) -> Self {
+        if width <= 0.0 {
+            return self;
+        }
+        self.with_share((x - left) / width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_divider_stays_clear_of_the_edges() {
+        let layout = Layout::default();
+        assert_eq!(layout.dragged_to(400.0, 100.0, 1000.0).share, 0.3);
+        assert_eq!(layout.dragged_to(0.0, 100.0, 1000.0).share, MIN_SHARE);
+        assert_eq!(
+            layout.dragged_to(5000.0, 100.0, 1000.0).share,
+            1.0 - MIN_SHARE
+        );
+        assert_eq!(layout.dragged_to(400.0, 100.0, 0.0), layout);
+        assert_eq!(layout.with_share(f64::NAN).share, 0.5);
+    }
+
+    #[test]
+    fn saved_layouts_fill_in_missing_fields() {
+        let layout: Layout = serde_json::from_str(r#"{"share":0.7}"#).unwrap();
+        assert_eq!(
+            layout,
+            Layout {
+                share: 0.7,
+                swapped: false
+            }
+        );
+    }
+}